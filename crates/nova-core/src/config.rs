@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -15,6 +16,19 @@ pub struct Config {
     pub quicklinks: Vec<QuicklinkConfig>,
     #[serde(default)]
     pub scripts: ScriptsConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub apps: AppsConfig,
+    #[serde(default)]
+    pub confirmation: ConfirmationConfig,
+    /// Result ids pinned to the empty-query view, in display order
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Keywords of quicklinks, scripts or extension commands offered with
+    /// the whole query as their argument once real results run out
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,8 +94,89 @@ pub struct AppearanceConfig {
 pub struct BehaviorConfig {
     pub autostart: bool,
     pub max_results: u32,
+    /// Layouts to retry weak queries in, for text typed with the wrong
+    /// layout active (e.g. `["us", "ru", "tr"]`)
+    pub keyboard_layouts: Vec<String>,
+}
+
+/// Which search providers run and in what order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Provider names listed first, in this order; others follow by priority
+    pub provider_order: Vec<String>,
+    /// Provider names that are never queried
+    pub disabled_providers: Vec<String>,
+    /// Per-provider score offsets, overriding the built-in weights
+    pub provider_weights: HashMap<String, i64>,
+    /// Result group names listed first, in this order; others follow by best match
+    pub group_order: Vec<String>,
+    /// Maximum number of results shown per group
+    pub group_limits: HashMap<String, usize>,
+    /// Scope prefixes by scope name; an empty list disables a scope's prefixes
+    pub scope_prefixes: HashMap<String, Vec<String>>,
 }
 
+/// Where applications are found besides desktop entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppsConfig {
+    /// Index AppImages and executables found in `local_dirs`
+    pub local_discovery: bool,
+    /// Directories holding AppImages and command line programs
+    pub local_dirs: Vec<String>,
+}
+
+impl AppsConfig {
+    /// The local directories to scan, with `~` expanded; empty when local
+    /// discovery is off
+    pub fn local_dirs(&self) -> Vec<PathBuf> {
+        if !self.local_discovery {
+            return Vec::new();
+        }
+        self.local_dirs
+            .iter()
+            .map(|dir| PathBuf::from(shellexpand::tilde(dir).as_ref()))
+            .collect()
+    }
+}
+
+/// How a command is confirmed before it runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confirmation {
+    /// Run right away
+    #[default]
+    None,
+    /// Ask in the launcher first
+    Confirm,
+    /// Run after a countdown in a notification that can cancel it
+    Countdown,
+}
+
+/// Which commands are confirmed before they run, and how
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationConfig {
+    /// Policy for commands marked destructive, such as Shut Down
+    pub destructive: Confirmation,
+    /// Seconds counted down before the command runs
+    pub countdown_seconds: u32,
+    /// Policies overriding the above, by system command id (e.g.
+    /// `"system:restart"`) or result id (e.g. `"script:backup"`)
+    pub commands: HashMap<String, Confirmation>,
+}
+
+impl ConfirmationConfig {
+    /// The policy for a result id, given whether its command is destructive
+    pub fn policy(&self, id: &str, destructive: bool) -> Confirmation {
+        match self.commands.get(id) {
+            Some(policy) => *policy,
+            None if destructive => self.destructive,
+            None => Confirmation::None,
+        }
+    }
+}
 
 impl Default for ScriptsConfig {
     fn default() -> Self {
@@ -92,6 +187,29 @@ impl Default for ScriptsConfig {
     }
 }
 
+impl Default for AppsConfig {
+    fn default() -> Self {
+        Self {
+            local_discovery: false,
+            local_dirs: vec![
+                "~/Applications".to_string(),
+                "~/.local/bin".to_string(),
+                "~/.cargo/bin".to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            destructive: Confirmation::Confirm,
+            countdown_seconds: 5,
+            commands: HashMap::new(),
+        }
+    }
+}
+
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
@@ -120,6 +238,7 @@ impl Default for BehaviorConfig {
         Self {
             autostart: false,
             max_results: 8,
+            keyboard_layouts: Vec::new(),
         }
     }
 }
@@ -169,6 +288,35 @@ impl Config {
         self.behavior.max_results = self.behavior.max_results.clamp(1, 20);
         self.appearance.window_width = self.appearance.window_width.clamp(400, 1200);
         self.appearance.description_size = self.appearance.description_size.clamp(10, 24);
+        self.confirmation.countdown_seconds = self.confirmation.countdown_seconds.clamp(1, 60);
+    }
+
+    /// Check if a result id is pinned
+    pub fn is_favorite(&self, id: &str) -> bool {
+        self.favorites.iter().any(|f| f == id)
+    }
+
+    /// Pin or unpin a result id. Returns true if it is pinned afterwards.
+    pub fn toggle_favorite(&mut self, id: &str) -> bool {
+        if self.is_favorite(id) {
+            self.favorites.retain(|f| f != id);
+            false
+        } else {
+            self.favorites.push(id.to_string());
+            true
+        }
+    }
+
+    /// Move a pinned id by `offset` positions (negative = earlier).
+    /// Returns its new position, or None if it is not pinned.
+    pub fn move_favorite(&mut self, id: &str, offset: isize) -> Option<usize> {
+        let from = self.favorites.iter().position(|f| f == id)?;
+        let to = from
+            .saturating_add_signed(offset)
+            .min(self.favorites.len() - 1);
+        let item = self.favorites.remove(from);
+        self.favorites.insert(to, item);
+        Some(to)
     }
 
     /// Save config to file
@@ -188,3 +336,61 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_favorite() {
+        let mut config = Config::default();
+        assert!(config.toggle_favorite("app:firefox"));
+        assert!(config.is_favorite("app:firefox"));
+        assert!(!config.toggle_favorite("app:firefox"));
+        assert!(config.favorites.is_empty());
+    }
+
+    #[test]
+    fn test_move_favorite() {
+        let mut config = Config::default();
+        for id in ["a", "b", "c"] {
+            config.toggle_favorite(id);
+        }
+
+        assert_eq!(config.move_favorite("c", -1), Some(1));
+        assert_eq!(config.favorites, vec!["a", "c", "b"]);
+        assert_eq!(config.move_favorite("a", -1), Some(0));
+        assert_eq!(config.move_favorite("a", 5), Some(2));
+        assert_eq!(config.favorites, vec!["c", "b", "a"]);
+        assert_eq!(config.move_favorite("x", 1), None);
+    }
+
+    #[test]
+    fn test_confirmation_policy() {
+        let config: Config = toml::from_str(
+            r#"
+            [confirmation]
+            destructive = "countdown"
+
+            [confirmation.commands]
+            "system:restart" = "confirm"
+            "system:logout" = "none"
+            "script:backup" = "confirm"
+            "#,
+        )
+        .unwrap();
+        let policy = |id, destructive| config.confirmation.policy(id, destructive);
+
+        assert_eq!(policy("system:shutdown", true), Confirmation::Countdown);
+        assert_eq!(policy("system:restart", true), Confirmation::Confirm);
+        assert_eq!(policy("system:logout", true), Confirmation::None);
+        assert_eq!(policy("script:backup", false), Confirmation::Confirm);
+        assert_eq!(policy("system:lock", false), Confirmation::None);
+        assert_eq!(config.confirmation.countdown_seconds, 5);
+        let defaults = ConfirmationConfig::default();
+        assert_eq!(
+            defaults.policy("system:shutdown", true),
+            Confirmation::Confirm
+        );
+    }
+}
@@ -1,12 +1,28 @@
 use std::path::PathBuf;
 
+use crate::config::{Confirmation, ConfirmationConfig};
 use crate::services::{LoadedCommand, ScriptOutputMode};
 
 /// The action to perform when a result is executed
 #[derive(Debug, Clone)]
 pub enum ExecutionAction {
-    /// Launch an application by its exec command
-    LaunchApp { exec: String, name: String },
+    /// Launch an application by its exec command, reading the Path and
+    /// Terminal keys from its desktop file when there is one
+    LaunchApp {
+        /// Desktop file id of the app
+        app_id: String,
+        exec: String,
+        name: String,
+        desktop_file: Option<PathBuf>,
+        /// Run inside a terminal emulator when there is no desktop file
+        /// saying otherwise
+        terminal: bool,
+        /// Desktop action to run instead of the main exec command
+        action: Option<String>,
+    },
+
+    /// Focus an open window by its window manager id
+    FocusWindow { id: String },
 
     /// Open Nova settings
     OpenSettings,
@@ -14,7 +30,7 @@ pub enum ExecutionAction {
     /// Quit the application
     Quit,
 
-    /// Execute a system command (lock, sleep, logout, restart, shutdown)
+    /// Execute a system command (power, session and desktop settings)
     SystemCommand { command: SystemCommand },
 
     /// Run a shell command
@@ -45,16 +61,138 @@ pub enum ExecutionAction {
     /// Open a file or directory
     OpenFile { path: String },
 
+    /// Show a file in its folder
+    RevealFile { path: String },
+
+    /// Let the user pick the application to open a file with
+    OpenWith { path: String },
+
+    /// Run a command in a terminal emulator
+    RunInTerminal { command: String, name: String },
+
     /// No action needed (e.g., quicklink waiting for query input)
     NeedsInput,
 }
 
+impl ExecutionAction {
+    /// How the action is confirmed before it runs. System commands are
+    /// confirmed wherever they are run from, by their id (`system:shutdown`).
+    pub fn confirmation(&self, config: &ConfirmationConfig) -> Confirmation {
+        match self {
+            ExecutionAction::SystemCommand { command } => {
+                config.policy(command.id(), command.is_destructive())
+            }
+            _ => Confirmation::None,
+        }
+    }
+}
+
+/// A secondary action offered for a result in the action panel
+#[derive(Debug, Clone)]
+pub struct NamedAction {
+    /// Label shown in the action panel (e.g., "Copy Path")
+    pub title: String,
+    pub action: ExecutionAction,
+}
+
+impl NamedAction {
+    pub fn new(title: impl Into<String>, action: ExecutionAction) -> Self {
+        Self {
+            title: title.into(),
+            action,
+        }
+    }
+}
+
 /// System commands that can be executed (pure data, no OS-specific logic)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SystemCommand {
     Lock,
+    /// Lock the screen, then suspend
+    LockAndSuspend,
     Sleep,
+    /// Suspend with a hibernation image to resume from if power runs out
+    HybridSleep,
+    Hibernate,
     Logout,
+    /// Show the login screen to sign in as another user, keeping the
+    /// current session
+    SwitchUser,
     Restart,
     Shutdown,
+    /// Turn the displays off until the next input
+    ScreenOff,
+    ToggleDoNotDisturb,
+    ToggleDarkMode,
+    VolumeUp,
+    VolumeDown,
+    ToggleMute,
+    BrightnessUp,
+    BrightnessDown,
+    EmptyTrash,
+}
+
+impl SystemCommand {
+    /// Every command, in the order they are listed
+    pub const ALL: [SystemCommand; 18] = [
+        SystemCommand::Lock,
+        SystemCommand::LockAndSuspend,
+        SystemCommand::Sleep,
+        SystemCommand::HybridSleep,
+        SystemCommand::Hibernate,
+        SystemCommand::Logout,
+        SystemCommand::SwitchUser,
+        SystemCommand::Restart,
+        SystemCommand::Shutdown,
+        SystemCommand::ScreenOff,
+        SystemCommand::ToggleDoNotDisturb,
+        SystemCommand::ToggleDarkMode,
+        SystemCommand::VolumeUp,
+        SystemCommand::VolumeDown,
+        SystemCommand::ToggleMute,
+        SystemCommand::BrightnessUp,
+        SystemCommand::BrightnessDown,
+        SystemCommand::EmptyTrash,
+    ];
+
+    /// Result id of the command
+    pub fn id(self) -> &'static str {
+        match self {
+            SystemCommand::Lock => "system:lock",
+            SystemCommand::LockAndSuspend => "system:lock-suspend",
+            SystemCommand::Sleep => "system:sleep",
+            SystemCommand::HybridSleep => "system:hybrid-sleep",
+            SystemCommand::Hibernate => "system:hibernate",
+            SystemCommand::Logout => "system:logout",
+            SystemCommand::SwitchUser => "system:switch-user",
+            SystemCommand::Restart => "system:restart",
+            SystemCommand::Shutdown => "system:shutdown",
+            SystemCommand::ScreenOff => "system:screen-off",
+            SystemCommand::ToggleDoNotDisturb => "system:do-not-disturb",
+            SystemCommand::ToggleDarkMode => "system:dark-mode",
+            SystemCommand::VolumeUp => "system:volume-up",
+            SystemCommand::VolumeDown => "system:volume-down",
+            SystemCommand::ToggleMute => "system:mute",
+            SystemCommand::BrightnessUp => "system:brightness-up",
+            SystemCommand::BrightnessDown => "system:brightness-down",
+            SystemCommand::EmptyTrash => "system:empty-trash",
+        }
+    }
+
+    /// Find a command by its result id
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|command| command.id() == id)
+    }
+
+    /// Whether running the command by accident loses work, so it is
+    /// confirmed before it runs
+    pub fn is_destructive(self) -> bool {
+        matches!(
+            self,
+            SystemCommand::Logout
+                | SystemCommand::Restart
+                | SystemCommand::Shutdown
+                | SystemCommand::EmptyTrash
+        )
+    }
 }
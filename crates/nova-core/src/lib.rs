@@ -1,12 +1,17 @@
 pub mod config;
 pub mod error;
 pub mod executor;
+pub mod matcher;
 pub mod search;
 pub mod services;
 pub mod theme;
 
-pub use config::Config;
+pub use config::{Config, Confirmation};
 pub use error::{NovaError, NovaResult};
-pub use executor::{ExecutionAction, SystemCommand};
-pub use search::{CommandModeState, PlatformAppEntry, SearchEngine, SearchResult};
+pub use executor::{ExecutionAction, NamedAction, SystemCommand};
+pub use search::{
+    merge_results, AppAction, CommandModeState, OpenWindow, PlatformAppEntry, ProviderRegistry, ResultGroup,
+    ResultIcon, ResultId, ResultKind, ResultSection, ScoredResult, SearchContext, SearchEngine,
+    SearchPhase, SearchProvider, SearchResult,
+};
 pub use theme::{ThemePalette, available_themes, get_theme_colors, get_theme_palette, parse_hex_color};
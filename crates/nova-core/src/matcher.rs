@@ -0,0 +1,187 @@
+//! Text matching shared by every search provider
+//!
+//! Both sides are folded before matching: NFKD-decomposed, stripped of
+//! combining marks and lowercased, so "resume" finds "Résumé.pdf". On top of
+//! the fuzzy match, hits on word starts and initials ("vsc" for "Visual
+//! Studio Code") score extra. Words split on spaces, punctuation, camelCase
+//! and letter/digit changes.
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use once_cell::sync::Lazy;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+
+/// Bonus when the query is the start of a word in the text
+pub const WORD_START_BONUS: i64 = 50;
+
+/// Bonus when the query spells the initials of the text's words
+pub const ACRONYM_BONUS: i64 = 80;
+
+/// A query prepared for matching against many texts
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    folded: String,
+}
+
+impl Pattern {
+    pub fn new(query: &str) -> Self {
+        Self {
+            folded: fold(query.trim()),
+        }
+    }
+
+    /// The folded query
+    pub fn as_str(&self) -> &str {
+        &self.folded
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folded.is_empty()
+    }
+
+    /// Match quality of `text`, or None if it doesn't match.
+    /// An empty pattern matches everything with score 0.
+    pub fn score(&self, text: &str) -> Option<i64> {
+        if self.is_empty() {
+            return Some(0);
+        }
+
+        let folded = fold_keep_case(text);
+        let words = split_words(&folded);
+        let lower = folded.to_lowercase();
+
+        let fuzzy = MATCHER.fuzzy_match(&lower, &self.folded);
+        let word_start = words.iter().any(|w| w.starts_with(&self.folded));
+        let acronym = self.is_acronym_of(&words);
+
+        if fuzzy.is_none() && !acronym {
+            return None;
+        }
+
+        let mut score = fuzzy.unwrap_or(0);
+        if word_start {
+            score += WORD_START_BONUS;
+        }
+        if acronym {
+            score += ACRONYM_BONUS;
+        }
+        Some(score)
+    }
+
+    /// Check if the folded text starts with the pattern
+    pub fn is_prefix_of(&self, text: &str) -> bool {
+        fold(text).starts_with(&self.folded)
+    }
+
+    /// Check if the folded text contains the pattern as a substring
+    pub fn is_contained_in(&self, text: &str) -> bool {
+        fold(text).contains(&self.folded)
+    }
+
+    fn is_acronym_of(&self, words: &[String]) -> bool {
+        if self.folded.chars().count() < 2 || words.len() < 2 {
+            return false;
+        }
+        let initials: String = words.iter().filter_map(|w| w.chars().next()).collect();
+        initials.starts_with(&self.folded)
+    }
+}
+
+/// Score `text` against `query`; see [`Pattern::score`]
+pub fn score(text: &str, query: &str) -> Option<i64> {
+    Pattern::new(query).score(text)
+}
+
+/// Fold text for comparison: decompose, drop accents and lowercase
+pub fn fold(text: &str) -> String {
+    fold_keep_case(text).to_lowercase()
+}
+
+fn fold_keep_case(text: &str) -> String {
+    text.nfkd().filter(|c| !is_combining_mark(*c)).collect()
+}
+
+/// Split text into lowercased words at separators, camelCase humps and
+/// letter/digit changes
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev = None;
+            continue;
+        }
+
+        let boundary = prev.is_some_and(|p| {
+            (p.is_lowercase() && c.is_uppercase()) || (p.is_alphabetic() != c.is_alphabetic())
+        });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+
+        current.extend(c.to_lowercase());
+        prev = Some(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_strips_accents() {
+        assert_eq!(fold("Résumé.pdf"), "resume.pdf");
+        assert_eq!(fold("Ｆｕｌｌ"), "full");
+    }
+
+    #[test]
+    fn test_diacritic_insensitive() {
+        assert!(score("Résumé.pdf", "resume").is_some());
+        assert!(score("resume.pdf", "résumé").is_some());
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words("VSCode-insiders mp3Gain"),
+            vec!["vscode", "insiders", "mp", "3", "gain"]
+        );
+        assert_eq!(split_words("camelCaseName"), vec!["camel", "case", "name"]);
+    }
+
+    #[test]
+    fn test_acronym() {
+        let pattern = Pattern::new("vsc");
+        let acronym = pattern.score("Visual Studio Code").unwrap();
+        let scattered = pattern.score("Voice Scanner").unwrap();
+        assert!(acronym > scattered);
+        assert!(score("Visual Studio Code", "vs").is_some());
+    }
+
+    #[test]
+    fn test_word_start_beats_infix() {
+        let pattern = Pattern::new("case");
+        let word = pattern.score("camelCaseName").unwrap();
+        let infix = pattern.score("Showcases").unwrap();
+        assert!(word > infix);
+    }
+
+    #[test]
+    fn test_empty_and_missing() {
+        assert_eq!(score("Firefox", ""), Some(0));
+        assert_eq!(score("Firefox", "xyz"), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Grouping of ranked results into titled sections
+//!
+//! Sections are ordered by the config's `group_order` first and by their best
+//! result otherwise, so the unified ranking still decides which section leads.
+
+use std::collections::HashMap;
+
+use crate::config::SearchConfig;
+use crate::search::id::ResultKind;
+use crate::search::scoring::{ScoredResult, FALLBACK_SCORE, PINNED_SCORE};
+
+/// Section a result is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResultGroup {
+    /// Pinned favourites; always first and never capped
+    Pinned,
+    Calculator,
+    Applications,
+    Windows,
+    Commands,
+    Quicklinks,
+    Extensions,
+    Clipboard,
+    Files,
+    Emoji,
+    /// Fallback commands taking the whole query
+    Fallbacks,
+}
+
+impl ResultGroup {
+    const ALL: [ResultGroup; 11] = [
+        ResultGroup::Pinned,
+        ResultGroup::Calculator,
+        ResultGroup::Applications,
+        ResultGroup::Windows,
+        ResultGroup::Commands,
+        ResultGroup::Quicklinks,
+        ResultGroup::Extensions,
+        ResultGroup::Clipboard,
+        ResultGroup::Files,
+        ResultGroup::Emoji,
+        ResultGroup::Fallbacks,
+    ];
+
+    /// Group of a result kind
+    pub fn of(kind: ResultKind) -> Self {
+        match kind {
+            ResultKind::App => ResultGroup::Applications,
+            ResultKind::Window => ResultGroup::Windows,
+            ResultKind::Command | ResultKind::Alias | ResultKind::Script => ResultGroup::Commands,
+            ResultKind::Quicklink => ResultGroup::Quicklinks,
+            ResultKind::Extension => ResultGroup::Extensions,
+            ResultKind::Calculation | ResultKind::UnitConversion => ResultGroup::Calculator,
+            ResultKind::Clipboard => ResultGroup::Clipboard,
+            ResultKind::File => ResultGroup::Files,
+            ResultKind::Emoji => ResultGroup::Emoji,
+            ResultKind::Fallback => ResultGroup::Fallbacks,
+        }
+    }
+
+    /// Name used in config (e.g., "applications")
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResultGroup::Pinned => "pinned",
+            ResultGroup::Calculator => "calculator",
+            ResultGroup::Applications => "applications",
+            ResultGroup::Windows => "windows",
+            ResultGroup::Commands => "commands",
+            ResultGroup::Quicklinks => "quicklinks",
+            ResultGroup::Extensions => "extensions",
+            ResultGroup::Clipboard => "clipboard",
+            ResultGroup::Files => "files",
+            ResultGroup::Emoji => "emoji",
+            ResultGroup::Fallbacks => "fallbacks",
+        }
+    }
+
+    /// Section header shown above the group's results
+    pub fn title(&self) -> &'static str {
+        match self {
+            ResultGroup::Pinned => "Pinned",
+            ResultGroup::Calculator => "Calculator",
+            ResultGroup::Applications => "Applications",
+            ResultGroup::Windows => "Windows",
+            ResultGroup::Commands => "Commands",
+            ResultGroup::Quicklinks => "Quicklinks",
+            ResultGroup::Extensions => "Extensions",
+            ResultGroup::Clipboard => "Clipboard",
+            ResultGroup::Files => "Files",
+            ResultGroup::Emoji => "Emoji",
+            ResultGroup::Fallbacks => "Fallback Commands",
+        }
+    }
+
+    /// Look up a group by its config name
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|group| group.name() == name)
+    }
+
+    /// Cap used when the config doesn't set one
+    fn default_limit(&self) -> Option<usize> {
+        match self {
+            ResultGroup::Clipboard | ResultGroup::Files => Some(5),
+            ResultGroup::Emoji => Some(8),
+            _ => None,
+        }
+    }
+}
+
+/// A titled run of results
+#[derive(Debug, Clone)]
+pub struct ResultSection {
+    pub group: ResultGroup,
+    pub results: Vec<ScoredResult>,
+}
+
+/// Group order and per-group caps from config
+#[derive(Debug, Clone, Default)]
+pub struct SectionLayout {
+    order: Vec<ResultGroup>,
+    limits: HashMap<ResultGroup, usize>,
+}
+
+impl SectionLayout {
+    pub fn from_config(config: &SearchConfig) -> Self {
+        let order = config
+            .group_order
+            .iter()
+            .filter_map(|name| {
+                let group = ResultGroup::parse(name);
+                if group.is_none() {
+                    eprintln!("[Nova] Unknown result group: {}", name);
+                }
+                group
+            })
+            .collect();
+
+        let limits = config
+            .group_limits
+            .iter()
+            .filter_map(|(name, limit)| Some((ResultGroup::parse(name)?, *limit)))
+            .collect();
+
+        Self { order, limits }
+    }
+
+    /// Maximum number of results shown for a group
+    pub fn limit(&self, group: ResultGroup) -> Option<usize> {
+        if group == ResultGroup::Pinned {
+            return None;
+        }
+        self.limits
+            .get(&group)
+            .copied()
+            .or_else(|| group.default_limit())
+    }
+
+    /// Split ranked results into sections.
+    ///
+    /// Results keep their relative order within a section. Pinned results
+    /// lead, then groups named in the config, then the rest by best score.
+    pub fn group(&self, results: Vec<ScoredResult>) -> Vec<ResultSection> {
+        let mut sections: Vec<ResultSection> = Vec::new();
+
+        for scored in results {
+            let group = if scored.score == PINNED_SCORE {
+                ResultGroup::Pinned
+            } else if scored.score == FALLBACK_SCORE {
+                ResultGroup::Fallbacks
+            } else {
+                ResultGroup::of(scored.result.id().kind)
+            };
+
+            match sections.iter_mut().find(|s| s.group == group) {
+                Some(section) => section.results.push(scored),
+                None => sections.push(ResultSection {
+                    group,
+                    results: vec![scored],
+                }),
+            }
+        }
+
+        for section in &mut sections {
+            if let Some(limit) = self.limit(section.group) {
+                section.results.truncate(limit);
+            }
+        }
+
+        // Stable sort: unlisted groups keep their best-score order
+        let rank = |group: ResultGroup| {
+            if group == ResultGroup::Pinned {
+                return 0;
+            }
+            self.order
+                .iter()
+                .position(|g| *g == group)
+                .map_or(usize::MAX, |pos| pos + 1)
+        };
+        sections.sort_by_key(|s| rank(s.group));
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchResult;
+
+    fn app(name: &str, score: i64) -> ScoredResult {
+        ScoredResult::new(
+            SearchResult::App {
+                id: name.to_string(),
+                name: name.to_string(),
+                exec: name.to_string(),
+                icon: None,
+                description: None,
+                desktop_file: None,
+                terminal: false,
+                actions: Vec::new(),
+                windows: Vec::new(),
+            },
+            score,
+        )
+    }
+
+    fn file(name: &str, score: i64) -> ScoredResult {
+        ScoredResult::new(
+            SearchResult::FileResult {
+                name: name.to_string(),
+                path: format!("/tmp/{}", name),
+                is_dir: false,
+            },
+            score,
+        )
+    }
+
+    fn groups(sections: &[ResultSection]) -> Vec<ResultGroup> {
+        sections.iter().map(|s| s.group).collect()
+    }
+
+    #[test]
+    fn test_groups_follow_best_score() {
+        let sections =
+            SectionLayout::default().group(vec![file("a", 90), app("firefox", 80), file("b", 70)]);
+
+        assert_eq!(
+            groups(&sections),
+            vec![ResultGroup::Files, ResultGroup::Applications]
+        );
+        assert_eq!(sections[0].results.len(), 2);
+    }
+
+    #[test]
+    fn test_config_order_and_limits() {
+        let layout = SectionLayout::from_config(&SearchConfig {
+            group_order: vec!["applications".to_string()],
+            group_limits: HashMap::from([("files".to_string(), 1)]),
+            ..Default::default()
+        });
+        let sections = layout.group(vec![
+            file("a", 90),
+            file("b", 70),
+            app("firefox", 10),
+            ScoredResult::new(app("pinned", 0).result, PINNED_SCORE),
+        ]);
+
+        assert_eq!(
+            groups(&sections),
+            vec![
+                ResultGroup::Pinned,
+                ResultGroup::Applications,
+                ResultGroup::Files
+            ]
+        );
+        assert_eq!(sections[2].results.len(), 1);
+    }
+
+    #[test]
+    fn test_fallbacks_come_last() {
+        let sections = SectionLayout::default().group(vec![
+            app("firefox", 10),
+            ScoredResult::new(app("search", 0).result, FALLBACK_SCORE),
+        ]);
+
+        assert_eq!(
+            groups(&sections),
+            vec![ResultGroup::Applications, ResultGroup::Fallbacks]
+        );
+    }
+
+    #[test]
+    fn test_default_limit() {
+        let results = (0..10).map(|i| file(&i.to_string(), 10 - i)).collect();
+        let sections = SectionLayout::default().group(results);
+        assert_eq!(sections[0].results.len(), 5);
+    }
+}
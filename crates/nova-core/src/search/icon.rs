@@ -0,0 +1,171 @@
+//! Icons shown in front of search results
+//!
+//! Results name freedesktop icons (or image paths) with generic fallbacks
+//! after the specific icon, so every row has something to show even when
+//! an app's own icon is missing from the theme.
+
+use std::path::Path;
+
+use crate::search::SearchResult;
+
+/// What to draw in front of a result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultIcon {
+    /// Icon names or image paths, most specific first
+    Themed(Vec<String>),
+    /// Text drawn in place of an icon, such as an emoji
+    Glyph(String),
+}
+
+impl ResultIcon {
+    fn themed(icon: Option<&str>, fallback: &str) -> Self {
+        let mut names: Vec<String> = icon
+            .filter(|i| !i.is_empty())
+            .map(|i| i.to_string())
+            .into_iter()
+            .collect();
+        names.push(fallback.to_string());
+        ResultIcon::Themed(names)
+    }
+}
+
+impl SearchResult {
+    /// Icon of this result, with a fallback for its kind
+    pub fn icon(&self) -> ResultIcon {
+        match self {
+            SearchResult::App { icon, .. } | SearchResult::AppAction { icon, .. } => {
+                ResultIcon::themed(icon.as_deref(), "application-x-executable")
+            }
+            SearchResult::Window { icon, .. } => {
+                ResultIcon::themed(icon.as_deref(), "application-x-executable")
+            }
+            SearchResult::Command { id, .. } => ResultIcon::themed(None, command_icon(id)),
+            SearchResult::Alias { icon, .. } => {
+                ResultIcon::themed(icon.as_deref(), "application-x-executable")
+            }
+            SearchResult::Quicklink { .. } | SearchResult::QuicklinkWithQuery { .. } => {
+                ResultIcon::themed(None, "web-browser")
+            }
+            SearchResult::Script { icon, .. } | SearchResult::ScriptWithArgument { icon, .. } => {
+                ResultIcon::themed(icon.as_deref(), "utilities-terminal")
+            }
+            SearchResult::ExtensionCommand { command }
+            | SearchResult::ExtensionCommandWithArg { command, .. } => ResultIcon::themed(
+                command.icon_path.as_deref().and_then(Path::to_str),
+                "application-x-addon",
+            ),
+            SearchResult::Calculation { .. } | SearchResult::UnitConversion { .. } => {
+                ResultIcon::Glyph("=".to_string())
+            }
+            SearchResult::ClipboardItem { .. } => ResultIcon::themed(None, "edit-paste"),
+            SearchResult::FileResult { name, is_dir, .. } => {
+                let icon = if *is_dir { "folder" } else { file_icon(name) };
+                ResultIcon::themed(None, icon)
+            }
+            SearchResult::EmojiResult { emoji, .. } => ResultIcon::Glyph(emoji.clone()),
+            SearchResult::Fallback { result } => result.icon(),
+        }
+    }
+}
+
+/// Icon of a built-in command
+fn command_icon(id: &str) -> &'static str {
+    match id {
+        "nova:settings" => "preferences-system",
+        "nova:quit" => "application-exit",
+        "system:lock" | "system:lock-suspend" => "system-lock-screen",
+        "system:sleep" | "system:hybrid-sleep" => "system-suspend",
+        "system:hibernate" => "system-suspend-hibernate",
+        "system:logout" => "system-log-out",
+        "system:switch-user" => "system-switch-user",
+        "system:restart" => "system-reboot",
+        "system:shutdown" => "system-shutdown",
+        "system:screen-off" => "video-display",
+        "system:do-not-disturb" => "notifications-disabled",
+        "system:dark-mode" => "weather-clear-night",
+        "system:volume-up" => "audio-volume-high",
+        "system:volume-down" => "audio-volume-low",
+        "system:mute" => "audio-volume-muted",
+        "system:brightness-up" | "system:brightness-down" => "display-brightness",
+        "system:empty-trash" => "user-trash",
+        _ => "system-run",
+    }
+}
+
+/// Generic icon for a file, from its extension
+fn file_icon(name: &str) -> &'static str {
+    let ext = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "tiff" | "ico" => {
+            "image-x-generic"
+        }
+        "mp3" | "flac" | "ogg" | "opus" | "wav" | "m4a" => "audio-x-generic",
+        "mp4" | "mkv" | "webm" | "avi" | "mov" => "video-x-generic",
+        "pdf" => "application-pdf",
+        "zip" | "tar" | "gz" | "xz" | "bz2" | "zst" | "7z" | "rar" => "package-x-generic",
+        "html" | "htm" => "text-html",
+        "sh" | "bash" | "zsh" | "fish" | "py" | "rb" | "pl" => "text-x-script",
+        "doc" | "docx" | "odt" | "rtf" => "x-office-document",
+        "xls" | "xlsx" | "ods" | "csv" => "x-office-spreadsheet",
+        "ppt" | "pptx" | "odp" => "x-office-presentation",
+        _ => "text-x-generic",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, is_dir: bool) -> SearchResult {
+        SearchResult::FileResult {
+            name: name.to_string(),
+            path: format!("/tmp/{}", name),
+            is_dir,
+        }
+    }
+
+    #[test]
+    fn test_fallbacks_follow_specific_icon() {
+        let app = SearchResult::App {
+            id: "firefox".to_string(),
+            name: "Firefox".to_string(),
+            exec: "firefox".to_string(),
+            icon: Some("firefox".to_string()),
+            description: None,
+            desktop_file: None,
+            terminal: false,
+            actions: Vec::new(),
+            windows: Vec::new(),
+        };
+        assert_eq!(
+            app.icon(),
+            ResultIcon::Themed(vec![
+                "firefox".to_string(),
+                "application-x-executable".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_file_and_glyph_icons() {
+        let icon = |result: &SearchResult| match result.icon() {
+            ResultIcon::Themed(names) => names.join(","),
+            ResultIcon::Glyph(glyph) => glyph,
+        };
+        assert_eq!(icon(&file("Photos", true)), "folder");
+        assert_eq!(icon(&file("report.PDF", false)), "application-pdf");
+        assert_eq!(icon(&file("notes", false)), "text-x-generic");
+
+        let emoji = SearchResult::EmojiResult {
+            emoji: "\u{1F600}".to_string(),
+            name: "grinning".to_string(),
+            aliases: String::new(),
+        };
+        assert_eq!(icon(&emoji), "\u{1F600}");
+    }
+}
@@ -0,0 +1,177 @@
+//! Stable, namespaced identifiers for search results
+//!
+//! Ids look like `app:firefox`, `ql:yt`, `ext:github/prs` or
+//! `file:/home/x/a.txt` and survive restarts, so they can be stored in config,
+//! sent over IPC and used to key persisted state.
+
+use std::fmt;
+
+/// Kind of result an id refers to, given by the id's namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResultKind {
+    App,
+    /// An open window; ids only last as long as the window
+    Window,
+    /// Built-in Nova and system commands; their namespace is part of the id
+    Command,
+    Alias,
+    Quicklink,
+    Script,
+    Extension,
+    Calculation,
+    Clipboard,
+    File,
+    Emoji,
+    UnitConversion,
+    /// A fallback command; the key is the id of the command it runs
+    Fallback,
+}
+
+impl ResultKind {
+    const ALL: [ResultKind; 13] = [
+        ResultKind::App,
+        ResultKind::Window,
+        ResultKind::Command,
+        ResultKind::Alias,
+        ResultKind::Quicklink,
+        ResultKind::Script,
+        ResultKind::Extension,
+        ResultKind::Calculation,
+        ResultKind::Clipboard,
+        ResultKind::File,
+        ResultKind::Emoji,
+        ResultKind::UnitConversion,
+        ResultKind::Fallback,
+    ];
+
+    /// Namespace prefix used in ids (without the trailing `:`)
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            ResultKind::App => "app",
+            ResultKind::Window => "window",
+            ResultKind::Command => "cmd",
+            ResultKind::Alias => "alias",
+            ResultKind::Quicklink => "ql",
+            ResultKind::Script => "script",
+            ResultKind::Extension => "ext",
+            ResultKind::Calculation => "calc",
+            ResultKind::Clipboard => "clip",
+            ResultKind::File => "file",
+            ResultKind::Emoji => "emoji",
+            ResultKind::UnitConversion => "unit",
+            ResultKind::Fallback => "fallback",
+        }
+    }
+
+    /// Name of the built-in provider that produces this kind of result
+    pub fn provider(&self) -> &'static str {
+        match self {
+            ResultKind::App => "apps",
+            ResultKind::Window => "windows",
+            ResultKind::Command => "system",
+            ResultKind::Alias => "aliases",
+            ResultKind::Quicklink => "quicklinks",
+            ResultKind::Script => "scripts",
+            ResultKind::Extension => "extensions",
+            ResultKind::Calculation => "calculator",
+            ResultKind::Clipboard => "clipboard",
+            ResultKind::File => "files",
+            ResultKind::Emoji => "emoji",
+            ResultKind::UnitConversion => "units",
+            ResultKind::Fallback => "fallbacks",
+        }
+    }
+}
+
+/// A parsed result id
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultId {
+    pub kind: ResultKind,
+    /// Provider-specific key, everything after the namespace
+    pub key: String,
+}
+
+impl ResultId {
+    pub fn new(kind: ResultKind, key: impl Into<String>) -> Self {
+        Self {
+            kind,
+            key: key.into(),
+        }
+    }
+
+    /// Parse an id such as `app:firefox`. Returns None for unknown namespaces
+    /// or an empty key.
+    pub fn parse(id: &str) -> Option<Self> {
+        let (namespace, key) = id.split_once(':')?;
+        if key.is_empty() {
+            return None;
+        }
+        let kind = ResultKind::ALL
+            .into_iter()
+            .find(|kind| kind.namespace() == namespace)?;
+        Some(Self::new(kind, key))
+    }
+
+    /// Name of the provider that produced the result
+    pub fn provider(&self) -> &'static str {
+        self.kind.provider()
+    }
+}
+
+impl fmt::Display for ResultId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind.namespace(), self.key)
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, used to key results by content
+pub(crate) fn content_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for id in [
+            "app:firefox",
+            "ql:yt",
+            "ext:github/prs",
+            "file:/home/x/a.txt",
+            "fallback:ql:g",
+        ] {
+            assert_eq!(ResultId::parse(id).unwrap().to_string(), id);
+        }
+    }
+
+    #[test]
+    fn test_parse_provider() {
+        assert_eq!(ResultId::parse("ql:yt").unwrap().provider(), "quicklinks");
+        assert_eq!(
+            ResultId::parse("cmd:system:lock").unwrap().key,
+            "system:lock"
+        );
+        assert_eq!(ResultId::parse("file:/tmp/a:b").unwrap().key, "/tmp/a:b");
+        assert_eq!(ResultId::parse("fallback:ql:g").unwrap().key, "ql:g");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(ResultId::parse("firefox"), None);
+        assert_eq!(ResultId::parse("app:"), None);
+        assert_eq!(ResultId::parse("nope:x"), None);
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_ne!(content_hash("a"), content_hash("b"));
+    }
+}
@@ -0,0 +1,1484 @@
+mod group;
+mod icon;
+mod id;
+mod provider;
+pub mod providers;
+mod query;
+mod scoring;
+
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::config::{Config, Confirmation, ConfirmationConfig};
+use crate::executor::{ExecutionAction, NamedAction, SystemCommand};
+use crate::services::*;
+
+pub use group::{ResultGroup, ResultSection, SectionLayout};
+pub use icon::ResultIcon;
+pub use id::{ResultId, ResultKind};
+pub use provider::{ProviderRegistry, SearchContext, SearchPhase, SearchProvider};
+use providers::*;
+pub use query::{ParsedQuery, QuerySyntax, Scope};
+pub use scoring::{
+    fuzzy_score, match_score, merge_results, ScoredResult, EXACT_KEYWORD_BONUS, FALLBACK_SCORE,
+    PINNED_SCORE, PREFIX_BONUS,
+};
+
+/// Represents the current command mode state
+#[derive(Debug, Clone, Default)]
+pub struct CommandModeState {
+    /// The active extension (if in command mode)
+    pub active_extension: Option<Extension>,
+}
+
+impl CommandModeState {
+    pub fn enter_mode(&mut self, extension: Extension) {
+        self.active_extension = Some(extension);
+    }
+
+    pub fn exit_mode(&mut self) {
+        self.active_extension = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active_extension.is_some()
+    }
+}
+
+/// Search results that appear in the launcher
+#[derive(Debug, Clone)]
+pub enum SearchResult {
+    App {
+        id: String,
+        name: String,
+        exec: String,
+        icon: Option<String>,
+        description: Option<String>,
+        desktop_file: Option<PathBuf>,
+        /// Runs inside a terminal emulator
+        terminal: bool,
+        /// Desktop actions, offered as secondary actions
+        actions: Vec<AppAction>,
+        /// Open windows of the app, offered to switch to
+        windows: Vec<OpenWindow>,
+    },
+    /// A desktop action of an app, listed as "App: Action"
+    AppAction {
+        app_id: String,
+        name: String,
+        action: AppAction,
+        icon: Option<String>,
+        desktop_file: Option<PathBuf>,
+        /// Runs inside a terminal emulator, as its app does
+        terminal: bool,
+    },
+    /// An open window, switched to when executed
+    Window {
+        id: String,
+        title: String,
+        app_id: String,
+        workspace: Option<String>,
+        /// App name and workspace, shown below the title
+        description: String,
+        icon: Option<String>,
+    },
+    Command {
+        id: String,
+        name: String,
+        description: String,
+    },
+    Alias {
+        keyword: String,
+        name: String,
+        target: String,
+        icon: Option<String>,
+    },
+    Quicklink {
+        keyword: String,
+        name: String,
+        url: String,
+        has_query: bool,
+    },
+    QuicklinkWithQuery {
+        keyword: String,
+        name: String,
+        url: String,
+        query: String,
+        resolved_url: String,
+    },
+    Script {
+        id: String,
+        name: String,
+        description: String,
+        icon: Option<String>,
+        path: PathBuf,
+        has_argument: bool,
+        output_mode: ScriptOutputMode,
+        destructive: bool,
+    },
+    ScriptWithArgument {
+        id: String,
+        name: String,
+        description: String,
+        icon: Option<String>,
+        path: PathBuf,
+        argument: String,
+        output_mode: ScriptOutputMode,
+        destructive: bool,
+    },
+    ExtensionCommand {
+        command: LoadedCommand,
+    },
+    ExtensionCommandWithArg {
+        command: LoadedCommand,
+        argument: String,
+    },
+    Calculation {
+        expression: String,
+        result: String,
+    },
+    ClipboardItem {
+        index: usize,
+        content: String,
+        preview: String,
+        time_ago: String,
+    },
+    FileResult {
+        name: String,
+        path: String,
+        is_dir: bool,
+    },
+    EmojiResult {
+        emoji: String,
+        name: String,
+        aliases: String,
+    },
+    UnitConversion {
+        display: String,
+        result: String,
+    },
+    /// A configured fallback command given the whole query, kept apart from
+    /// the same command found by its keyword
+    Fallback {
+        result: Box<SearchResult>,
+    },
+}
+
+impl SearchResult {
+    pub fn name(&self) -> &str {
+        match self {
+            SearchResult::App { name, .. } => name,
+            SearchResult::AppAction { name, .. } => name,
+            SearchResult::Window { title, .. } => title,
+            SearchResult::Command { name, .. } => name,
+            SearchResult::Alias { name, .. } => name,
+            SearchResult::Quicklink { name, .. } => name,
+            SearchResult::QuicklinkWithQuery { name, .. } => name,
+            SearchResult::Script { name, .. } => name,
+            SearchResult::ScriptWithArgument { name, .. } => name,
+            SearchResult::ExtensionCommand { command } => &command.name,
+            SearchResult::ExtensionCommandWithArg { command, .. } => &command.name,
+            SearchResult::Calculation { result, .. } => result,
+            SearchResult::ClipboardItem { preview, .. } => preview,
+            SearchResult::FileResult { name, .. } => name,
+            SearchResult::EmojiResult { name, .. } => name,
+            SearchResult::UnitConversion { result, .. } => result,
+            SearchResult::Fallback { result } => result.name(),
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            SearchResult::App { description, .. } => description.as_deref(),
+            SearchResult::AppAction { .. } => None,
+            SearchResult::Window { description, .. } => Some(description),
+            SearchResult::Command { description, .. } => Some(description),
+            SearchResult::Alias { target, .. } => Some(target),
+            SearchResult::Quicklink { url, .. } => Some(url),
+            SearchResult::QuicklinkWithQuery { resolved_url, .. } => Some(resolved_url),
+            SearchResult::Script { description, .. } => {
+                if description.is_empty() { None } else { Some(description) }
+            }
+            SearchResult::ScriptWithArgument { description, .. } => {
+                if description.is_empty() { None } else { Some(description) }
+            }
+            SearchResult::ExtensionCommand { command } => {
+                if command.description.is_empty() { None } else { Some(&command.description) }
+            }
+            SearchResult::ExtensionCommandWithArg { command, .. } => {
+                if command.description.is_empty() { None } else { Some(&command.description) }
+            }
+            SearchResult::Calculation { expression, .. } => Some(expression),
+            SearchResult::ClipboardItem { time_ago, .. } => Some(time_ago),
+            SearchResult::FileResult { path, .. } => Some(path),
+            SearchResult::EmojiResult { aliases, .. } => Some(aliases),
+            SearchResult::UnitConversion { display, .. } => Some(display),
+            SearchResult::Fallback { result } => result.description(),
+        }
+    }
+
+    /// Stable, namespaced identity of this result (e.g. `app:firefox`, `ql:yt`).
+    ///
+    /// Variants carrying a query or argument share the id of the plain result,
+    /// so `yt cats` and `yt` both map to `ql:yt`. Fallbacks prefix the id of
+    /// the command they run (`fallback:ql:yt`).
+    pub fn id(&self) -> ResultId {
+        match self {
+            SearchResult::App { id, .. } => ResultId::new(ResultKind::App, id.as_str()),
+            SearchResult::AppAction { app_id, action, .. } => {
+                ResultId::new(ResultKind::App, format!("{}/{}", app_id, action.id))
+            }
+            SearchResult::Window { id, .. } => ResultId::new(ResultKind::Window, id.as_str()),
+            SearchResult::Command { id, .. } => ResultId::new(ResultKind::Command, id.as_str()),
+            SearchResult::Alias { keyword, .. } => {
+                ResultId::new(ResultKind::Alias, keyword.as_str())
+            }
+            SearchResult::Quicklink { keyword, .. }
+            | SearchResult::QuicklinkWithQuery { keyword, .. } => {
+                ResultId::new(ResultKind::Quicklink, keyword.as_str())
+            }
+            SearchResult::Script { id, .. } | SearchResult::ScriptWithArgument { id, .. } => {
+                ResultId::new(ResultKind::Script, id.as_str())
+            }
+            SearchResult::ExtensionCommand { command }
+            | SearchResult::ExtensionCommandWithArg { command, .. } => ResultId::new(
+                ResultKind::Extension,
+                format!("{}/{}", command.extension_id, command.id),
+            ),
+            SearchResult::Calculation { expression, .. } => {
+                ResultId::new(ResultKind::Calculation, expression.trim())
+            }
+            SearchResult::ClipboardItem { content, .. } => {
+                ResultId::new(ResultKind::Clipboard, id::content_hash(content))
+            }
+            SearchResult::FileResult { path, .. } => {
+                ResultId::new(ResultKind::File, expand_home(path))
+            }
+            SearchResult::EmojiResult { emoji, .. } => {
+                ResultId::new(ResultKind::Emoji, emoji.as_str())
+            }
+            SearchResult::UnitConversion { display, .. } => {
+                ResultId::new(ResultKind::UnitConversion, display.as_str())
+            }
+            SearchResult::Fallback { result } => {
+                ResultId::new(ResultKind::Fallback, result.id().to_string())
+            }
+        }
+    }
+
+    /// Trigger keyword of keyword-driven results (aliases, quicklinks, scripts, extensions)
+    pub fn keyword(&self) -> Option<&str> {
+        match self {
+            SearchResult::Alias { keyword, .. } => Some(keyword),
+            SearchResult::Quicklink { keyword, .. } => Some(keyword),
+            SearchResult::QuicklinkWithQuery { keyword, .. } => Some(keyword),
+            SearchResult::Script { id, .. } => Some(id),
+            SearchResult::ScriptWithArgument { id, .. } => Some(id),
+            SearchResult::ExtensionCommand { command } => Some(&command.keyword),
+            SearchResult::ExtensionCommandWithArg { command, .. } => Some(&command.keyword),
+            SearchResult::Fallback { result } => result.keyword(),
+            _ => None,
+        }
+    }
+
+    /// Whether the result runs a command marked destructive: a system
+    /// command like Shut Down, or a script or extension command flagged so
+    pub fn is_destructive(&self) -> bool {
+        match self {
+            SearchResult::Command { id, .. } => {
+                SystemCommand::from_id(id).is_some_and(SystemCommand::is_destructive)
+            }
+            SearchResult::Script { destructive, .. }
+            | SearchResult::ScriptWithArgument { destructive, .. } => *destructive,
+            SearchResult::ExtensionCommand { command }
+            | SearchResult::ExtensionCommandWithArg { command, .. } => command.destructive,
+            SearchResult::Fallback { result } => result.is_destructive(),
+            _ => false,
+        }
+    }
+
+    /// How the result is confirmed before it runs. System commands are
+    /// confirmed by the action they run (see
+    /// [`ExecutionAction::confirmation`]), other results by their result id
+    /// (`script:backup`).
+    pub fn confirmation(&self, config: &ConfirmationConfig) -> Confirmation {
+        match self {
+            SearchResult::Command { .. } => self.execution_action().confirmation(config),
+            SearchResult::Fallback { result } => result.confirmation(config),
+            _ => config.policy(&self.id().to_string(), self.is_destructive()),
+        }
+    }
+
+    /// Get the action to perform when this result is executed
+    pub fn execution_action(&self) -> ExecutionAction {
+        match self {
+            SearchResult::App {
+                id,
+                exec,
+                name,
+                desktop_file,
+                terminal,
+                ..
+            } => ExecutionAction::LaunchApp {
+                app_id: id.clone(),
+                exec: exec.clone(),
+                name: name.clone(),
+                desktop_file: desktop_file.clone(),
+                terminal: *terminal,
+                action: None,
+            },
+            SearchResult::AppAction {
+                app_id,
+                name,
+                action,
+                desktop_file,
+                terminal,
+                ..
+            } => action.launch(app_id, name, desktop_file, *terminal),
+            SearchResult::Window { id, .. } => ExecutionAction::FocusWindow { id: id.clone() },
+            SearchResult::Command { id, .. } => match id.as_str() {
+                "nova:settings" => ExecutionAction::OpenSettings,
+                "nova:quit" => ExecutionAction::Quit,
+                id => match SystemCommand::from_id(id) {
+                    Some(command) => ExecutionAction::SystemCommand { command },
+                    None => ExecutionAction::NeedsInput,
+                },
+            },
+            SearchResult::Alias { target, .. } => ExecutionAction::RunShellCommand {
+                command: target.clone(),
+            },
+            SearchResult::Quicklink { url, has_query, .. } => {
+                if *has_query {
+                    ExecutionAction::NeedsInput
+                } else {
+                    ExecutionAction::OpenUrl { url: url.clone() }
+                }
+            }
+            SearchResult::QuicklinkWithQuery { resolved_url, .. } => ExecutionAction::OpenUrl {
+                url: resolved_url.clone(),
+            },
+            SearchResult::Script {
+                path, has_argument, output_mode, ..
+            } => {
+                if *has_argument {
+                    ExecutionAction::NeedsInput
+                } else {
+                    ExecutionAction::RunScript {
+                        path: path.clone(),
+                        argument: None,
+                        output_mode: output_mode.clone(),
+                    }
+                }
+            }
+            SearchResult::ScriptWithArgument {
+                path, argument, output_mode, ..
+            } => ExecutionAction::RunScript {
+                path: path.clone(),
+                argument: Some(argument.clone()),
+                output_mode: output_mode.clone(),
+            },
+            SearchResult::ExtensionCommand { command } => {
+                if command.has_argument {
+                    ExecutionAction::NeedsInput
+                } else {
+                    ExecutionAction::RunExtensionCommand {
+                        command: command.clone(),
+                        argument: None,
+                    }
+                }
+            }
+            SearchResult::ExtensionCommandWithArg { command, argument } => {
+                ExecutionAction::RunExtensionCommand {
+                    command: command.clone(),
+                    argument: Some(argument.clone()),
+                }
+            }
+            SearchResult::Calculation { result, expression } => {
+                let value = result.trim_start_matches("= ");
+                ExecutionAction::CopyToClipboard {
+                    content: value.to_string(),
+                    notification: format!("{} = {}", expression, value),
+                }
+            }
+            SearchResult::ClipboardItem { content, preview, .. } => {
+                ExecutionAction::CopyToClipboard {
+                    content: content.clone(),
+                    notification: preview.clone(),
+                }
+            }
+            SearchResult::FileResult { path, .. } => ExecutionAction::OpenFile {
+                path: expand_home(path),
+            },
+            SearchResult::EmojiResult { emoji, name, .. } => ExecutionAction::CopyToClipboard {
+                content: emoji.clone(),
+                notification: name.clone(),
+            },
+            SearchResult::UnitConversion { result, display } => {
+                ExecutionAction::CopyToClipboard {
+                    content: result.clone(),
+                    notification: display.clone(),
+                }
+            }
+            SearchResult::Fallback { result } => result.execution_action(),
+        }
+    }
+
+    /// Secondary actions offered in the action panel, besides the default
+    /// [`execution_action`](Self::execution_action)
+    pub fn actions(&self) -> Vec<NamedAction> {
+        match self {
+            SearchResult::App {
+                id,
+                name,
+                exec,
+                desktop_file,
+                terminal,
+                actions: app_actions,
+                windows,
+                ..
+            } => {
+                let mut actions: Vec<NamedAction> = windows
+                    .iter()
+                    .map(|window| {
+                        NamedAction::new(
+                            format!("Switch to {}", window.title),
+                            ExecutionAction::FocusWindow {
+                                id: window.id.clone(),
+                            },
+                        )
+                    })
+                    .collect();
+                actions.extend(app_actions.iter().map(|action| {
+                    let title = format!("{}: {}", name, action.name);
+                    let launch = action.launch(id, &title, desktop_file, *terminal);
+                    NamedAction::new(&action.name, launch)
+                }));
+                actions.push(NamedAction::new(
+                    "Open in Terminal",
+                    ExecutionAction::RunInTerminal {
+                        command: exec.clone(),
+                        name: name.clone(),
+                    },
+                ));
+                if let Some(path) = desktop_file {
+                    actions.push(NamedAction::new(
+                        "Show Desktop File",
+                        ExecutionAction::RevealFile {
+                            path: path.display().to_string(),
+                        },
+                    ));
+                }
+                actions.push(NamedAction::new(
+                    "Copy Exec Line",
+                    ExecutionAction::CopyToClipboard {
+                        content: exec.clone(),
+                        notification: format!("Exec line of {}", name),
+                    },
+                ));
+                actions
+            }
+            SearchResult::FileResult { path, .. } => {
+                let path = expand_home(path);
+                vec![
+                    NamedAction::new(
+                        "Reveal in Folder",
+                        ExecutionAction::RevealFile { path: path.clone() },
+                    ),
+                    NamedAction::new(
+                        "Copy Path",
+                        ExecutionAction::CopyToClipboard {
+                            content: path.clone(),
+                            notification: path.clone(),
+                        },
+                    ),
+                    NamedAction::new("Open With\u{2026}", ExecutionAction::OpenWith { path }),
+                ]
+            }
+            SearchResult::Quicklink {
+                url,
+                has_query: false,
+                ..
+            }
+            | SearchResult::QuicklinkWithQuery {
+                resolved_url: url, ..
+            } => vec![NamedAction::new(
+                "Copy URL",
+                ExecutionAction::CopyToClipboard {
+                    content: url.clone(),
+                    notification: url.clone(),
+                },
+            )],
+            SearchResult::Fallback { result } => result.actions(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Best score below which a query is also retried in other keyboard layouts
+const WEAK_MATCH_SCORE: i64 = 100;
+
+fn parse_layouts(names: &[String]) -> Vec<KeyboardLayout> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let layout = KeyboardLayout::parse(name);
+            if layout.is_none() {
+                eprintln!("[Nova] Ignoring unknown keyboard layout: {}", name);
+            }
+            layout
+        })
+        .collect()
+}
+
+/// Quicklink or script named by a fallback keyword, given `query` as its argument
+fn custom_fallback(
+    commands: &CustomCommandsIndex,
+    keyword: &str,
+    query: &str,
+) -> Option<SearchResult> {
+    let keyword = keyword.to_lowercase();
+
+    if let Some(quicklink) = commands
+        .quicklinks
+        .iter()
+        .find(|ql| ql.keyword.to_lowercase() == keyword && ql.has_query_placeholder())
+    {
+        return Some(SearchResult::QuicklinkWithQuery {
+            keyword: quicklink.keyword.clone(),
+            name: format!("{}: {}", quicklink.name, query),
+            url: quicklink.url.clone(),
+            query: query.to_string(),
+            resolved_url: quicklink.resolve_url(query),
+        });
+    }
+
+    commands
+        .scripts
+        .iter()
+        .find(|script| script.has_argument && script.id.to_lowercase() == keyword)
+        .map(|script| SearchResult::ScriptWithArgument {
+            id: script.id.clone(),
+            name: format!("{}: {}", script.name, query),
+            description: script.description.clone(),
+            icon: script.icon.clone(),
+            path: script.path.clone(),
+            argument: query.to_string(),
+            output_mode: script.output_mode.clone(),
+            destructive: script.destructive,
+        })
+}
+
+fn parse_favorites(ids: &[String]) -> Vec<ResultId> {
+    ids.iter()
+        .filter_map(|id| {
+            let parsed = ResultId::parse(id);
+            if parsed.is_none() {
+                eprintln!("[Nova] Ignoring invalid favorite id: {}", id);
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> String {
+    if path.starts_with("~/") {
+        dirs::home_dir()
+            .map(|h| format!("{}{}", h.display(), &path[1..]))
+            .unwrap_or_else(|| path.to_string())
+    } else {
+        path.to_string()
+    }
+}
+
+/// Built-in commands: Nova's own, then the system commands in `supported`
+pub fn get_system_commands(supported: &[SystemCommand]) -> Vec<SearchResult> {
+    let mut commands = vec![
+        SearchResult::Command {
+            id: "nova:settings".to_string(),
+            name: "Settings".to_string(),
+            description: "Open Nova settings".to_string(),
+        },
+        SearchResult::Command {
+            id: "nova:quit".to_string(),
+            name: "Quit Nova".to_string(),
+            description: "Close Nova completely".to_string(),
+        },
+    ];
+    commands.extend(
+        SystemCommand::ALL
+            .into_iter()
+            .filter(|command| supported.contains(command))
+            .map(|command| {
+                let (name, description) = system_command_text(command);
+                SearchResult::Command {
+                    id: command.id().to_string(),
+                    name: name.to_string(),
+                    description: description.to_string(),
+                }
+            }),
+    );
+    commands
+}
+
+/// Name and description of a system command
+fn system_command_text(command: SystemCommand) -> (&'static str, &'static str) {
+    match command {
+        SystemCommand::Lock => ("Lock Screen", "Lock the screen"),
+        SystemCommand::LockAndSuspend => ("Lock & Suspend", "Lock the screen and go to sleep"),
+        SystemCommand::Sleep => ("Sleep", "Put computer to sleep"),
+        SystemCommand::HybridSleep => (
+            "Hybrid Sleep",
+            "Sleep, keeping a hibernation image in case power runs out",
+        ),
+        SystemCommand::Hibernate => ("Hibernate", "Save the session to disk and power off"),
+        SystemCommand::Logout => ("Log Out", "Log out of current session"),
+        SystemCommand::SwitchUser => ("Switch User", "Log in as another user"),
+        SystemCommand::Restart => ("Restart", "Restart the computer"),
+        SystemCommand::Shutdown => ("Shut Down", "Shut down the computer"),
+        SystemCommand::ScreenOff => ("Screen Off", "Turn off the displays"),
+        SystemCommand::ToggleDoNotDisturb => (
+            "Toggle Do Not Disturb",
+            "Turn notification popups off or on",
+        ),
+        SystemCommand::ToggleDarkMode => ("Toggle Dark Mode", "Switch between dark and light"),
+        SystemCommand::VolumeUp => ("Volume Up", "Raise the output volume"),
+        SystemCommand::VolumeDown => ("Volume Down", "Lower the output volume"),
+        SystemCommand::ToggleMute => ("Toggle Mute", "Mute or unmute the output"),
+        SystemCommand::BrightnessUp => ("Brightness Up", "Brighten the screen"),
+        SystemCommand::BrightnessDown => ("Brightness Down", "Dim the screen"),
+        SystemCommand::EmptyTrash => ("Empty Trash", "Permanently delete the files in the trash"),
+    }
+}
+
+/// Platform-agnostic app entry for search results
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformAppEntry {
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    /// The `.desktop` file the entry was read from, if any
+    pub desktop_file: Option<PathBuf>,
+    /// Runs inside a terminal emulator, like `Terminal=true` entries and
+    /// command line programs
+    pub terminal: bool,
+    /// Desktop actions, such as "New Private Window"
+    pub actions: Vec<AppAction>,
+}
+
+impl PlatformAppEntry {
+    /// The search result launching this app
+    pub fn to_result(&self) -> SearchResult {
+        self.result_with_windows(Vec::new())
+    }
+
+    /// The search result launching this app, which also offers to switch to
+    /// its open windows
+    pub fn result_with_windows(&self, windows: Vec<OpenWindow>) -> SearchResult {
+        SearchResult::App {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            exec: self.exec.clone(),
+            icon: self.icon.clone(),
+            description: self.description.clone(),
+            desktop_file: self.desktop_file.clone(),
+            terminal: self.terminal,
+            actions: self.actions.clone(),
+            windows,
+        }
+    }
+
+    /// The search result running one of this app's desktop actions
+    pub fn action_result(&self, action: &AppAction) -> SearchResult {
+        SearchResult::AppAction {
+            app_id: self.id.clone(),
+            name: format!("{}: {}", self.name, action.name),
+            action: action.clone(),
+            icon: action.icon.clone().or_else(|| self.icon.clone()),
+            desktop_file: self.desktop_file.clone(),
+            terminal: self.terminal,
+        }
+    }
+}
+
+/// A `[Desktop Action]` of an application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppAction {
+    /// Action key in the desktop file (e.g., "new-private-window")
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,
+}
+
+impl AppAction {
+    /// Launch this action of the app with the given id and display name,
+    /// in a terminal when the app runs in one
+    fn launch(
+        &self,
+        app_id: &str,
+        name: &str,
+        desktop_file: &Option<PathBuf>,
+        terminal: bool,
+    ) -> ExecutionAction {
+        ExecutionAction::LaunchApp {
+            app_id: app_id.to_string(),
+            exec: self.exec.clone(),
+            name: name.to_string(),
+            desktop_file: desktop_file.clone(),
+            terminal,
+            action: Some(self.id.clone()),
+        }
+    }
+}
+
+/// Platform-agnostic open window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenWindow {
+    /// Window manager specific id used to focus the window
+    pub id: String,
+    pub title: String,
+    /// Wayland app id or X11 window class
+    pub app_id: String,
+    /// Name of the workspace the window is on
+    pub workspace: Option<String>,
+}
+
+impl OpenWindow {
+    /// Check if the window was opened by `app`.
+    ///
+    /// Window managers report the Wayland app id or the X11 class, which is
+    /// usually the desktop file id (`org.gnome.Nautilus`), its last
+    /// component (`nautilus`) or the program name (`firefox`).
+    pub fn belongs_to(&self, app: &PlatformAppEntry) -> bool {
+        let app_id = self.app_id.to_lowercase();
+        if app_id.is_empty() {
+            return false;
+        }
+        let id = app.id.to_lowercase();
+        let program = app
+            .exec
+            .split_whitespace()
+            .find(|arg| !arg.contains('='))
+            .and_then(|arg| arg.rsplit('/').next())
+            .unwrap_or_default()
+            .to_lowercase();
+        app_id == id || id.rsplit('.').next() == Some(app_id.as_str()) || app_id == program
+    }
+
+    /// The search result switching to this window, described with the app
+    /// that opened it when known
+    pub fn to_result(&self, app: Option<&PlatformAppEntry>) -> SearchResult {
+        let owner = app.map_or(self.app_id.as_str(), |app| app.name.as_str());
+        let description = match &self.workspace {
+            Some(workspace) => format!("{} \u{2014} Workspace {}", owner, workspace),
+            None => owner.to_string(),
+        };
+        SearchResult::Window {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            app_id: self.app_id.clone(),
+            workspace: self.workspace.clone(),
+            description,
+            icon: app
+                .and_then(|app| app.icon.clone())
+                .or_else(|| Some(self.app_id.clone()).filter(|id| !id.is_empty())),
+        }
+    }
+}
+
+/// Search engine that aggregates results from all providers
+pub struct SearchEngine {
+    pub custom_commands: CustomCommandsIndex,
+    pub extension_manager: Arc<ExtensionManager>,
+    pub extension_index: ExtensionIndex,
+    providers: ProviderRegistry,
+    syntax: QuerySyntax,
+    sections: SectionLayout,
+    layouts: Vec<KeyboardLayout>,
+    fallbacks: Vec<String>,
+    /// Launch history, shared with the engines rebuilt from this one
+    usage: Arc<Mutex<FrecencyStore>>,
+    favorites: RwLock<Vec<ResultId>>,
+    /// System commands the platform supports
+    system_commands: Arc<RwLock<Vec<SystemCommand>>>,
+}
+
+impl SearchEngine {
+    pub fn new(config: &Config) -> Self {
+        Self::with_usage(config, Arc::new(Mutex::new(FrecencyStore::load())))
+    }
+
+    /// Create an engine keeping the launch history of an existing one, so
+    /// rebuilding the engine doesn't load it from disk again
+    pub fn with_usage(config: &Config, usage: Arc<Mutex<FrecencyStore>>) -> Self {
+        let custom_commands = CustomCommandsIndex::new(config);
+        let extension_manager = Arc::new(ExtensionManager::load(&get_extensions_dir()));
+        let extension_index = ExtensionIndex::from_custom_commands(
+            &custom_commands,
+            &config.aliases,
+            &config.quicklinks,
+        );
+
+        let mut providers = ProviderRegistry::new();
+        providers.register(Box::new(AliasProvider::new(
+            custom_commands.aliases.clone(),
+        )));
+        providers.register(Box::new(CalculatorProvider));
+        providers.register(Box::new(UnitsProvider));
+        providers.register(Box::new(ClipboardProvider));
+        providers.register(Box::new(FileProvider));
+        providers.register(Box::new(EmojiProvider));
+        providers.register(Box::new(QuicklinkProvider::new(
+            custom_commands.quicklinks.clone(),
+        )));
+        providers.register(Box::new(ScriptProvider::new(
+            custom_commands.scripts.clone(),
+        )));
+        let system_commands = Arc::new(RwLock::new(Vec::new()));
+        providers.register(Box::new(SystemCommandProvider::new(Arc::clone(
+            &system_commands,
+        ))));
+        providers.register(Box::new(ExtensionCommandProvider::new(Arc::clone(
+            &extension_manager,
+        ))));
+        providers.register(Box::new(AppProvider));
+        providers.register(Box::new(WindowProvider));
+        providers.apply_config(&config.search);
+
+        let engine = Self {
+            custom_commands,
+            extension_manager,
+            extension_index,
+            providers,
+            syntax: QuerySyntax::from_config(&config.search),
+            sections: SectionLayout::from_config(&config.search),
+            layouts: parse_layouts(&config.behavior.keyboard_layouts),
+            fallbacks: config.fallbacks.clone(),
+            usage,
+            favorites: RwLock::new(parse_favorites(&config.favorites)),
+            system_commands,
+        };
+
+        for keyword in &engine.fallbacks {
+            if engine.fallback_result(keyword, "").is_none() {
+                eprintln!("[Nova] Ignoring unknown fallback command: {}", keyword);
+            }
+        }
+        engine
+    }
+
+    /// Replace the pinned result ids
+    pub fn set_favorites(&self, ids: &[String]) {
+        *self.favorites.write().unwrap() = parse_favorites(ids);
+    }
+
+    /// Check if a result is pinned
+    pub fn is_favorite(&self, result: &SearchResult) -> bool {
+        self.favorites.read().unwrap().contains(&result.id())
+    }
+
+    /// Replace the system commands offered; only commands the platform
+    /// supports should be listed
+    pub fn set_system_commands(&self, commands: Vec<SystemCommand>) {
+        *self.system_commands.write().unwrap() = commands;
+    }
+
+    /// The system commands offered
+    pub fn system_commands(&self) -> Vec<SystemCommand> {
+        self.system_commands.read().unwrap().clone()
+    }
+
+    /// The launch history, for [`SearchEngine::with_usage`]
+    pub fn usage(&self) -> Arc<Mutex<FrecencyStore>> {
+        Arc::clone(&self.usage)
+    }
+
+    /// Add a provider, replacing any built-in provider with the same name
+    pub fn register_provider(&mut self, provider: Box<dyn SearchProvider>) {
+        self.providers.register(provider);
+    }
+
+    /// Access the provider registry
+    pub fn providers(&self) -> &ProviderRegistry {
+        &self.providers
+    }
+
+    /// Mutable access to the provider registry
+    pub fn providers_mut(&mut self) -> &mut ProviderRegistry {
+        &mut self.providers
+    }
+
+    /// Perform a search with all available providers
+    pub fn search(
+        &self,
+        apps: &[PlatformAppEntry],
+        windows: &[OpenWindow],
+        clipboard_history: &clipboard::ClipboardHistory,
+        query: &str,
+        max_results: usize,
+    ) -> Vec<SearchResult> {
+        self.search_scored(apps, windows, clipboard_history, query, max_results)
+            .into_iter()
+            .map(|scored| scored.result)
+            .collect()
+    }
+
+    /// Like [`SearchEngine::search`], but split into titled sections
+    pub fn search_grouped(
+        &self,
+        apps: &[PlatformAppEntry],
+        windows: &[OpenWindow],
+        clipboard_history: &clipboard::ClipboardHistory,
+        query: &str,
+        max_results: usize,
+    ) -> Vec<ResultSection> {
+        self.group_results(self.search_scored(apps, windows, clipboard_history, query, max_results))
+    }
+
+    /// Split ranked results into sections using the configured group order and caps
+    pub fn group_results(&self, results: Vec<ScoredResult>) -> Vec<ResultSection> {
+        self.sections.group(results)
+    }
+
+    /// Like [`SearchEngine::search`], but keeps each result's ranking score
+    pub fn search_scored(
+        &self,
+        apps: &[PlatformAppEntry],
+        windows: &[OpenWindow],
+        clipboard_history: &clipboard::ClipboardHistory,
+        query: &str,
+        max_results: usize,
+    ) -> Vec<ScoredResult> {
+        self.search_phase(
+            apps,
+            windows,
+            clipboard_history,
+            query,
+            max_results,
+            SearchPhase::All,
+            &AtomicBool::new(false),
+        )
+    }
+
+    /// Search with only the providers of one phase.
+    ///
+    /// Lets callers show fast results right away and merge in slow ones with
+    /// [`merge_results`] once they arrive. Pins are added by the fast phase.
+    /// Providers stop early once `cancelled` is set.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_phase(
+        &self,
+        apps: &[PlatformAppEntry],
+        windows: &[OpenWindow],
+        clipboard_history: &clipboard::ClipboardHistory,
+        query: &str,
+        max_results: usize,
+        phase: SearchPhase,
+        cancelled: &AtomicBool,
+    ) -> Vec<ScoredResult> {
+        let parsed = self.syntax.parse(query);
+        let ctx = SearchContext::parsed(parsed, max_results, apps, clipboard_history)
+            .with_windows(windows)
+            .with_cancel(cancelled);
+        let mut results = self.providers.search_phase(&ctx, phase);
+
+        // Weak results may mean the query was typed with the wrong layout
+        let best = results.first().map_or(i64::MIN, |r| r.score);
+        if !query.is_empty() && best < WEAK_MATCH_SCORE {
+            for alternative in keyboard_layout::alternatives(query, &self.layouts) {
+                let parsed = self.syntax.parse(&alternative);
+                let ctx = SearchContext::parsed(parsed, max_results, apps, clipboard_history)
+                    .with_windows(windows)
+                    .with_cancel(cancelled);
+                results.extend(self.providers.search_phase(&ctx, phase));
+            }
+            results = merge_results(results, usize::MAX);
+        }
+
+        let usage = self.usage.lock().unwrap();
+        if !usage.is_empty() {
+            for scored in &mut results {
+                scored.score += usage.score(&scored.result.id().to_string(), query);
+            }
+            results.sort_by_key(|r| std::cmp::Reverse(r.score));
+        }
+        drop(usage);
+
+        // Fallbacks sort last, so they only fill slots real results leave free
+        if !query.trim().is_empty() && phase != SearchPhase::Slow {
+            results.extend(self.fallback_results(query.trim()));
+        }
+
+        // Pinned favourites lead the empty-query view, in the user's order
+        let favorites = self.favorites.read().unwrap();
+        if query.is_empty() && !favorites.is_empty() && phase != SearchPhase::Slow {
+            let pinned: Vec<ScoredResult> = favorites
+                .iter()
+                .filter_map(|id| self.resolve(id, apps))
+                .map(|result| ScoredResult::new(result, PINNED_SCORE))
+                .collect();
+
+            results.retain(|r| !favorites.contains(&r.result.id()));
+            let limit = max_results.max(pinned.len());
+            results.splice(0..0, pinned);
+            results.truncate(limit);
+            return results;
+        }
+
+        results.truncate(max_results);
+        results
+    }
+
+    /// Rebuild the result an id refers to, if its source still exists.
+    ///
+    /// Clipboard entries, unit conversions, windows and fallbacks cannot be
+    /// rebuilt from their id.
+    pub fn resolve(&self, id: &ResultId, apps: &[PlatformAppEntry]) -> Option<SearchResult> {
+        let key = id.key.as_str();
+        match id.kind {
+            ResultKind::App => match key.split_once('/') {
+                Some((app_id, action_id)) => {
+                    let app = apps.iter().find(|app| app.id == app_id)?;
+                    let action = app.actions.iter().find(|a| a.id == action_id)?;
+                    Some(app.action_result(action))
+                }
+                None => apps
+                    .iter()
+                    .find(|app| app.id == key)
+                    .map(|app| app.to_result()),
+            },
+            ResultKind::Command => get_system_commands(&self.system_commands.read().unwrap())
+                .into_iter()
+                .find(|cmd| matches!(cmd, SearchResult::Command { id, .. } if id == key)),
+            ResultKind::Alias => self
+                .custom_commands
+                .aliases
+                .iter()
+                .find(|alias| alias.keyword == key)
+                .map(|alias| SearchResult::Alias {
+                    keyword: alias.keyword.clone(),
+                    name: alias.name.clone(),
+                    target: alias.target.clone(),
+                    icon: alias.icon.clone(),
+                }),
+            ResultKind::Quicklink => self
+                .custom_commands
+                .quicklinks
+                .iter()
+                .find(|ql| ql.keyword == key)
+                .map(|ql| SearchResult::Quicklink {
+                    keyword: ql.keyword.clone(),
+                    name: ql.name.clone(),
+                    url: ql.url.clone(),
+                    has_query: ql.has_query_placeholder(),
+                }),
+            ResultKind::Script => self
+                .custom_commands
+                .scripts
+                .iter()
+                .find(|script| script.id == key)
+                .map(|script| SearchResult::Script {
+                    id: script.id.clone(),
+                    name: script.name.clone(),
+                    description: script.description.clone(),
+                    icon: script.icon.clone(),
+                    path: script.path.clone(),
+                    has_argument: script.has_argument,
+                    output_mode: script.output_mode.clone(),
+                    destructive: script.destructive,
+                }),
+            ResultKind::Extension => {
+                let (extension_id, command_id) = key.split_once('/')?;
+                self.extension_manager
+                    .commands()
+                    .find(|cmd| cmd.extension_id == extension_id && cmd.id == command_id)
+                    .map(|cmd| SearchResult::ExtensionCommand {
+                        command: cmd.clone(),
+                    })
+            }
+            ResultKind::Calculation => {
+                calculator::evaluate(key).map(|value| SearchResult::Calculation {
+                    expression: key.to_string(),
+                    result: format!("= {}", calculator::format_result(value)),
+                })
+            }
+            ResultKind::File => {
+                let path = PathBuf::from(key);
+                let name = path.file_name()?.to_string_lossy().to_string();
+                let is_dir = path.is_dir();
+                if !path.exists() {
+                    return None;
+                }
+                let entry = file_search::FileEntry { name, path, is_dir };
+                let icon_prefix = if is_dir { "[D] " } else { "" };
+                Some(SearchResult::FileResult {
+                    name: format!("{}{}", icon_prefix, entry.display_name()),
+                    path: entry.display_path(),
+                    is_dir,
+                })
+            }
+            ResultKind::Emoji => emoji::find(key).map(|e| SearchResult::EmojiResult {
+                emoji: e.char.to_string(),
+                name: format!("{} {}", e.char, e.name()),
+                aliases: e.aliases(),
+            }),
+            ResultKind::Clipboard | ResultKind::UnitConversion | ResultKind::Window => None,
+            ResultKind::Fallback => None,
+        }
+    }
+
+    /// Configured fallback commands, each taking the whole query as its argument
+    pub fn fallback_results(&self, query: &str) -> Vec<ScoredResult> {
+        self.fallbacks
+            .iter()
+            .filter_map(|keyword| self.fallback_result(keyword, query))
+            .map(|result| {
+                let result = SearchResult::Fallback {
+                    result: Box::new(result),
+                };
+                ScoredResult::new(result, FALLBACK_SCORE)
+            })
+            .collect()
+    }
+
+    fn fallback_result(&self, keyword: &str, query: &str) -> Option<SearchResult> {
+        if let Some(result) = custom_fallback(&self.custom_commands, keyword, query) {
+            return Some(result);
+        }
+
+        let command = self
+            .extension_manager
+            .get_command(keyword)
+            .filter(|cmd| cmd.has_argument)?;
+        Some(SearchResult::ExtensionCommandWithArg {
+            command: command.clone(),
+            argument: query.to_string(),
+        })
+    }
+
+    /// Remember that `result` was launched for `query`, so it ranks higher next time
+    pub fn record_usage(&self, query: &str, result: &SearchResult) {
+        self.usage
+            .lock()
+            .unwrap()
+            .record(query, &result.id().to_string());
+    }
+
+    /// Search within a specific command mode context
+    pub fn search_in_command_mode(
+        &self,
+        mode_state: &CommandModeState,
+        query: &str,
+        _max_results: usize,
+    ) -> Vec<SearchResult> {
+        let Some(ref ext) = mode_state.active_extension else {
+            return Vec::new();
+        };
+
+        match &ext.kind {
+            ExtensionKind::Quicklink { url, .. } => {
+                if query.is_empty() {
+                    vec![SearchResult::Quicklink {
+                        keyword: ext.keyword.clone(),
+                        name: format!("Type to search {}", ext.name),
+                        url: url.clone(),
+                        has_query: true,
+                    }]
+                } else {
+                    let resolved = url.replace("{query}", &urlencoding::encode(query));
+                    vec![SearchResult::QuicklinkWithQuery {
+                        keyword: ext.keyword.clone(),
+                        name: format!("{}: {}", ext.name, query),
+                        url: url.clone(),
+                        query: query.to_string(),
+                        resolved_url: resolved,
+                    }]
+                }
+            }
+            ExtensionKind::Script {
+                path,
+                output_mode,
+                description,
+                destructive,
+                ..
+            } => {
+                if query.is_empty() {
+                    vec![SearchResult::Script {
+                        id: ext.keyword.clone(),
+                        name: format!("{} (type argument)", ext.name),
+                        description: description.clone(),
+                        icon: ext.icon.clone(),
+                        path: path.clone(),
+                        has_argument: true,
+                        output_mode: output_mode.clone(),
+                        destructive: *destructive,
+                    }]
+                } else {
+                    vec![SearchResult::ScriptWithArgument {
+                        id: ext.keyword.clone(),
+                        name: format!("{}: {}", ext.name, query),
+                        description: description.clone(),
+                        icon: ext.icon.clone(),
+                        path: path.clone(),
+                        argument: query.to_string(),
+                        output_mode: output_mode.clone(),
+                        destructive: *destructive,
+                    }]
+                }
+            }
+            ExtensionKind::Alias { target } => {
+                vec![SearchResult::Alias {
+                    keyword: ext.keyword.clone(),
+                    name: ext.name.clone(),
+                    target: target.clone(),
+                    icon: ext.icon.clone(),
+                }]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(result: &SearchResult) -> Vec<String> {
+        result.actions().into_iter().map(|a| a.title).collect()
+    }
+
+    #[test]
+    fn test_app_actions() {
+        let entry = PlatformAppEntry {
+            id: "firefox".to_string(),
+            name: "Firefox".to_string(),
+            exec: "firefox %u".to_string(),
+            icon: Some("firefox".to_string()),
+            description: None,
+            keywords: Vec::new(),
+            desktop_file: Some(PathBuf::from("/usr/share/applications/firefox.desktop")),
+            terminal: false,
+            actions: vec![AppAction {
+                id: "new-private-window".to_string(),
+                name: "New Private Window".to_string(),
+                exec: "firefox --private-window %u".to_string(),
+                icon: None,
+            }],
+        };
+        assert_eq!(
+            titles(&entry.to_result()),
+            vec![
+                "New Private Window",
+                "Open in Terminal",
+                "Show Desktop File",
+                "Copy Exec Line"
+            ]
+        );
+
+        let action = entry.action_result(&entry.actions[0]);
+        assert_eq!(action.name(), "Firefox: New Private Window");
+        assert_eq!(action.id().to_string(), "app:firefox/new-private-window");
+        assert!(matches!(
+            action.execution_action(),
+            ExecutionAction::LaunchApp { action: Some(id), terminal: false, .. }
+                if id == "new-private-window"
+        ));
+
+        // Actions of terminal apps run in a terminal too
+        let mut htop = entry.clone();
+        htop.terminal = true;
+        assert!(matches!(
+            htop.action_result(&htop.actions[0]).execution_action(),
+            ExecutionAction::LaunchApp { terminal: true, .. }
+        ));
+        assert!(matches!(
+            &htop.to_result().actions()[0].action,
+            ExecutionAction::LaunchApp { terminal: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_file_actions_use_expanded_path() {
+        let file = SearchResult::FileResult {
+            name: "a.txt".to_string(),
+            path: "/tmp/a.txt".to_string(),
+            is_dir: false,
+        };
+        let actions = file.actions();
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(
+            &actions[1].action,
+            ExecutionAction::CopyToClipboard { content, .. } if content == "/tmp/a.txt"
+        ));
+    }
+
+    #[test]
+    fn test_quicklink_actions() {
+        let waiting = SearchResult::Quicklink {
+            keyword: "yt".to_string(),
+            name: "YouTube".to_string(),
+            url: "https://youtube.com/results?q={query}".to_string(),
+            has_query: true,
+        };
+        assert!(waiting.actions().is_empty());
+
+        let resolved = SearchResult::QuicklinkWithQuery {
+            keyword: "yt".to_string(),
+            name: "YouTube: cats".to_string(),
+            url: "https://youtube.com/results?q={query}".to_string(),
+            query: "cats".to_string(),
+            resolved_url: "https://youtube.com/results?q=cats".to_string(),
+        };
+        assert_eq!(titles(&resolved), vec!["Copy URL"]);
+    }
+
+    #[test]
+    fn test_system_commands_follow_support() {
+        let ids = |commands: Vec<SearchResult>| -> Vec<String> {
+            commands.iter().map(|c| c.id().key).collect()
+        };
+        assert_eq!(
+            ids(get_system_commands(&[])),
+            vec!["nova:settings", "nova:quit"]
+        );
+        assert_eq!(
+            ids(get_system_commands(&[
+                SystemCommand::EmptyTrash,
+                SystemCommand::Lock
+            ])),
+            vec![
+                "nova:settings",
+                "nova:quit",
+                "system:lock",
+                "system:empty-trash"
+            ]
+        );
+
+        for command in get_system_commands(&SystemCommand::ALL).into_iter().skip(2) {
+            assert!(matches!(
+                command.execution_action(),
+                ExecutionAction::SystemCommand { command: c } if c.id() == command.id().key
+            ));
+        }
+    }
+
+    #[test]
+    fn test_destructive_results() {
+        let destructive: Vec<String> = get_system_commands(&SystemCommand::ALL)
+            .iter()
+            .filter(|command| command.is_destructive())
+            .map(|command| command.id().key)
+            .collect();
+        assert_eq!(
+            destructive,
+            vec![
+                "system:logout",
+                "system:restart",
+                "system:shutdown",
+                "system:empty-trash"
+            ]
+        );
+
+        let script = SearchResult::ScriptWithArgument {
+            id: "wipe".to_string(),
+            name: "Wipe: cache".to_string(),
+            description: String::new(),
+            icon: None,
+            path: PathBuf::from("/scripts/wipe.sh"),
+            argument: "cache".to_string(),
+            output_mode: ScriptOutputMode::Silent,
+            destructive: true,
+        };
+        assert!(script.is_destructive());
+    }
+
+    #[test]
+    fn test_confirmation_lookup() {
+        let mut config = ConfirmationConfig::default();
+        config
+            .commands
+            .insert("system:shutdown".to_string(), Confirmation::Countdown);
+        config
+            .commands
+            .insert("script:deploy".to_string(), Confirmation::Confirm);
+        let commands = get_system_commands(&SystemCommand::ALL);
+        let command = |id: &str| {
+            let command = commands.iter().find(|c| c.id().key == id).unwrap();
+            command.confirmation(&config)
+        };
+
+        assert_eq!(command("system:shutdown"), Confirmation::Countdown);
+        assert_eq!(command("system:restart"), Confirmation::Confirm);
+        assert_eq!(command("system:lock"), Confirmation::None);
+
+        let script = SearchResult::Script {
+            id: "deploy".to_string(),
+            name: "Deploy".to_string(),
+            description: String::new(),
+            icon: None,
+            path: PathBuf::from("/scripts/deploy.sh"),
+            has_argument: false,
+            output_mode: ScriptOutputMode::Silent,
+            destructive: false,
+        };
+        assert_eq!(script.confirmation(&config), Confirmation::Confirm);
+    }
+
+    #[test]
+    fn test_panel_action_confirmation() {
+        // The action panel runs actions rather than results, so a system
+        // command started there is still confirmed by its own policy
+        let config = ConfirmationConfig::default();
+        let shutdown = ExecutionAction::SystemCommand {
+            command: SystemCommand::Shutdown,
+        };
+        assert_eq!(shutdown.confirmation(&config), Confirmation::Confirm);
+
+        for command in get_system_commands(&SystemCommand::ALL) {
+            assert_eq!(
+                command.execution_action().confirmation(&config),
+                command.confirmation(&config)
+            );
+        }
+        let url = ExecutionAction::OpenUrl {
+            url: "https://example.com".to_string(),
+        };
+        assert_eq!(url.confirmation(&config), Confirmation::None);
+    }
+
+    #[test]
+    fn test_custom_fallback() {
+        let commands = CustomCommandsIndex {
+            aliases: Vec::new(),
+            quicklinks: vec![
+                crate::config::QuicklinkConfig {
+                    keyword: "g".to_string(),
+                    name: "Google".to_string(),
+                    url: "https://google.com/search?q={query}".to_string(),
+                    icon: None,
+                },
+                crate::config::QuicklinkConfig {
+                    keyword: "gh".to_string(),
+                    name: "GitHub".to_string(),
+                    url: "https://github.com".to_string(),
+                    icon: None,
+                },
+            ],
+            scripts: Vec::new(),
+        };
+
+        match custom_fallback(&commands, "G", "rust lang") {
+            Some(SearchResult::QuicklinkWithQuery { resolved_url, .. }) => {
+                assert_eq!(resolved_url, "https://google.com/search?q=rust%20lang");
+            }
+            other => panic!("unexpected fallback: {:?}", other),
+        }
+        // Quicklinks without a query placeholder can't take the query
+        assert!(custom_fallback(&commands, "gh", "nova").is_none());
+        assert!(custom_fallback(&commands, "missing", "nova").is_none());
+    }
+}
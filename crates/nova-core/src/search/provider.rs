@@ -0,0 +1,420 @@
+//! Search provider trait and registry for unified search across sources
+//!
+//! Every source of results (apps, calculator, clipboard, ...) implements
+//! [`SearchProvider`]. The [`ProviderRegistry`] decides which providers run
+//! and in which order, so new sources can be added without touching
+//! `SearchEngine::search`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::SearchConfig;
+use crate::search::query::{ParsedQuery, QuerySyntax};
+use crate::search::scoring::{ScoredResult, EXACT_KEYWORD_BONUS};
+use crate::search::{OpenWindow, PlatformAppEntry};
+use crate::services::clipboard::ClipboardHistory;
+
+/// Flag of searches that can't be cancelled
+static NOT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// A search provider that can contribute results for a query
+pub trait SearchProvider: Send + Sync {
+    /// Unique name of this provider (e.g., "apps", "calculator", "clipboard")
+    fn name(&self) -> &str;
+
+    /// Check if this provider should handle the given query
+    /// Returns true if the provider wants to contribute results
+    fn should_search(&self, _ctx: &SearchContext) -> bool {
+        true
+    }
+
+    /// Priority for this provider (higher = runs first and wins score ties)
+    fn priority(&self) -> i32 {
+        50
+    }
+
+    /// Score added to every result of this provider
+    fn weight(&self) -> i64 {
+        0
+    }
+
+    /// Whether this provider does blocking work (disk, subprocesses).
+    /// Slow providers run in a second phase so fast results show first.
+    fn is_slow(&self) -> bool {
+        false
+    }
+
+    /// Produce results for the query, scored by match quality
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult>;
+}
+
+/// Context passed to search providers
+pub struct SearchContext<'a> {
+    /// The search text: the query without its scope prefix, negated terms
+    /// and quote marks
+    pub query: String,
+    /// Query converted to lowercase
+    pub query_lower: String,
+    /// First word of query (keyword)
+    pub keyword: String,
+    /// Text after the keyword (if any)
+    pub remaining: Option<String>,
+    /// Scope and filters parsed from the query
+    pub parsed: ParsedQuery,
+    /// Maximum results to return
+    pub max_results: usize,
+    /// Discovered applications
+    pub apps: &'a [PlatformAppEntry],
+    /// Open windows, empty unless the caller listed them
+    pub windows: &'a [OpenWindow],
+    /// Clipboard history
+    pub clipboard: &'a ClipboardHistory,
+    /// Set once the results are no longer wanted
+    pub cancelled: &'a AtomicBool,
+}
+
+impl<'a> SearchContext<'a> {
+    /// Create a context for `query` using the default query syntax
+    pub fn new(
+        query: &str,
+        max_results: usize,
+        apps: &'a [PlatformAppEntry],
+        clipboard: &'a ClipboardHistory,
+    ) -> Self {
+        Self::parsed(
+            QuerySyntax::default().parse(query),
+            max_results,
+            apps,
+            clipboard,
+        )
+    }
+
+    /// Create a context for an already parsed query
+    pub fn parsed(
+        parsed: ParsedQuery,
+        max_results: usize,
+        apps: &'a [PlatformAppEntry],
+        clipboard: &'a ClipboardHistory,
+    ) -> Self {
+        let query = parsed.text.clone();
+        let query_lower = query.to_lowercase();
+        let parts: Vec<&str> = query.splitn(2, ' ').collect();
+        let keyword = parts[0].to_lowercase();
+        let remaining = parts.get(1).map(|s| s.to_string());
+
+        Self {
+            query,
+            query_lower,
+            keyword,
+            remaining,
+            parsed,
+            max_results,
+            apps,
+            windows: &[],
+            clipboard,
+            cancelled: &NOT_CANCELLED,
+        }
+    }
+
+    /// Search the given open windows too
+    pub fn with_windows(mut self, windows: &'a [OpenWindow]) -> Self {
+        self.windows = windows;
+        self
+    }
+
+    /// Stop the search early once `cancelled` is set
+    pub fn with_cancel(mut self, cancelled: &'a AtomicBool) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+
+    /// Check if the results are no longer wanted. Providers doing long
+    /// blocking work should check this as they go.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Check if query starts with any of the given prefixes
+    pub fn starts_with_any(&self, prefixes: &[&str]) -> bool {
+        prefixes.iter().any(|p| self.query_lower.starts_with(p))
+    }
+
+    /// Check if query contains the given substring (case-insensitive)
+    pub fn contains(&self, needle: &str) -> bool {
+        self.query_lower.contains(&needle.to_lowercase())
+    }
+
+    /// Check if a provider may run: a scoped query only runs its scope's provider
+    pub fn in_scope(&self, provider: &str) -> bool {
+        self.parsed
+            .scope
+            .is_none_or(|scope| scope.name() == provider)
+    }
+}
+
+/// Which providers a search runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchPhase {
+    /// Every enabled provider
+    All,
+    /// Only providers that answer instantly
+    Fast,
+    /// Only providers that report [`SearchProvider::is_slow`]
+    Slow,
+}
+
+impl SearchPhase {
+    /// Check if a provider runs in this phase
+    pub fn includes(&self, provider: &dyn SearchProvider) -> bool {
+        match self {
+            SearchPhase::All => true,
+            SearchPhase::Fast => !provider.is_slow(),
+            SearchPhase::Slow => provider.is_slow(),
+        }
+    }
+}
+
+/// Ordered collection of search providers
+#[derive(Default)]
+pub struct ProviderRegistry {
+    providers: Vec<Box<dyn SearchProvider>>,
+    order: Vec<String>,
+    disabled: Vec<String>,
+    weights: HashMap<String, i64>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a provider. Providers with a name that is already registered
+    /// replace the existing one.
+    pub fn register(&mut self, provider: Box<dyn SearchProvider>) {
+        self.providers.retain(|p| p.name() != provider.name());
+        self.providers.push(provider);
+        self.sort();
+    }
+
+    /// Remove a provider by name, returning it if it was registered
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn SearchProvider>> {
+        let pos = self.providers.iter().position(|p| p.name() == name)?;
+        Some(self.providers.remove(pos))
+    }
+
+    /// Apply enabled state and ordering from config.
+    ///
+    /// Providers named in `provider_order` run first, in that order; the rest
+    /// follow by descending priority.
+    pub fn apply_config(&mut self, config: &SearchConfig) {
+        self.order = config.provider_order.clone();
+        self.disabled = config.disabled_providers.clone();
+        self.weights = config.provider_weights.clone();
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        let order = &self.order;
+        let rank = |p: &dyn SearchProvider| {
+            order
+                .iter()
+                .position(|name| name == p.name())
+                .unwrap_or(usize::MAX)
+        };
+        self.providers.sort_by(|a, b| {
+            rank(a.as_ref())
+                .cmp(&rank(b.as_ref()))
+                .then(b.priority().cmp(&a.priority()))
+        });
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|d| d == name)
+    }
+
+    /// Names of all registered providers in execution order
+    pub fn names(&self) -> Vec<&str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Enabled providers in execution order
+    pub fn active(&self) -> impl Iterator<Item = &dyn SearchProvider> {
+        self.providers
+            .iter()
+            .filter(|p| self.is_enabled(p.name()))
+            .map(|p| p.as_ref())
+    }
+
+    /// Effective weight of a provider, honouring config overrides
+    pub fn weight_of(&self, provider: &dyn SearchProvider) -> i64 {
+        self.weights
+            .get(provider.name())
+            .copied()
+            .unwrap_or_else(|| provider.weight())
+    }
+
+    /// Run every enabled provider and rank all results on one scale.
+    ///
+    /// The final score is the provider's match score plus the provider weight,
+    /// plus [`EXACT_KEYWORD_BONUS`] when the result's keyword equals the first
+    /// word of the query. Ties keep provider order.
+    pub fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        self.search_phase(ctx, SearchPhase::All)
+    }
+
+    /// Like [`search`](Self::search), but only runs the providers of `phase`
+    pub fn search_phase(&self, ctx: &SearchContext, phase: SearchPhase) -> Vec<ScoredResult> {
+        let mut results = Vec::new();
+
+        for provider in self.active() {
+            if ctx.is_cancelled() {
+                break;
+            }
+            if !phase.includes(provider)
+                || !ctx.in_scope(provider.name())
+                || !provider.should_search(ctx)
+            {
+                continue;
+            }
+
+            let weight = self.weight_of(provider);
+            for mut scored in provider.search(ctx) {
+                if !ctx.parsed.accepts(&scored.result) {
+                    continue;
+                }
+                scored.score += weight;
+                if scored
+                    .result
+                    .keyword()
+                    .is_some_and(|kw| kw.to_lowercase() == ctx.keyword)
+                {
+                    scored.score += EXACT_KEYWORD_BONUS;
+                }
+                results.push(scored);
+            }
+        }
+
+        results.sort_by_key(|r| std::cmp::Reverse(r.score));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchResult;
+
+    struct Fixed(&'static str, i32);
+
+    struct Slow;
+
+    impl SearchProvider for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn is_slow(&self) -> bool {
+            true
+        }
+
+        fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+            Fixed("slow", 0).search(ctx)
+        }
+    }
+
+    impl SearchProvider for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn priority(&self) -> i32 {
+            self.1
+        }
+
+        fn search(&self, _ctx: &SearchContext) -> Vec<ScoredResult> {
+            vec![ScoredResult::new(
+                SearchResult::Alias {
+                    keyword: self.0.to_string(),
+                    name: self.0.to_string(),
+                    target: String::new(),
+                    icon: None,
+                },
+                self.1 as i64,
+            )]
+        }
+    }
+
+    fn registry() -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Box::new(Fixed("low", 10)));
+        registry.register(Box::new(Fixed("high", 90)));
+        registry.register(Box::new(Fixed("mid", 50)));
+        registry
+    }
+
+    #[test]
+    fn test_priority_order() {
+        assert_eq!(registry().names(), vec!["high", "mid", "low"]);
+    }
+
+    #[test]
+    fn test_config_order_and_disable() {
+        let mut registry = registry();
+        registry.apply_config(&SearchConfig {
+            provider_order: vec!["low".to_string()],
+            disabled_providers: vec!["mid".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(registry.names(), vec!["low", "high", "mid"]);
+        let active: Vec<&str> = registry.active().map(|p| p.name()).collect();
+        assert_eq!(active, vec!["low", "high"]);
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = registry();
+        registry.register(Box::new(Fixed("low", 100)));
+        assert_eq!(registry.names(), vec!["low", "high", "mid"]);
+        assert!(registry.unregister("mid").is_some());
+        assert_eq!(registry.names(), vec!["low", "high"]);
+    }
+
+    #[test]
+    fn test_search_ranks_by_score_and_keyword() {
+        let registry = registry();
+        let clipboard = ClipboardHistory::new(1);
+
+        let ctx = SearchContext::new("", 10, &[], &clipboard);
+        let names: Vec<String> = registry
+            .search(&ctx)
+            .into_iter()
+            .map(|r| r.result.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["high", "mid", "low"]);
+
+        let ctx = SearchContext::new("low", 10, &[], &clipboard);
+        let top = registry.search(&ctx).remove(0);
+        assert_eq!(top.result.name(), "low");
+        assert_eq!(top.score, 10 + EXACT_KEYWORD_BONUS);
+    }
+
+    #[test]
+    fn test_search_phases() {
+        let mut registry = registry();
+        registry.register(Box::new(Slow));
+        let clipboard = ClipboardHistory::new(1);
+        let ctx = SearchContext::new("", 10, &[], &clipboard);
+
+        let names = |phase| -> Vec<String> {
+            registry
+                .search_phase(&ctx, phase)
+                .into_iter()
+                .map(|r| r.result.name().to_string())
+                .collect()
+        };
+        assert_eq!(names(SearchPhase::Fast), vec!["high", "mid", "low"]);
+        assert_eq!(names(SearchPhase::Slow), vec!["slow"]);
+        assert_eq!(names(SearchPhase::All).len(), 4);
+    }
+}
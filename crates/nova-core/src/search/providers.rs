@@ -0,0 +1,739 @@
+//! Built-in search providers
+//!
+//! Each provider ports one of the sources that used to be hard-coded in
+//! `SearchEngine::search`. Default priorities keep the historical order for
+//! score ties. Providers that only run on explicit syntax (calculator, units,
+//! clipboard, files, emoji) carry a large weight so their results lead.
+
+use std::sync::{Arc, RwLock};
+
+use crate::config::{AliasConfig, QuicklinkConfig};
+use crate::executor::SystemCommand;
+use crate::search::provider::{SearchContext, SearchProvider};
+use crate::search::query::Scope;
+use crate::search::scoring::{match_score, ScoredResult};
+use crate::search::{get_system_commands, SearchResult};
+use crate::services::custom_commands::ScriptEntry;
+use crate::services::*;
+
+/// Weight for providers triggered by explicit query syntax
+const TRIGGERED_WEIGHT: i64 = 1000;
+
+/// User-defined aliases that launch a shell command
+pub struct AliasProvider {
+    aliases: Vec<AliasConfig>,
+}
+
+impl AliasProvider {
+    pub fn new(aliases: Vec<AliasConfig>) -> Self {
+        Self { aliases }
+    }
+}
+
+impl SearchProvider for AliasProvider {
+    fn name(&self) -> &str {
+        "aliases"
+    }
+
+    fn priority(&self) -> i32 {
+        100
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        self.aliases
+            .iter()
+            .filter_map(|alias| {
+                let score = if alias.keyword.to_lowercase() == ctx.keyword {
+                    0
+                } else {
+                    match_score(
+                        &ctx.query_lower,
+                        &alias.name,
+                        [alias.keyword.as_str()],
+                        None,
+                    )?
+                };
+                Some(ScoredResult::new(
+                    SearchResult::Alias {
+                        keyword: alias.keyword.clone(),
+                        name: alias.name.clone(),
+                        target: alias.target.clone(),
+                        icon: alias.icon.clone(),
+                    },
+                    score,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Inline math evaluation
+pub struct CalculatorProvider;
+
+impl SearchProvider for CalculatorProvider {
+    fn name(&self) -> &str {
+        "calculator"
+    }
+
+    fn priority(&self) -> i32 {
+        95
+    }
+
+    fn weight(&self) -> i64 {
+        TRIGGERED_WEIGHT
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        calculator::evaluate(&ctx.query)
+            .map(|value| {
+                ScoredResult::new(
+                    SearchResult::Calculation {
+                        expression: ctx.query.clone(),
+                        result: format!("= {}", calculator::format_result(value)),
+                    },
+                    0,
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Unit conversions such as "10 km to miles"
+pub struct UnitsProvider;
+
+impl SearchProvider for UnitsProvider {
+    fn name(&self) -> &str {
+        "units"
+    }
+
+    fn priority(&self) -> i32 {
+        90
+    }
+
+    fn weight(&self) -> i64 {
+        TRIGGERED_WEIGHT
+    }
+
+    fn should_search(&self, ctx: &SearchContext) -> bool {
+        ctx.query.contains(" to ")
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        units::convert(&ctx.query)
+            .map(|conversion| {
+                ScoredResult::new(
+                    SearchResult::UnitConversion {
+                        display: conversion.display(),
+                        result: conversion.result(),
+                    },
+                    0,
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Clipboard history, for queries in the clipboard scope ("clip", "paste", ...)
+pub struct ClipboardProvider;
+
+impl SearchProvider for ClipboardProvider {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn priority(&self) -> i32 {
+        85
+    }
+
+    fn weight(&self) -> i64 {
+        TRIGGERED_WEIGHT
+    }
+
+    fn should_search(&self, ctx: &SearchContext) -> bool {
+        ctx.parsed.scope == Some(Scope::Clipboard)
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        let items = if ctx.query.trim().is_empty() {
+            ctx.clipboard.all()
+        } else {
+            ctx.clipboard.search(&ctx.query_lower)
+        };
+
+        items
+            .iter()
+            .take(10)
+            .enumerate()
+            .map(|(idx, entry)| {
+                ScoredResult::new(
+                    SearchResult::ClipboardItem {
+                        index: idx,
+                        content: entry.content.clone(),
+                        preview: entry.preview(60),
+                        time_ago: entry.time_ago(),
+                    },
+                    0,
+                )
+            })
+            .collect()
+    }
+}
+
+/// File system search for queries in the files scope (`~`, `/`, `file:`)
+pub struct FileProvider;
+
+impl SearchProvider for FileProvider {
+    fn name(&self) -> &str {
+        "files"
+    }
+
+    fn priority(&self) -> i32 {
+        80
+    }
+
+    fn weight(&self) -> i64 {
+        TRIGGERED_WEIGHT
+    }
+
+    fn is_slow(&self) -> bool {
+        true
+    }
+
+    fn should_search(&self, ctx: &SearchContext) -> bool {
+        ctx.parsed.scope == Some(Scope::Files)
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        file_search::search_files(&ctx.query, 10, ctx.cancelled)
+            .into_iter()
+            .map(|entry| {
+                let icon_prefix = if entry.is_dir { "[D] " } else { "" };
+                ScoredResult::new(
+                    SearchResult::FileResult {
+                        name: format!("{}{}", icon_prefix, entry.display_name()),
+                        path: entry.display_path(),
+                        is_dir: entry.is_dir,
+                    },
+                    0,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Emoji picker for queries in the emoji scope (`:`, `emoji:`)
+pub struct EmojiProvider;
+
+impl SearchProvider for EmojiProvider {
+    fn name(&self) -> &str {
+        "emoji"
+    }
+
+    fn priority(&self) -> i32 {
+        75
+    }
+
+    fn weight(&self) -> i64 {
+        TRIGGERED_WEIGHT
+    }
+
+    fn should_search(&self, ctx: &SearchContext) -> bool {
+        ctx.parsed.scope == Some(Scope::Emoji)
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        emoji::search(&ctx.query, 10)
+            .into_iter()
+            .map(|e| {
+                ScoredResult::new(
+                    SearchResult::EmojiResult {
+                        emoji: e.char.to_string(),
+                        name: format!("{} {}", e.char, e.name()),
+                        aliases: e.aliases(),
+                    },
+                    0,
+                )
+            })
+            .collect()
+    }
+}
+
+/// URL shortcuts, optionally taking a search query
+pub struct QuicklinkProvider {
+    quicklinks: Vec<QuicklinkConfig>,
+}
+
+impl QuicklinkProvider {
+    pub fn new(quicklinks: Vec<QuicklinkConfig>) -> Self {
+        Self { quicklinks }
+    }
+}
+
+impl SearchProvider for QuicklinkProvider {
+    fn name(&self) -> &str {
+        "quicklinks"
+    }
+
+    fn priority(&self) -> i32 {
+        70
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        let mut results = Vec::new();
+
+        for quicklink in &self.quicklinks {
+            let ql_keyword = quicklink.keyword.to_lowercase();
+
+            if ql_keyword == ctx.keyword {
+                let result = if quicklink.has_query_placeholder() {
+                    if let Some(ref q) = ctx.remaining {
+                        SearchResult::QuicklinkWithQuery {
+                            keyword: quicklink.keyword.clone(),
+                            name: format!("{}: {}", quicklink.name, q),
+                            url: quicklink.url.clone(),
+                            query: q.clone(),
+                            resolved_url: quicklink.resolve_url(q),
+                        }
+                    } else {
+                        SearchResult::Quicklink {
+                            keyword: quicklink.keyword.clone(),
+                            name: format!("{} (type to search)", quicklink.name),
+                            url: quicklink.url.clone(),
+                            has_query: true,
+                        }
+                    }
+                } else {
+                    SearchResult::Quicklink {
+                        keyword: quicklink.keyword.clone(),
+                        name: quicklink.name.clone(),
+                        url: quicklink.url.clone(),
+                        has_query: false,
+                    }
+                };
+                results.push(ScoredResult::new(result, 0));
+            } else if let Some(score) = match_score(
+                &ctx.query_lower,
+                &quicklink.name,
+                [quicklink.keyword.as_str()],
+                None,
+            ) {
+                results.push(ScoredResult::new(
+                    SearchResult::Quicklink {
+                        keyword: quicklink.keyword.clone(),
+                        name: quicklink.name.clone(),
+                        url: quicklink.url.clone(),
+                        has_query: quicklink.has_query_placeholder(),
+                    },
+                    score,
+                ));
+            }
+        }
+
+        results
+    }
+}
+
+/// Scripts from the user's scripts directory
+pub struct ScriptProvider {
+    scripts: Vec<ScriptEntry>,
+}
+
+impl ScriptProvider {
+    pub fn new(scripts: Vec<ScriptEntry>) -> Self {
+        Self { scripts }
+    }
+}
+
+impl SearchProvider for ScriptProvider {
+    fn name(&self) -> &str {
+        "scripts"
+    }
+
+    fn priority(&self) -> i32 {
+        65
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        let mut results = Vec::new();
+
+        for script in &self.scripts {
+            let keyword_hit = script.has_argument && script.id.to_lowercase() == ctx.keyword;
+            let score = if keyword_hit {
+                Some(0)
+            } else {
+                match_score(
+                    &ctx.query_lower,
+                    &script.name,
+                    std::iter::once(script.id.as_str())
+                        .chain(script.keywords.iter().map(|k| k.as_str())),
+                    None,
+                )
+            };
+
+            let Some(score) = score else {
+                continue;
+            };
+
+            let result = if script.has_argument {
+                match ctx.remaining {
+                    Some(ref arg) if keyword_hit => SearchResult::ScriptWithArgument {
+                        id: script.id.clone(),
+                        name: format!("{}: {}", script.name, arg),
+                        description: script.description.clone(),
+                        icon: script.icon.clone(),
+                        path: script.path.clone(),
+                        argument: arg.clone(),
+                        output_mode: script.output_mode.clone(),
+                        destructive: script.destructive,
+                    },
+                    _ => SearchResult::Script {
+                        id: script.id.clone(),
+                        name: format!("{} (type argument)", script.name),
+                        description: script.description.clone(),
+                        icon: script.icon.clone(),
+                        path: script.path.clone(),
+                        has_argument: true,
+                        output_mode: script.output_mode.clone(),
+                        destructive: script.destructive,
+                    },
+                }
+            } else {
+                SearchResult::Script {
+                    id: script.id.clone(),
+                    name: script.name.clone(),
+                    description: script.description.clone(),
+                    icon: script.icon.clone(),
+                    path: script.path.clone(),
+                    has_argument: false,
+                    output_mode: script.output_mode.clone(),
+                    destructive: script.destructive,
+                }
+            };
+            results.push(ScoredResult::new(result, score));
+        }
+
+        results
+    }
+}
+
+/// Nova and system commands
+pub struct SystemCommandProvider {
+    supported: Arc<RwLock<Vec<SystemCommand>>>,
+}
+
+impl SystemCommandProvider {
+    /// Create a provider listing the system commands in `supported`, which
+    /// the engine updates once the platform reports them
+    pub fn new(supported: Arc<RwLock<Vec<SystemCommand>>>) -> Self {
+        Self { supported }
+    }
+}
+
+impl SearchProvider for SystemCommandProvider {
+    fn name(&self) -> &str {
+        "system"
+    }
+
+    fn priority(&self) -> i32 {
+        60
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        get_system_commands(&self.supported.read().unwrap())
+            .into_iter()
+            .filter_map(|cmd| {
+                let score = match_score(&ctx.query_lower, cmd.name(), [], cmd.description())?;
+                Some(ScoredResult::new(cmd, score))
+            })
+            .collect()
+    }
+}
+
+/// Commands contributed by installed extensions
+pub struct ExtensionCommandProvider {
+    manager: Arc<ExtensionManager>,
+}
+
+impl ExtensionCommandProvider {
+    pub fn new(manager: Arc<ExtensionManager>) -> Self {
+        Self { manager }
+    }
+}
+
+impl SearchProvider for ExtensionCommandProvider {
+    fn name(&self) -> &str {
+        "extensions"
+    }
+
+    fn priority(&self) -> i32 {
+        55
+    }
+
+    fn is_slow(&self) -> bool {
+        true
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        let mut results = Vec::new();
+
+        for cmd in self.manager.commands() {
+            if cmd.keyword.to_lowercase() == ctx.keyword {
+                let result = match ctx.remaining {
+                    Some(ref arg) if cmd.has_argument => SearchResult::ExtensionCommandWithArg {
+                        command: cmd.clone(),
+                        argument: arg.clone(),
+                    },
+                    _ => SearchResult::ExtensionCommand {
+                        command: cmd.clone(),
+                    },
+                };
+                results.push(ScoredResult::new(result, 0));
+            } else if let Some(score) = match_score(
+                &ctx.query_lower,
+                &cmd.name,
+                [cmd.keyword.as_str()],
+                Some(&cmd.description),
+            ) {
+                results.push(ScoredResult::new(
+                    SearchResult::ExtensionCommand {
+                        command: cmd.clone(),
+                    },
+                    score,
+                ));
+            }
+        }
+
+        results
+    }
+}
+
+/// Installed applications
+pub struct AppProvider;
+
+impl SearchProvider for AppProvider {
+    fn name(&self) -> &str {
+        "apps"
+    }
+
+    fn priority(&self) -> i32 {
+        50
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        let mut results = Vec::new();
+
+        for app in ctx.apps {
+            let app_score = match_score(
+                &ctx.query_lower,
+                &app.name,
+                app.keywords.iter().map(|k| k.as_str()),
+                app.description.as_deref(),
+            );
+            if let Some(score) = app_score {
+                let windows = ctx
+                    .windows
+                    .iter()
+                    .filter(|w| w.belongs_to(app))
+                    .cloned()
+                    .collect();
+                results.push(ScoredResult::new(app.result_with_windows(windows), score));
+            }
+
+            // Actions only show up once something is typed
+            if ctx.query.trim().is_empty() {
+                continue;
+            }
+            for action in &app.actions {
+                let result = app.action_result(action);
+                let Some(mut score) = match_score(
+                    &ctx.query_lower,
+                    result.name(),
+                    [action.name.as_str()],
+                    None,
+                ) else {
+                    continue;
+                };
+                // Keep actions right below their app when both match
+                if let Some(app_score) = app_score {
+                    score = score.min(app_score - 1);
+                }
+                results.push(ScoredResult::new(result, score));
+            }
+        }
+
+        results
+    }
+}
+
+/// Open windows, matched by title and by the app that opened them
+pub struct WindowProvider;
+
+impl SearchProvider for WindowProvider {
+    fn name(&self) -> &str {
+        "windows"
+    }
+
+    fn priority(&self) -> i32 {
+        45
+    }
+
+    fn should_search(&self, ctx: &SearchContext) -> bool {
+        // Listing every window would crowd out the default results
+        !ctx.query.trim().is_empty()
+    }
+
+    fn search(&self, ctx: &SearchContext) -> Vec<ScoredResult> {
+        ctx.windows
+            .iter()
+            .filter_map(|window| {
+                let app = ctx.apps.iter().find(|app| window.belongs_to(app));
+                let result = window.to_result(app);
+                let app_name = app.map(|app| app.name.as_str());
+                let score = match_score(
+                    &ctx.query_lower,
+                    &window.title,
+                    app_name.into_iter().chain([window.app_id.as_str()]),
+                    window.workspace.as_deref(),
+                )?;
+                Some(ScoredResult::new(result, score))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::ExecutionAction;
+    use crate::search::{AppAction, OpenWindow, PlatformAppEntry, ProviderRegistry};
+    use crate::services::clipboard::ClipboardHistory;
+
+    fn app(name: &str) -> PlatformAppEntry {
+        PlatformAppEntry {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            exec: name.to_lowercase(),
+            icon: None,
+            description: None,
+            keywords: Vec::new(),
+            desktop_file: None,
+            terminal: false,
+            actions: Vec::new(),
+        }
+    }
+
+    fn registry() -> ProviderRegistry {
+        let mut registry = ProviderRegistry::new();
+        registry.register(Box::new(AliasProvider::new(vec![AliasConfig {
+            keyword: "cf".to_string(),
+            name: "Campfire Notes".to_string(),
+            target: "notes".to_string(),
+            icon: None,
+        }])));
+        registry.register(Box::new(AppProvider));
+        registry
+    }
+
+    #[test]
+    fn test_exact_app_name_beats_alias_substring() {
+        let apps = vec![app("Files"), app("Firefox")];
+        let clipboard = ClipboardHistory::new(1);
+        let ctx = SearchContext::new("fire", 8, &apps, &clipboard);
+
+        let results = registry().search(&ctx);
+        assert_eq!(results[0].result.name(), "Firefox");
+        assert_eq!(results[1].result.name(), "Campfire Notes");
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_exact_keyword_wins() {
+        let apps = vec![app("Cfdisk")];
+        let clipboard = ClipboardHistory::new(1);
+        let ctx = SearchContext::new("cf", 8, &apps, &clipboard);
+
+        let results = registry().search(&ctx);
+        assert_eq!(results[0].result.name(), "Campfire Notes");
+    }
+
+    #[test]
+    fn test_desktop_actions() {
+        let mut firefox = app("Firefox");
+        firefox.actions = vec![AppAction {
+            id: "new-private-window".to_string(),
+            name: "New Private Window".to_string(),
+            exec: "firefox --private-window %u".to_string(),
+            icon: None,
+        }];
+        let apps = vec![firefox];
+        let clipboard = ClipboardHistory::new(1);
+        let names = |query: &str| -> Vec<String> {
+            let ctx = SearchContext::new(query, 8, &apps, &clipboard);
+            AppProvider
+                .search(&ctx)
+                .into_iter()
+                .map(|r| r.result.name().to_string())
+                .collect()
+        };
+
+        assert_eq!(names("private"), vec!["Firefox: New Private Window"]);
+        let mut ranked = AppProvider.search(&SearchContext::new("firefox", 8, &apps, &clipboard));
+        ranked.sort_by_key(|r| std::cmp::Reverse(r.score));
+        assert_eq!(ranked[0].result.name(), "Firefox");
+        assert_eq!(ranked[1].result.name(), "Firefox: New Private Window");
+        assert_eq!(names(""), vec!["Firefox"]);
+    }
+
+    #[test]
+    fn test_open_windows() {
+        let mut files = app("Files");
+        files.id = "org.gnome.Nautilus".to_string();
+        files.exec = "nautilus --new-window".to_string();
+        let apps = vec![app("Firefox"), files];
+        let window = |id: &str, title: &str, app_id: &str| OpenWindow {
+            id: id.to_string(),
+            title: title.to_string(),
+            app_id: app_id.to_string(),
+            workspace: Some("2".to_string()),
+        };
+        let windows = vec![
+            window("1", "GitHub \u{2014} Mozilla Firefox", "firefox"),
+            window("2", "Downloads", "org.gnome.Nautilus"),
+        ];
+        let clipboard = ClipboardHistory::new(1);
+        let ctx = |query| SearchContext::new(query, 8, &apps, &clipboard).with_windows(&windows);
+
+        let results = WindowProvider.search(&ctx("down"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result.name(), "Downloads");
+        assert_eq!(
+            results[0].result.description(),
+            Some("Files \u{2014} Workspace 2")
+        );
+        assert!(matches!(
+            results[0].result.execution_action(),
+            ExecutionAction::FocusWindow { id } if id == "2"
+        ));
+        // Windows also match by the app that opened them
+        assert_eq!(WindowProvider.search(&ctx("files")).len(), 1);
+        assert!(!WindowProvider.should_search(&ctx("")));
+
+        // A running app offers to switch to its window, and still launches
+        let firefox = AppProvider.search(&ctx("firefox")).remove(0).result;
+        assert!(matches!(
+            firefox.execution_action(),
+            ExecutionAction::LaunchApp { .. }
+        ));
+        let actions = firefox.actions();
+        assert_eq!(
+            actions[0].title,
+            "Switch to GitHub \u{2014} Mozilla Firefox"
+        );
+        assert!(matches!(
+            &actions[0].action,
+            ExecutionAction::FocusWindow { id } if id == "1"
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! Query syntax: scope prefixes, negated terms and quoted phrases
+//!
+//! A query may start with a scope prefix such as `app:` or `file:` to search
+//! a single source. Within the rest, `-term` drops results mentioning the
+//! term and `"some phrase"` keeps only results containing the phrase.
+//! Prefixes are configurable per scope; an empty list disables a scope's
+//! prefixes.
+
+use crate::config::SearchConfig;
+use crate::matcher;
+use crate::search::SearchResult;
+
+/// A source a query can be narrowed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Apps,
+    Files,
+    Extensions,
+    Clipboard,
+    Emoji,
+}
+
+impl Scope {
+    const ALL: [Scope; 5] = [
+        Scope::Apps,
+        Scope::Files,
+        Scope::Extensions,
+        Scope::Clipboard,
+        Scope::Emoji,
+    ];
+
+    /// Name used in config, which is also the name of the provider
+    /// searching the scope (e.g., "files")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Apps => "apps",
+            Scope::Files => "files",
+            Scope::Extensions => "extensions",
+            Scope::Clipboard => "clipboard",
+            Scope::Emoji => "emoji",
+        }
+    }
+
+    /// Look up a scope by its config name
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.name() == name)
+    }
+
+    /// Prefixes used when the config doesn't set any
+    fn default_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            Scope::Apps => &["app:"],
+            Scope::Files => &["file:", "~", "/"],
+            Scope::Extensions => &["ext:"],
+            Scope::Clipboard => &["clip:", "clip", "clipboard", "paste", "history"],
+            Scope::Emoji => &["emoji:", ":"],
+        }
+    }
+}
+
+/// A query split into its scope, search text and filters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// Source the query is narrowed to, if it had a scope prefix
+    pub scope: Option<Scope>,
+    /// Text to match, without the prefix, negated terms or quote marks
+    pub text: String,
+    /// Quoted phrases results must contain
+    pub phrases: Vec<String>,
+    /// Negated terms results must not contain
+    pub excluded: Vec<String>,
+}
+
+impl ParsedQuery {
+    /// Check a result against the quoted phrases and negated terms
+    pub fn accepts(&self, result: &SearchResult) -> bool {
+        if self.phrases.is_empty() && self.excluded.is_empty() {
+            return true;
+        }
+
+        let haystack = matcher::fold(&format!(
+            "{} {}",
+            result.name(),
+            result.description().unwrap_or_default()
+        ));
+        self.phrases.iter().all(|p| haystack.contains(p.as_str()))
+            && !self.excluded.iter().any(|t| haystack.contains(t.as_str()))
+    }
+}
+
+/// Scope prefixes in effect, longest first
+#[derive(Debug, Clone)]
+pub struct QuerySyntax {
+    prefixes: Vec<(String, Scope)>,
+}
+
+impl Default for QuerySyntax {
+    fn default() -> Self {
+        Self::from_config(&SearchConfig::default())
+    }
+}
+
+impl QuerySyntax {
+    pub fn from_config(config: &SearchConfig) -> Self {
+        for name in config.scope_prefixes.keys() {
+            if Scope::parse(name).is_none() {
+                eprintln!("[Nova] Unknown search scope: {}", name);
+            }
+        }
+
+        let mut prefixes: Vec<(String, Scope)> = Scope::ALL
+            .into_iter()
+            .flat_map(|scope| {
+                let names: Vec<String> = match config.scope_prefixes.get(scope.name()) {
+                    Some(names) => names.iter().map(|p| p.to_lowercase()).collect(),
+                    None => scope
+                        .default_prefixes()
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect(),
+                };
+                names
+                    .into_iter()
+                    .filter(|p| !p.is_empty())
+                    .map(move |p| (p, scope))
+            })
+            .collect();
+
+        // Longest first, so "clipboard" wins over "clip"
+        prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Self { prefixes }
+    }
+
+    /// Split a query into scope, search text and filters
+    pub fn parse(&self, query: &str) -> ParsedQuery {
+        let Some((prefix, scope)) = self.find_prefix(query) else {
+            return parse_terms(None, query);
+        };
+
+        // Path prefixes stay part of the path being searched
+        if scope == Scope::Files && (prefix.starts_with('~') || prefix.starts_with('/')) {
+            return ParsedQuery {
+                scope: Some(scope),
+                text: query.to_string(),
+                ..Default::default()
+            };
+        }
+
+        parse_terms(Some(scope), query[prefix.len()..].trim_start())
+    }
+
+    fn find_prefix(&self, query: &str) -> Option<(&str, Scope)> {
+        self.prefixes.iter().find_map(|(prefix, scope)| {
+            let head = query.get(..prefix.len())?;
+            if !head.eq_ignore_ascii_case(prefix) {
+                return None;
+            }
+
+            // Word prefixes ("clip") need a space after them; "clip:" doesn't
+            let is_word = prefix.chars().all(char::is_alphanumeric);
+            let rest = &query[prefix.len()..];
+            if is_word && !(rest.is_empty() || rest.starts_with(' ')) {
+                return None;
+            }
+            Some((prefix.as_str(), *scope))
+        })
+    }
+}
+
+/// Pull quoted phrases and negated terms out of `text`.
+///
+/// Text without either is kept verbatim, spacing included.
+fn parse_terms(scope: Option<Scope>, text: &str) -> ParsedQuery {
+    let mut words: Vec<String> = Vec::new();
+    let mut phrases = Vec::new();
+    let mut excluded = Vec::new();
+
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let (phrase, after) = quoted.split_once('"').unwrap_or((quoted, ""));
+            if !phrase.trim().is_empty() {
+                phrases.push(matcher::fold(phrase.trim()));
+                words.push(phrase.trim().to_string());
+            }
+            rest = after;
+            continue;
+        }
+
+        let end = rest.find(' ').unwrap_or(rest.len());
+        let word = &rest[..end];
+        // "-5" is a number, not a negation
+        match word.strip_prefix('-') {
+            Some(term) if term.starts_with(char::is_alphabetic) => {
+                excluded.push(matcher::fold(term));
+            }
+            _ => words.push(word.to_string()),
+        }
+        rest = &rest[end..];
+    }
+
+    let text = if phrases.is_empty() && excluded.is_empty() {
+        text.to_string()
+    } else {
+        words.join(" ")
+    };
+
+    ParsedQuery {
+        scope,
+        text,
+        phrases,
+        excluded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse(query: &str) -> ParsedQuery {
+        QuerySyntax::default().parse(query)
+    }
+
+    #[test]
+    fn test_plain_query_is_verbatim() {
+        let parsed = parse("yt  cats ");
+        assert_eq!(parsed.scope, None);
+        assert_eq!(parsed.text, "yt  cats ");
+    }
+
+    #[test]
+    fn test_scope_prefixes() {
+        assert_eq!(parse("app:fire").scope, Some(Scope::Apps));
+        assert_eq!(parse("app:fire").text, "fire");
+        assert_eq!(parse("EMOJI: smile").text, "smile");
+        assert_eq!(parse(":smile").scope, Some(Scope::Emoji));
+        assert_eq!(parse("clipboard foo").text, "foo");
+        assert_eq!(parse("clip").scope, Some(Scope::Clipboard));
+        // Word prefixes must stand alone
+        assert_eq!(parse("clipper").scope, None);
+        assert_eq!(parse("historyx").scope, None);
+    }
+
+    #[test]
+    fn test_path_prefix_is_kept() {
+        let parsed = parse("~/my-notes");
+        assert_eq!(parsed.scope, Some(Scope::Files));
+        assert_eq!(parsed.text, "~/my-notes");
+        assert!(parsed.excluded.is_empty());
+    }
+
+    #[test]
+    fn test_negation_and_phrases() {
+        let parsed = parse("app:\"Visual Studio\" -insiders code");
+        assert_eq!(parsed.scope, Some(Scope::Apps));
+        assert_eq!(parsed.text, "Visual Studio code");
+        assert_eq!(parsed.phrases, vec!["visual studio"]);
+        assert_eq!(parsed.excluded, vec!["insiders"]);
+
+        // Minus signs in math are not negations
+        assert_eq!(parse("10 - -5").text, "10 - -5");
+        assert!(parse("10 - -5").excluded.is_empty());
+    }
+
+    #[test]
+    fn test_accepts() {
+        let result = |name: &str| SearchResult::Alias {
+            keyword: "x".to_string(),
+            name: name.to_string(),
+            target: String::new(),
+            icon: None,
+        };
+        let parsed = parse("\"studio code\" -insiders");
+        assert!(parsed.accepts(&result("Visual Studio Code")));
+        assert!(!parsed.accepts(&result("Visual Studio Code Insiders")));
+        assert!(!parsed.accepts(&result("Android Studio")));
+    }
+
+    #[test]
+    fn test_remap_and_disable() {
+        let syntax = QuerySyntax::from_config(&SearchConfig {
+            scope_prefixes: HashMap::from([
+                ("files".to_string(), vec!["f:".to_string()]),
+                ("emoji".to_string(), Vec::new()),
+            ]),
+            ..Default::default()
+        });
+
+        assert_eq!(syntax.parse("f:notes").scope, Some(Scope::Files));
+        assert_eq!(syntax.parse("file:notes").scope, None);
+        assert_eq!(syntax.parse("~/notes").scope, None);
+        assert_eq!(syntax.parse(":smile").scope, None);
+        assert_eq!(syntax.parse("app:fire").scope, Some(Scope::Apps));
+    }
+}
@@ -0,0 +1,196 @@
+//! Shared scoring used to rank results from every provider on one scale
+
+use std::collections::HashSet;
+
+use crate::matcher::{self, Pattern};
+use crate::search::SearchResult;
+
+/// Bonus for results whose keyword equals the first word of the query
+pub const EXACT_KEYWORD_BONUS: i64 = 500;
+
+/// Bonus for names starting with the query
+pub const PREFIX_BONUS: i64 = 100;
+
+/// Score of pinned favourites, which always lead the results
+pub const PINNED_SCORE: i64 = i64::MAX;
+
+/// Score of fallback commands, which only fill slots nothing else took
+pub const FALLBACK_SCORE: i64 = i64::MIN;
+
+/// A search result together with its ranking score
+#[derive(Debug, Clone)]
+pub struct ScoredResult {
+    pub result: SearchResult,
+    pub score: i64,
+}
+
+impl ScoredResult {
+    pub fn new(result: SearchResult, score: i64) -> Self {
+        Self { result, score }
+    }
+}
+
+/// Match quality of `text` against the query, see [`matcher::Pattern::score`]
+pub fn fuzzy_score(text: &str, query_lower: &str) -> Option<i64> {
+    matcher::score(text, query_lower)
+}
+
+/// Best match over a result's name, keywords and description.
+///
+/// Names starting with the query get a prefix boost and descriptions count
+/// half, so a direct name hit always wins over an incidental one.
+pub fn match_score<'a>(
+    query_lower: &str,
+    name: &str,
+    keywords: impl IntoIterator<Item = &'a str>,
+    description: Option<&str>,
+) -> Option<i64> {
+    let pattern = Pattern::new(query_lower);
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let name_score = pattern.score(name).map(|score| {
+        if pattern.is_prefix_of(name) {
+            score + PREFIX_BONUS
+        } else {
+            score
+        }
+    });
+
+    let keyword_score = keywords
+        .into_iter()
+        .filter_map(|kw| pattern.score(kw))
+        .max();
+
+    let desc_score = description.and_then(|d| pattern.score(d)).map(|s| s / 2);
+
+    [name_score, keyword_score, desc_score]
+        .into_iter()
+        .flatten()
+        .max()
+}
+
+/// Merge result batches into one ranking.
+///
+/// Sorts by score (stable, so earlier batches win ties), drops later
+/// duplicates of the same result id and keeps at most `limit` results plus
+/// any pinned ones.
+pub fn merge_results(mut results: Vec<ScoredResult>, limit: usize) -> Vec<ScoredResult> {
+    results.sort_by_key(|r| std::cmp::Reverse(r.score));
+
+    let mut seen = HashSet::new();
+    results.retain(|r| seen.insert(r.result.id()));
+
+    let pinned = results.iter().filter(|r| r.score == PINNED_SCORE).count();
+    results.truncate(limit.max(pinned));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        assert_eq!(match_score("", "Firefox", [], None), Some(0));
+    }
+
+    #[test]
+    fn test_prefix_beats_infix() {
+        let prefix = match_score("fire", "Firefox", [], None).unwrap();
+        let infix = match_score("fire", "Campfire Notes", [], None).unwrap();
+        assert!(prefix > infix);
+    }
+
+    #[test]
+    fn test_description_counts_half() {
+        let by_name = match_score("term", "Terminal", [], None).unwrap();
+        let by_desc = match_score("term", "Console", [], Some("Terminal emulator")).unwrap();
+        assert!(by_name > by_desc);
+    }
+
+    #[test]
+    fn test_acronym_and_accents() {
+        let acronym = match_score("vsc", "Visual Studio Code", [], None).unwrap();
+        let scattered = match_score("vsc", "Voice Scanner", [], None).unwrap();
+        assert!(acronym > scattered);
+        assert!(match_score("resume", "Résumé.pdf", [], None).is_some());
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(
+            match_score("xyz", "Firefox", ["browser"], Some("Web")),
+            None
+        );
+    }
+
+    fn alias(name: &str) -> SearchResult {
+        SearchResult::Alias {
+            keyword: name.to_string(),
+            name: name.to_string(),
+            target: String::new(),
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let merged = merge_results(
+            vec![
+                ScoredResult::new(alias("a"), 10),
+                ScoredResult::new(alias("b"), 5),
+                ScoredResult::new(alias("c"), 20),
+                ScoredResult::new(alias("a"), 30),
+            ],
+            2,
+        );
+        let names: Vec<&str> = merged.iter().map(|r| r.result.name()).collect();
+        assert_eq!(names, vec!["a", "c"]);
+        assert_eq!(merged[0].score, 30);
+    }
+
+    #[test]
+    fn test_merge_keeps_pins_beyond_limit() {
+        let merged = merge_results(
+            vec![
+                ScoredResult::new(alias("a"), PINNED_SCORE),
+                ScoredResult::new(alias("b"), PINNED_SCORE),
+                ScoredResult::new(alias("c"), 1),
+            ],
+            1,
+        );
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_keeps_fallback_of_listed_quicklink() {
+        let quicklink = SearchResult::Quicklink {
+            keyword: "g".to_string(),
+            name: "Google".to_string(),
+            url: "https://google.com/search?q={query}".to_string(),
+            has_query: true,
+        };
+        let fallback = SearchResult::Fallback {
+            result: Box::new(SearchResult::QuicklinkWithQuery {
+                keyword: "g".to_string(),
+                name: "Google".to_string(),
+                url: "https://google.com/search?q={query}".to_string(),
+                query: "g".to_string(),
+                resolved_url: "https://google.com/search?q=g".to_string(),
+            }),
+        };
+        assert_eq!(fallback.id().to_string(), "fallback:ql:g");
+
+        let merged = merge_results(
+            vec![
+                ScoredResult::new(quicklink, 10),
+                ScoredResult::new(fallback, FALLBACK_SCORE),
+            ],
+            10,
+        );
+        let ids: Vec<String> = merged.iter().map(|r| r.result.id().to_string()).collect();
+        assert_eq!(ids, vec!["ql:g", "fallback:ql:g"]);
+    }
+}
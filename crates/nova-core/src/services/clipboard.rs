@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
 use std::time::Instant;
 
+use crate::matcher::Pattern;
+
 /// Entry in clipboard history
 #[derive(Debug, Clone)]
 pub struct ClipboardEntry {
@@ -40,6 +42,7 @@ impl ClipboardEntry {
 ///
 /// The UI layer is responsible for feeding clipboard changes via `add()`.
 /// Polling the system clipboard is handled by the platform layer.
+#[derive(Clone)]
 pub struct ClipboardHistory {
     items: VecDeque<ClipboardEntry>,
     max_items: usize,
@@ -84,12 +87,12 @@ impl ClipboardHistory {
         }
     }
 
-    /// Search items by content
+    /// Search items by content, ignoring case and accents
     pub fn search(&self, query: &str) -> Vec<&ClipboardEntry> {
-        let query_lower = query.to_lowercase();
+        let pattern = Pattern::new(query);
         self.items
             .iter()
-            .filter(|item| item.content.to_lowercase().contains(&query_lower))
+            .filter(|item| pattern.is_contained_in(&item.content))
             .collect()
     }
 
@@ -22,6 +22,8 @@ pub struct ScriptEntry {
     pub keywords: Vec<String>,
     pub has_argument: bool,
     pub output_mode: ScriptOutputMode,
+    /// Confirmed before it runs, like destructive system commands
+    pub destructive: bool,
 }
 
 pub struct CustomCommandsIndex {
@@ -103,6 +105,10 @@ impl CustomCommandsIndex {
                 Some("inline") => ScriptOutputMode::Inline,
                 _ => ScriptOutputMode::Silent,
             },
+            destructive: metadata
+                .get("destructive")
+                .map(|v| v == "true")
+                .unwrap_or(false),
         })
     }
 
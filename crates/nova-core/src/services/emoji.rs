@@ -1,5 +1,4 @@
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
+use crate::matcher::Pattern;
 
 /// An emoji entry with character and searchable names
 #[derive(Debug, Clone)]
@@ -22,25 +21,19 @@ impl Emoji {
 
 /// Search for emojis matching a query
 pub fn search(query: &str, max_results: usize) -> Vec<&'static Emoji> {
-    let query = query.trim().to_lowercase();
+    let pattern = Pattern::new(query);
 
-    if query.is_empty() {
+    if pattern.is_empty() {
         return EMOJIS.iter().take(max_results).collect();
     }
 
-    let matcher = SkimMatcherV2::default();
     let mut results: Vec<(i64, &'static Emoji)> = Vec::new();
 
     for emoji in EMOJIS.iter() {
-        let mut best_score: Option<i64> = None;
-        for name in emoji.names {
-            if let Some(score) = matcher.fuzzy_match(name, &query) {
-                best_score = Some(best_score.map_or(score, |s| s.max(score)));
-            }
-        }
+        let best_score = emoji.names.iter().filter_map(|n| pattern.score(n)).max();
 
         if let Some(score) = best_score {
-            let boosted = if emoji.names.iter().any(|n| n.starts_with(&query)) {
+            let boosted = if emoji.names.iter().any(|n| pattern.is_prefix_of(n)) {
                 score + 100
             } else {
                 score
@@ -49,7 +42,7 @@ pub fn search(query: &str, max_results: usize) -> Vec<&'static Emoji> {
         }
     }
 
-    results.sort_by(|a, b| b.0.cmp(&a.0));
+    results.sort_by_key(|r| std::cmp::Reverse(r.0));
     results
         .into_iter()
         .take(max_results)
@@ -57,6 +50,11 @@ pub fn search(query: &str, max_results: usize) -> Vec<&'static Emoji> {
         .collect()
 }
 
+/// Look up an emoji by its character
+pub fn find(emoji: &str) -> Option<&'static Emoji> {
+    EMOJIS.iter().find(|e| e.char == emoji)
+}
+
 /// Common emojis with searchable names
 static EMOJIS: &[Emoji] = &[
     Emoji { char: "\u{1f600}", names: &["grinning", "smile", "happy"] },
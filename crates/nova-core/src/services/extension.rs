@@ -28,6 +28,7 @@ pub enum ExtensionKind {
         has_argument: bool,
         output_mode: ScriptOutputMode,
         description: String,
+        destructive: bool,
     },
 }
 
@@ -108,6 +109,7 @@ impl ExtensionIndex {
                     has_argument: script.has_argument,
                     output_mode: script.output_mode.clone(),
                     description: script.description.clone(),
+                    destructive: script.destructive,
                 },
             };
             by_keyword.insert(script.id.to_lowercase(), ext.clone());
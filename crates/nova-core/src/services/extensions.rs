@@ -41,6 +41,9 @@ pub struct ExtensionCommand {
     pub output: OutputMode,
     #[serde(default)]
     pub icon: Option<String>,
+    /// Confirmed before it runs, like destructive system commands
+    #[serde(default)]
+    pub destructive: bool,
 }
 
 fn default_output_mode() -> OutputMode {
@@ -87,6 +90,7 @@ pub struct LoadedCommand {
     pub has_argument: bool,
     pub output: OutputMode,
     pub icon_path: Option<PathBuf>,
+    pub destructive: bool,
 }
 
 /// Result item from extension script (JSON output)
@@ -198,6 +202,7 @@ impl ExtensionManager {
                     has_argument: cmd.has_argument,
                     output: cmd.output,
                     icon_path: cmd_icon.or_else(|| icon_path.clone()),
+                    destructive: cmd.destructive,
                 }
             })
             .collect();
@@ -219,6 +224,11 @@ impl ExtensionManager {
         &self.extensions
     }
 
+    /// Get all commands, in extension load order
+    pub fn commands(&self) -> impl Iterator<Item = &LoadedCommand> {
+        self.extensions.iter().flat_map(|ext| ext.commands.iter())
+    }
+
     /// Get a command by keyword
     pub fn get_command(&self, keyword: &str) -> Option<&LoadedCommand> {
         self.commands_by_keyword.get(&keyword.to_lowercase())
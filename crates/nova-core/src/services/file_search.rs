@@ -1,8 +1,9 @@
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use walkdir::WalkDir;
 
+use crate::matcher::Pattern;
+
 /// A file search result
 #[derive(Debug, Clone)]
 pub struct FileEntry {
@@ -33,8 +34,8 @@ impl FileEntry {
     }
 }
 
-/// Search for files matching a query
-pub fn search_files(query: &str, max_results: usize) -> Vec<FileEntry> {
+/// Search for files matching a query, giving up once `cancelled` is set
+pub fn search_files(query: &str, max_results: usize, cancelled: &AtomicBool) -> Vec<FileEntry> {
     let query = query.trim();
 
     let (base_path, search_term) = parse_query(query);
@@ -47,7 +48,7 @@ pub fn search_files(query: &str, max_results: usize) -> Vec<FileEntry> {
         return Vec::new();
     }
 
-    let matcher = SkimMatcherV2::default();
+    let pattern = Pattern::new(&search_term);
     let mut results: Vec<(i64, FileEntry)> = Vec::new();
 
     let max_depth = if search_term.is_empty() { 1 } else { 4 };
@@ -58,6 +59,9 @@ pub fn search_files(query: &str, max_results: usize) -> Vec<FileEntry> {
         .into_iter()
         .filter_map(|e| e.ok())
     {
+        if cancelled.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
         if entry.path() == base_path {
             continue;
         }
@@ -82,7 +86,7 @@ pub fn search_files(query: &str, max_results: usize) -> Vec<FileEntry> {
             continue;
         }
 
-        if let Some(score) = matcher.fuzzy_match(&file_name, &search_term) {
+        if let Some(score) = pattern.score(&file_name) {
             results.push((
                 score,
                 FileEntry {
@@ -94,7 +98,7 @@ pub fn search_files(query: &str, max_results: usize) -> Vec<FileEntry> {
         }
     }
 
-    results.sort_by(|a, b| b.0.cmp(&a.0));
+    results.sort_by_key(|r| std::cmp::Reverse(r.0));
     results
         .into_iter()
         .take(max_results)
@@ -189,4 +193,17 @@ mod tests {
         assert_eq!(path, Some(PathBuf::from("/tmp")));
         assert!(term.is_empty());
     }
+
+    #[test]
+    fn test_search_cancelled() {
+        let dir = std::env::temp_dir().join(format!("nova-files-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+        let query = format!("{}/no", dir.display());
+
+        let found = search_files(&query, 10, &AtomicBool::new(false));
+        let cancelled = search_files(&query, 10, &AtomicBool::new(true));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(cancelled.is_empty());
+    }
 }
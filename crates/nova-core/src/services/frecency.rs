@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of usage records kept on disk
+const MAX_RECORDS: usize = 2000;
+
+/// Upper bound for the frecency bonus of a single result
+pub const MAX_FRECENCY_BONUS: i64 = 300;
+
+/// One launch of a result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Lowercased query that was typed when the result was launched
+    pub query: String,
+    /// Identifier of the launched result
+    pub result_id: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Persistent launch history used to rank frequently and recently used results
+#[derive(Debug, Default)]
+pub struct FrecencyStore {
+    records: Vec<UsageRecord>,
+    path: Option<PathBuf>,
+    /// Thread writing the records to `path`, started by the first save
+    writer: Option<Sender<Vec<UsageRecord>>>,
+}
+
+impl FrecencyStore {
+    /// Get the usage file path (~/.local/share/nova/usage.json)
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .map(|h| h.join(".local/share"))
+                    .unwrap_or_else(|| PathBuf::from("/tmp"))
+            })
+            .join("nova")
+            .join("usage.json")
+    }
+
+    /// Load the store from the default path
+    pub fn load() -> Self {
+        Self::load_from(Self::default_path())
+    }
+
+    /// Load the store from a file, starting empty if it is missing.
+    ///
+    /// An invalid file is moved aside to `usage.json.bak` rather than
+    /// saved over; if that fails, the store isn't saved at all.
+    pub fn load_from(path: PathBuf) -> Self {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => {
+                return Self {
+                    path: Some(path),
+                    ..Self::default()
+                }
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(records) => Self {
+                records,
+                path: Some(path),
+                writer: None,
+            },
+            Err(e) => {
+                eprintln!("[Nova] Failed to parse usage history: {}", e);
+                let backup = path.with_extension("json.bak");
+                let path = match fs::rename(&path, &backup) {
+                    Ok(()) => Some(path),
+                    Err(e) => {
+                        eprintln!("[Nova] Usage history won't be saved: {}", e);
+                        None
+                    }
+                };
+                Self {
+                    path,
+                    ..Self::default()
+                }
+            }
+        }
+    }
+
+    /// Create a store that is never written to disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Record that `result_id` was launched for `query` and persist the store.
+    ///
+    /// The file is written on a background thread, so launching from the
+    /// UI never waits for the disk.
+    pub fn record(&mut self, query: &str, result_id: &str) {
+        self.record_at(query, result_id, now());
+        self.save();
+    }
+
+    fn record_at(&mut self, query: &str, result_id: &str, timestamp: u64) {
+        self.records.push(UsageRecord {
+            query: query.trim().to_lowercase(),
+            result_id: result_id.to_string(),
+            timestamp,
+        });
+
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    /// Frecency bonus of a result for the current query.
+    ///
+    /// Each launch counts by age; launches made with a query sharing a prefix
+    /// with the current one count fully, others count a quarter. Launches
+    /// from the empty query only count fully for the empty query.
+    pub fn score(&self, result_id: &str, query: &str) -> i64 {
+        self.score_at(result_id, query, now())
+    }
+
+    fn score_at(&self, result_id: &str, query: &str, now: u64) -> i64 {
+        let query = query.trim().to_lowercase();
+
+        let total: i64 = self
+            .records
+            .iter()
+            .filter(|r| r.result_id == result_id)
+            .map(|r| {
+                let weight = recency_weight(now.saturating_sub(r.timestamp));
+                let related = if r.query.is_empty() {
+                    query.is_empty()
+                } else {
+                    r.query.starts_with(&query) || query.starts_with(&r.query)
+                };
+                if related {
+                    weight
+                } else {
+                    weight / 4
+                }
+            })
+            .sum();
+
+        total.min(MAX_FRECENCY_BONUS)
+    }
+
+    /// Number of recorded launches
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if nothing has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Hand the records to the writer thread
+    fn save(&mut self) {
+        let Some(ref path) = self.path else {
+            return;
+        };
+        let writer = self
+            .writer
+            .get_or_insert_with(|| spawn_writer(path.clone()));
+        if writer.send(self.records.clone()).is_err() {
+            eprintln!("[Nova] Failed to save usage history: writer stopped");
+        }
+    }
+}
+
+/// Start a thread writing the records it is sent to `path`. When several
+/// saves queue up, only the latest records are written.
+fn spawn_writer(path: PathBuf) -> Sender<Vec<UsageRecord>> {
+    let (sender, receiver) = mpsc::channel::<Vec<UsageRecord>>();
+    std::thread::spawn(move || {
+        while let Ok(mut records) = receiver.recv() {
+            while let Ok(newer) = receiver.try_recv() {
+                records = newer;
+            }
+            if let Err(e) = write_records(&path, &records) {
+                eprintln!("[Nova] Failed to save usage history: {}", e);
+            }
+        }
+    });
+    sender
+}
+
+fn write_records(path: &Path, records: &[UsageRecord]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string(records)
+        .map_err(|e| format!("Failed to serialize usage history: {}", e))?;
+
+    // Readers never see a half-written file
+    let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&temp, content)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            format!("Failed to write usage history: {}", e)
+        })
+}
+
+/// Weight of a single launch by its age in seconds
+fn recency_weight(age_secs: u64) -> i64 {
+    const DAY: u64 = 86400;
+    match age_secs {
+        a if a < 4 * DAY => 100,
+        a if a < 14 * DAY => 70,
+        a if a < 31 * DAY => 50,
+        a if a < 90 * DAY => 30,
+        _ => 10,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_unused_result_scores_zero() {
+        let store = FrecencyStore::in_memory();
+        assert_eq!(store.score_at("firefox", "fi", NOW), 0);
+    }
+
+    #[test]
+    fn test_matching_prefix_counts_fully() {
+        let mut store = FrecencyStore::in_memory();
+        store.record_at("fire", "firefox", NOW);
+
+        assert_eq!(store.score_at("firefox", "fi", NOW), 100);
+        assert_eq!(store.score_at("firefox", "", NOW), 100);
+        assert_eq!(store.score_at("firefox", "term", NOW), 25);
+    }
+
+    #[test]
+    fn test_empty_query_launches() {
+        let mut store = FrecencyStore::in_memory();
+        store.record_at("", "firefox", NOW);
+
+        assert_eq!(store.score_at("firefox", "", NOW), 100);
+        assert_eq!(store.score_at("firefox", "fi", NOW), 25);
+        assert_eq!(store.score_at("firefox", "term", NOW), 25);
+    }
+
+    #[test]
+    fn test_old_launches_weigh_less() {
+        let mut store = FrecencyStore::in_memory();
+        store.record_at("f", "recent", NOW);
+        store.record_at("f", "old", NOW - 100 * 86400);
+
+        assert!(store.score_at("recent", "f", NOW) > store.score_at("old", "f", NOW));
+    }
+
+    #[test]
+    fn test_bonus_is_capped() {
+        let mut store = FrecencyStore::in_memory();
+        for _ in 0..10 {
+            store.record_at("f", "firefox", NOW);
+        }
+        assert_eq!(store.score_at("firefox", "f", NOW), MAX_FRECENCY_BONUS);
+    }
+
+    #[test]
+    fn test_record_limit() {
+        let mut store = FrecencyStore::in_memory();
+        for i in 0..MAX_RECORDS + 5 {
+            store.record_at("q", &i.to_string(), NOW);
+        }
+        assert_eq!(store.len(), MAX_RECORDS);
+    }
+
+    #[test]
+    fn test_saved_in_background() {
+        let path = std::env::temp_dir().join(format!("nova-usage-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut store = FrecencyStore::load_from(path.clone());
+        store.record("fire", "firefox");
+        store.record("term", "terminal");
+
+        // Wait for the writer thread
+        let mut loaded = FrecencyStore::load_from(path.clone());
+        for _ in 0..100 {
+            if loaded.len() == 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            loaded = FrecencyStore::load_from(path.clone());
+        }
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.score_at("terminal", "te", now()), 100);
+    }
+
+    #[test]
+    fn test_invalid_file_kept() {
+        let dir = std::env::temp_dir().join(format!("nova-usage-invalid-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("usage.json");
+        fs::write(&path, "[{\"query\": \"fi").unwrap();
+
+        let store = FrecencyStore::load_from(path.clone());
+        let backup = fs::read_to_string(dir.join("usage.json.bak"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(store.is_empty());
+        assert_eq!(backup.unwrap(), "[{\"query\": \"fi");
+    }
+}
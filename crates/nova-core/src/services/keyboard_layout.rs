@@ -0,0 +1,147 @@
+//! Query correction for text typed with the wrong keyboard layout active
+//!
+//! Each layout lists the characters of the same physical keys, in US key
+//! order, so a character can be carried from one layout to another by
+//! position: "ашкуащч" typed on a Russian layout is "firefox" on a US one.
+
+/// A keyboard layout Nova can remap queries between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    Us,
+    Russian,
+    TurkishQ,
+}
+
+impl KeyboardLayout {
+    /// Parse a layout from its config name ("us", "ru", "tr")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "us" | "en" => Some(KeyboardLayout::Us),
+            "ru" => Some(KeyboardLayout::Russian),
+            "tr" => Some(KeyboardLayout::TurkishQ),
+            _ => None,
+        }
+    }
+
+    /// Unshifted and shifted characters of every key, in US key order
+    fn keys(&self) -> [&'static str; 2] {
+        match self {
+            KeyboardLayout::Us => [
+                "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
+                "~!@#$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:\"ZXCVBNM<>?",
+            ],
+            KeyboardLayout::Russian => [
+                "ё1234567890-=йцукенгшщзхъ\\фывапролджэячсмитьбю.",
+                "Ё!\"№;%:?*()_+ЙЦУКЕНГШЩЗХЪ/ФЫВАПРОЛДЖЭЯЧСМИТЬБЮ,",
+            ],
+            KeyboardLayout::TurkishQ => [
+                "\"1234567890*-qwertyuıopğü,asdfghjklşizxcvbnmöç.",
+                "é!'^+%&/()=?_QWERTYUIOPĞÜ;ASDFGHJKLŞİZXCVBNMÖÇ:",
+            ],
+        }
+    }
+
+    /// Shift level and position of the key producing `c`
+    fn locate(&self, c: char) -> Option<(usize, usize)> {
+        self.keys()
+            .iter()
+            .enumerate()
+            .find_map(|(level, keys)| Some((level, keys.chars().position(|k| k == c)?)))
+    }
+
+    fn key(&self, level: usize, position: usize) -> Option<char> {
+        self.keys()[level].chars().nth(position)
+    }
+}
+
+/// Re-type `text` as if it had been typed on `to` with the same keys
+/// pressed on `from`. Characters not on `from` are kept as they are.
+pub fn remap(text: &str, from: KeyboardLayout, to: KeyboardLayout) -> String {
+    text.chars()
+        .map(|c| {
+            if c == ' ' {
+                return c;
+            }
+            from.locate(c)
+                .and_then(|(level, position)| to.key(level, position))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+/// Every distinct reading of `query` when typed on one of `layouts` but
+/// meant for another, excluding the query itself
+pub fn alternatives(query: &str, layouts: &[KeyboardLayout]) -> Vec<String> {
+    let mut results: Vec<String> = Vec::new();
+
+    for &from in layouts {
+        // Only layouts the query could have been typed on
+        if !query
+            .chars()
+            .any(|c| c != ' ' && !c.is_ascii_digit() && from.locate(c).is_some())
+        {
+            continue;
+        }
+
+        for &to in layouts {
+            if from == to {
+                continue;
+            }
+            let remapped = remap(query, from, to);
+            if remapped != query && !results.contains(&remapped) {
+                results.push(remapped);
+            }
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keys in US order: number row, then the three letter rows
+    const KEY_COUNT: usize = 47;
+
+    #[test]
+    fn test_tables_cover_every_key() {
+        for layout in [
+            KeyboardLayout::Us,
+            KeyboardLayout::Russian,
+            KeyboardLayout::TurkishQ,
+        ] {
+            for keys in layout.keys() {
+                assert_eq!(keys.chars().count(), KEY_COUNT, "{:?}", layout);
+            }
+        }
+    }
+
+    #[test]
+    fn test_russian_to_us() {
+        assert_eq!(
+            remap("ашкуащч", KeyboardLayout::Russian, KeyboardLayout::Us),
+            "firefox"
+        );
+        assert_eq!(
+            remap("firefox", KeyboardLayout::Us, KeyboardLayout::Russian),
+            "ашкуащч"
+        );
+    }
+
+    #[test]
+    fn test_turkish_dotless_i() {
+        assert_eq!(
+            remap("fırefox", KeyboardLayout::TurkishQ, KeyboardLayout::Us),
+            "firefox"
+        );
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let layouts = [KeyboardLayout::Us, KeyboardLayout::Russian];
+        assert_eq!(alternatives("ашкуащч", &layouts), vec!["firefox"]);
+        assert_eq!(alternatives("firefox", &layouts), vec!["ашкуащч"]);
+        assert!(alternatives("123", &layouts).is_empty());
+    }
+}
@@ -5,9 +5,15 @@ pub mod emoji;
 pub mod extension;
 pub mod extensions;
 pub mod file_search;
+pub mod frecency;
 pub mod format;
+pub mod keyboard_layout;
+pub mod query_history;
 pub mod units;
 
 pub use custom_commands::{CustomCommandsIndex, ScriptOutputMode};
 pub use extension::{Extension, ExtensionIndex, ExtensionKind};
 pub use extensions::{get_extensions_dir, ExtensionManager, LoadedCommand, OutputMode};
+pub use frecency::FrecencyStore;
+pub use keyboard_layout::KeyboardLayout;
+pub use query_history::{HistoryEntry, QueryHistory};
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::matcher::Pattern;
+
+/// Maximum number of queries kept on disk
+const MAX_ENTRIES: usize = 200;
+
+/// A previously launched query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Text typed in the search field
+    pub query: String,
+    /// Keyword of the command-mode extension that was active, if any
+    #[serde(default)]
+    pub extension: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Text shown when listing the entry, e.g. "yt cats" for command mode
+    pub fn display(&self) -> String {
+        match self.extension {
+            Some(ref keyword) => format!("{} {}", keyword, self.query),
+            None => self.query.clone(),
+        }
+    }
+}
+
+/// Persistent list of queries, most recent first
+#[derive(Debug, Default)]
+pub struct QueryHistory {
+    entries: Vec<HistoryEntry>,
+    path: Option<PathBuf>,
+}
+
+impl QueryHistory {
+    /// Get the history file path (~/.local/share/nova/history.json)
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| {
+                dirs::home_dir()
+                    .map(|h| h.join(".local/share"))
+                    .unwrap_or_else(|| PathBuf::from("/tmp"))
+            })
+            .join("nova")
+            .join("history.json")
+    }
+
+    /// Load the history from the default path
+    pub fn load() -> Self {
+        Self::load_from(Self::default_path())
+    }
+
+    /// Load the history from a file, starting empty if it is missing or invalid
+    pub fn load_from(path: PathBuf) -> Self {
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("[Nova] Failed to parse query history: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            entries,
+            path: Some(path),
+        }
+    }
+
+    /// Create a history that is never written to disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Remember a query and persist the history. Repeated queries move to
+    /// the front instead of being stored twice.
+    pub fn record(&mut self, query: &str, extension: Option<&str>) {
+        if self.push(query, extension) {
+            if let Err(e) = self.save() {
+                eprintln!("[Nova] Failed to save query history: {}", e);
+            }
+        }
+    }
+
+    fn push(&mut self, query: &str, extension: Option<&str>) -> bool {
+        let query = query.trim();
+        if query.is_empty() && extension.is_none() {
+            return false;
+        }
+
+        let entry = HistoryEntry {
+            query: query.to_string(),
+            extension: extension.map(|k| k.to_string()),
+        };
+        self.entries.retain(|e| *e != entry);
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        true
+    }
+
+    /// All entries, most recent first
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Entry at `index`, counting back from the most recent
+    pub fn get(&self, index: usize) -> Option<&HistoryEntry> {
+        self.entries.get(index)
+    }
+
+    /// Entries matching `query`, best match first; recency breaks ties
+    pub fn search(&self, query: &str) -> Vec<&HistoryEntry> {
+        let pattern = Pattern::new(query);
+        let mut matches: Vec<(i64, &HistoryEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|e| Some((pattern.score(&e.display())?, e)))
+            .collect();
+        matches.sort_by_key(|m| std::cmp::Reverse(m.0));
+        matches.into_iter().map(|(_, e)| e).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+
+        let content = serde_json::to_string(&self.entries)
+            .map_err(|e| format!("Failed to serialize query history: {}", e))?;
+
+        fs::write(path, content).map_err(|e| format!("Failed to write query history: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_recent_first_without_duplicates() {
+        let mut history = QueryHistory::in_memory();
+        history.push("fire", None);
+        history.push("term", None);
+        history.push("fire", None);
+
+        let queries: Vec<&str> = history.entries().iter().map(|e| e.query.as_str()).collect();
+        assert_eq!(queries, vec!["fire", "term"]);
+    }
+
+    #[test]
+    fn test_command_mode_is_kept() {
+        let mut history = QueryHistory::in_memory();
+        history.push("cats", Some("yt"));
+        history.push("cats", None);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(1).unwrap().display(), "yt cats");
+    }
+
+    #[test]
+    fn test_empty_query_is_skipped() {
+        let mut history = QueryHistory::in_memory();
+        assert!(!history.push("  ", None));
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_search() {
+        let mut history = QueryHistory::in_memory();
+        history.push("firefox", None);
+        history.push("cats", Some("yt"));
+        history.push("terminal", None);
+
+        let found: Vec<String> = history.search("yt").iter().map(|e| e.display()).collect();
+        assert_eq!(found, vec!["yt cats"]);
+        assert_eq!(history.search("").len(), 3);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut history = QueryHistory::in_memory();
+        for i in 0..MAX_ENTRIES + 5 {
+            history.push(&i.to_string(), None);
+        }
+        assert_eq!(history.len(), MAX_ENTRIES);
+        assert_eq!(history.get(0).unwrap().query, (MAX_ENTRIES + 4).to_string());
+    }
+}
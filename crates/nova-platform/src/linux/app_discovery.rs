@@ -1,95 +1,145 @@
 use freedesktop_desktop_entry::DesktopEntry;
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
-use nova_core::{NovaError, NovaResult, PlatformAppEntry};
-use std::path::PathBuf;
+use nova_core::{AppAction, NovaError, NovaResult, PlatformAppEntry};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use walkdir::WalkDir;
 
-use crate::traits::AppDiscovery;
+use super::app_watcher::LinuxAppWatcher;
+use super::startup::{self, Launchee, StartupNotification};
+use super::{exec, local_apps, locale, systemd, xdg};
+use crate::traits::{AppDiscovery, AppWatcher};
 
 pub struct LinuxAppDiscovery {
     entries: Vec<PlatformAppEntry>,
-    matcher: SkimMatcherV2,
+    /// Directories searched for AppImages and command line programs
+    local_dirs: Vec<PathBuf>,
 }
 
 impl Default for LinuxAppDiscovery {
     fn default() -> Self {
-        Self::new()
+        Self::new(Vec::new())
     }
 }
 
 impl LinuxAppDiscovery {
-    pub fn new() -> Self {
-        let mut entries = Vec::new();
-        let matcher = SkimMatcherV2::default();
-
-        let mut dirs_to_scan: Vec<PathBuf> = vec![
-            PathBuf::from("/usr/share/applications"),
-            PathBuf::from("/usr/local/share/applications"),
-        ];
+    pub fn new(local_dirs: Vec<PathBuf>) -> Self {
+        let entries = Self::scan(&local_dirs);
 
-        if let Some(data_home) = dirs::data_local_dir() {
-            dirs_to_scan.push(data_home.join("applications"));
-        }
+        println!("[Nova] Indexed {} applications", entries.len());
 
-        if let Some(home) = dirs::home_dir() {
-            dirs_to_scan.push(home.join(".local/share/flatpak/exports/share/applications"));
+        Self {
+            entries,
+            local_dirs,
         }
+    }
 
-        dirs_to_scan.push(PathBuf::from("/var/lib/snapd/desktop/applications"));
+    /// Read every application directory, then the local directories,
+    /// sorted by name
+    pub(super) fn scan(local_dirs: &[PathBuf]) -> Vec<PlatformAppEntry> {
+        let locales = locale::user_locales();
+        let desktops = xdg::current_desktops();
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
 
-        for dir in dirs_to_scan {
-            if dir.exists() {
-                Self::scan_directory(&dir, &mut entries);
+        for dir in xdg::application_dirs() {
+            if dir.is_dir() {
+                Self::scan_directory(&dir, &locales, &desktops, &mut seen, &mut entries);
             }
         }
+        let local = local_apps::scan(local_dirs, &entries, &locales);
+        entries.extend(local);
 
-        entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-
-        println!("[Nova] Indexed {} applications", entries.len());
-
-        Self { entries, matcher }
+        entries.sort_by_key(|e| e.name.to_lowercase());
+        entries
     }
 
-    fn scan_directory(dir: &PathBuf, entries: &mut Vec<PlatformAppEntry>) {
+    /// Index the entries of one applications directory.
+    ///
+    /// Directories are scanned by precedence, so the first file with a
+    /// desktop file id wins and masks the others, even when it is hidden.
+    fn scan_directory(
+        dir: &Path,
+        locales: &[String],
+        desktops: &[String],
+        seen: &mut HashSet<String>,
+        entries: &mut Vec<PlatformAppEntry>,
+    ) {
         for entry in WalkDir::new(dir)
-            .max_depth(2)
+            .follow_links(true)
+            .sort_by_file_name()
             .into_iter()
             .filter_map(|e| e.ok())
         {
+            if !entry.file_type().is_file() {
+                continue;
+            }
             let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "desktop") {
-                if let Some(app_entry) = Self::parse_desktop_file(path.to_path_buf()) {
-                    if !entries.iter().any(|e| e.id == app_entry.id) {
-                        entries.push(app_entry);
-                    }
-                }
+            let Some(id) = xdg::desktop_file_id(dir, path) else {
+                continue;
+            };
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            if let Some(app_entry) =
+                Self::parse_desktop_file(path.to_path_buf(), id, locales, desktops)
+            {
+                entries.push(app_entry);
             }
         }
     }
 
-    fn parse_desktop_file(path: PathBuf) -> Option<PlatformAppEntry> {
+    /// Read a desktop entry, showing its name in the user's locale.
+    ///
+    /// Entries the desktop shell wouldn't show are skipped: hidden ones, ones
+    /// meant for other desktops and ones whose `TryExec` program is missing.
+    ///
+    /// Localized and untranslated Name, GenericName and Keywords are all
+    /// indexed, so both the translated and the English terms find the app.
+    fn parse_desktop_file(
+        path: PathBuf,
+        id: String,
+        locales: &[String],
+        desktops: &[String],
+    ) -> Option<PlatformAppEntry> {
         let content = std::fs::read_to_string(&path).ok()?;
-        let entry = DesktopEntry::from_str(&path, &content, Some(&["en"])).ok()?;
+        let entry = DesktopEntry::from_str(&path, &content, Some(locales)).ok()?;
 
         if entry.no_display() || entry.hidden() {
             return None;
         }
+        // Links and directories aren't launchable
+        if entry.type_().is_some_and(|t| t != "Application") {
+            return None;
+        }
+        if !xdg::shown_in(entry.only_show_in(), entry.not_show_in(), desktops) {
+            return None;
+        }
+        if entry.try_exec().is_some_and(|p| !xdg::is_installed(p)) {
+            return None;
+        }
 
-        let locales: &[&str] = &[];
         let name = entry.name(locales)?.to_string();
         let exec = entry.exec()?.to_string();
-        let id = path.file_stem()?.to_string_lossy().to_string();
         let icon = entry.icon().map(|s| s.to_string());
         let description = entry.comment(locales).map(|s| s.to_string());
+        let keywords = keywords(&entry, &name, locales);
 
-        let mut keywords: Vec<String> = entry
-            .keywords(locales)
-            .map(|kw| kw.iter().map(|s| s.to_string()).collect())
-            .unwrap_or_default();
-
-        keywords.extend(name.split_whitespace().map(|s| s.to_lowercase()));
+        // Actions without an Exec key are D-Bus activated, which Nova doesn't do
+        let actions = entry
+            .actions()
+            .into_iter()
+            .flatten()
+            .filter(|id| !id.is_empty())
+            .filter_map(|id| {
+                Some(AppAction {
+                    id: id.to_string(),
+                    name: entry.action_name(id, locales)?.to_string(),
+                    exec: entry.action_exec(id)?.to_string(),
+                    icon: entry.action_entry(id, "Icon").map(|s| s.to_string()),
+                })
+            })
+            .collect();
 
         Some(PlatformAppEntry {
             id,
@@ -98,53 +148,107 @@ impl LinuxAppDiscovery {
             icon,
             description,
             keywords,
+            desktop_file: Some(path),
+            terminal: entry.terminal(),
+            actions,
         })
     }
 
-    /// Search for apps matching the query using fuzzy matching
-    pub fn search(&self, query: &str) -> Vec<&PlatformAppEntry> {
-        if query.is_empty() {
-            return self.entries.iter().take(8).collect();
+    /// Launch an app or one of its desktop actions following the Desktop
+    /// Entry Specification.
+    ///
+    /// The desktop file is read again for the keys the index doesn't keep:
+    /// `Path` sets the working directory and `Terminal=true` apps open in
+    /// the user's terminal emulator. Apps declaring `StartupNotify` or
+    /// `StartupWMClass` get a startup notification token where the display
+    /// server hands out a usable one in time, and every app runs in its own
+    /// systemd scope.
+    fn launch(app: &PlatformAppEntry, action: Option<&str>) -> NovaResult<()> {
+        let locales = locale::user_locales();
+        let entry = app
+            .desktop_file
+            .as_ref()
+            .and_then(|path| DesktopEntry::from_path(path, Some(&locales)).ok());
+
+        let name = entry
+            .as_ref()
+            .and_then(|e| e.name(&locales))
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| app.name.clone());
+        let icon = match action {
+            Some(action) => entry
+                .as_ref()
+                .and_then(|e| e.action_entry(action, "Icon").or(e.icon())),
+            None => entry.as_ref().and_then(|e| e.icon()),
+        };
+        let values = exec::FieldValues {
+            name: &name,
+            icon: icon.or(app.icon.as_deref()),
+            desktop_file: app.desktop_file.as_deref(),
+        };
+        let exec = entry
+            .as_ref()
+            .and_then(|e| match action {
+                Some(action) => e.action_exec(action),
+                None => e.exec(),
+            })
+            .unwrap_or(&app.exec);
+        let args = exec::expand(exec, &values).map_err(NovaError::Launch)?;
+
+        // Variables set before the command are passed on, also to the
+        // terminal the app runs in
+        let (vars, args) = exec::split_env(&args);
+        let (program, program_args) = args
+            .split_first()
+            .ok_or_else(|| NovaError::Launch("Empty exec command".to_string()))?;
+        let mut command = if entry.as_ref().map_or(app.terminal, |e| e.terminal()) {
+            terminal_command(args)?
+        } else {
+            let mut command = Command::new(program);
+            command.args(program_args);
+            command
+        };
+        command.envs(vars);
+
+        let startup = entry
+            .as_ref()
+            .filter(|e| e.startup_notify() || e.startup_wm_class().is_some())
+            .and_then(|e| {
+                StartupNotification::begin(&Launchee {
+                    app_id: &app.id,
+                    name: &name,
+                    icon: values.icon,
+                    bin: program,
+                    wm_class: e.startup_wm_class(),
+                    desktop_file: app.desktop_file.as_deref(),
+                })
+            });
+        startup::set_env(&mut command, startup.as_ref());
+
+        if let Some(dir) = entry.as_ref().and_then(|e| e.path()) {
+            if Path::new(dir).is_dir() {
+                command.current_dir(dir);
+            } else {
+                eprintln!("[Nova] Working directory of {} not found: {}", name, dir);
+            }
         }
 
-        let query_lower = query.to_lowercase();
-        let mut scored: Vec<(&PlatformAppEntry, i64)> = self
-            .entries
-            .iter()
-            .filter_map(|entry| {
-                let name_score = self
-                    .matcher
-                    .fuzzy_match(&entry.name.to_lowercase(), &query_lower);
-
-                let keyword_score = entry
-                    .keywords
-                    .iter()
-                    .filter_map(|kw| self.matcher.fuzzy_match(&kw.to_lowercase(), &query_lower))
-                    .max();
-
-                let desc_score = entry
-                    .description
-                    .as_ref()
-                    .and_then(|d| self.matcher.fuzzy_match(&d.to_lowercase(), &query_lower))
-                    .map(|s| s / 2);
-
-                let best_score = [name_score, keyword_score, desc_score]
-                    .into_iter()
-                    .flatten()
-                    .max()?;
-
-                let prefix_boost = if entry.name.to_lowercase().starts_with(&query_lower) {
-                    100
-                } else {
-                    0
-                };
-
-                Some((entry, best_score + prefix_boost))
-            })
-            .collect();
+        let child = command.spawn().map_err(|e| {
+            if let Some(startup) = startup {
+                startup.cancel();
+            }
+            NovaError::Launch(format!("Failed to launch {}: {}", name, e))
+        })?;
+
+        // The launch doesn't wait for the service manager to answer
+        let (app_id, pid) = (app.id.clone(), child.id());
+        std::thread::spawn(move || {
+            if let Err(e) = systemd::move_to_scope(&app_id, pid) {
+                eprintln!("[Nova] {}", e);
+            }
+        });
 
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
-        scored.into_iter().take(8).map(|(entry, _)| entry).collect()
+        Ok(())
     }
 }
 
@@ -153,30 +257,206 @@ impl AppDiscovery for LinuxAppDiscovery {
         self.entries.clone()
     }
 
+    fn watch(&self) -> NovaResult<Box<dyn AppWatcher>> {
+        Ok(Box::new(LinuxAppWatcher::new(
+            &self.entries,
+            self.local_dirs.clone(),
+        )?))
+    }
+
     fn launch_app(&self, app: &PlatformAppEntry) -> NovaResult<()> {
-        let exec = app
-            .exec
-            .replace("%f", "")
-            .replace("%F", "")
-            .replace("%u", "")
-            .replace("%U", "")
-            .replace("%i", "")
-            .replace("%c", "")
-            .replace("%k", "");
-
-        let parts: Vec<&str> = exec.split_whitespace().collect();
-        if parts.is_empty() {
-            return Err(NovaError::Launch("Empty exec command".to_string()));
-        }
+        Self::launch(app, None)
+    }
+
+    fn launch_action(&self, app: &PlatformAppEntry, action: &str) -> NovaResult<()> {
+        Self::launch(app, Some(action))
+    }
 
-        let program = parts[0];
-        let args = &parts[1..];
+    fn launch_in_terminal(&self, exec: &str, name: &str) -> NovaResult<()> {
+        let values = exec::FieldValues {
+            name,
+            ..Default::default()
+        };
+        let args = exec::expand(exec, &values).map_err(NovaError::Launch)?;
 
-        Command::new(program)
-            .args(args)
-            .spawn()
-            .map_err(|e| NovaError::Launch(format!("Failed to launch {}: {}", app.name, e)))?;
+        terminal_command(&args)?.spawn().map_err(|e| {
+            NovaError::Launch(format!("Failed to open {} in terminal: {}", name, e))
+        })?;
 
         Ok(())
     }
 }
+
+/// Search terms of a desktop entry besides its name: the translated and
+/// untranslated Name, GenericName and Keywords, then the words of the name
+pub(super) fn keywords(entry: &DesktopEntry, name: &str, locales: &[String]) -> Vec<String> {
+    // Translated terms first, then the untranslated (usually English) ones
+    let untranslated: &[String] = &[];
+    let mut terms: Vec<String> = Vec::new();
+    for locales in [locales, untranslated] {
+        terms.extend(entry.name(locales).map(|s| s.to_string()));
+        terms.extend(entry.generic_name(locales).map(|s| s.to_string()));
+        terms.extend(
+            entry
+                .keywords(locales)
+                .into_iter()
+                .flatten()
+                .map(|kw| kw.to_string()),
+        );
+    }
+
+    let mut keywords: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.trim();
+        if !term.is_empty() && term != name && !keywords.iter().any(|k| k == term) {
+            keywords.push(term.to_string());
+        }
+    }
+
+    keywords.extend(name.split_whitespace().map(|s| s.to_lowercase()));
+    keywords
+}
+
+/// Well-known terminal emulators, in the order they are looked for
+const TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "kgx",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "alacritty",
+    "kitty",
+    "foot",
+    "wezterm",
+    "xterm",
+];
+
+/// Build a command running `args` inside the user's terminal emulator
+fn terminal_command(args: &[String]) -> NovaResult<Command> {
+    let terminal = find_terminal()
+        .ok_or_else(|| NovaError::Launch("No terminal emulator found".to_string()))?;
+    Ok(command_in_terminal(&terminal, args))
+}
+
+/// Run `args` in `terminal`, a program name or path
+fn command_in_terminal(terminal: &str, args: &[String]) -> Command {
+    let name = Path::new(terminal)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(terminal);
+    // Most terminals take the command after `-e`
+    let prefix: &[&str] = match name {
+        "gnome-terminal" | "kgx" | "ptyxis" => &["--"],
+        "wezterm" => &["start", "--"],
+        "kitty" | "foot" => &[],
+        // Its `-e` takes the command as a single string
+        "xfce4-terminal" => &["-x"],
+        _ => &["-e"],
+    };
+
+    let mut command = Command::new(terminal);
+    command.args(prefix).args(args);
+    command
+}
+
+/// Pick a terminal emulator: `$TERMINAL`, else the first well-known one on `PATH`
+fn find_terminal() -> Option<String> {
+    if let Ok(terminal) = std::env::var("TERMINAL") {
+        if !terminal.is_empty() {
+            return Some(terminal);
+        }
+    }
+
+    let path = std::env::var_os("PATH")?;
+    TERMINALS
+        .iter()
+        .find(|name| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use nova_core::search::providers::AppProvider;
+    use nova_core::search::{SearchContext, SearchProvider};
+    use nova_core::services::clipboard::ClipboardHistory;
+
+    use super::*;
+
+    #[test]
+    fn test_command_in_terminal() {
+        let args = ["htop".to_string(), "-d".to_string(), "5".to_string()];
+        let command_line = |terminal: &str| -> Vec<String> {
+            let command = command_in_terminal(terminal, &args);
+            std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let expected = [
+            "x-terminal-emulator -e htop -d 5",
+            "kgx -- htop -d 5",
+            "gnome-terminal -- htop -d 5",
+            "konsole -e htop -d 5",
+            "xfce4-terminal -x htop -d 5",
+            "alacritty -e htop -d 5",
+            "kitty htop -d 5",
+            "foot htop -d 5",
+            "wezterm start -- htop -d 5",
+            "xterm -e htop -d 5",
+        ];
+        assert_eq!(TERMINALS.len(), expected.len());
+        for (terminal, expected) in TERMINALS.iter().zip(expected) {
+            assert_eq!(command_line(terminal).join(" "), expected);
+        }
+        // `$TERMINAL` may hold a path
+        assert_eq!(
+            command_line("/usr/bin/wezterm").join(" "),
+            "/usr/bin/wezterm start -- htop -d 5"
+        );
+    }
+
+    #[test]
+    fn test_localized_keywords() {
+        let path = std::env::temp_dir().join(format!("nova-files-{}.desktop", std::process::id()));
+        let content = "[Desktop Entry]\n\
+            Type=Application\n\
+            Name=Files\n\
+            Name[de]=Dateien\n\
+            GenericName=File Manager\n\
+            GenericName[de_DE]=Dateimanager\n\
+            Keywords=folder;explorer;\n\
+            Keywords[de]=Ordner;\n\
+            Exec=nautilus\n";
+        std::fs::write(&path, content).unwrap();
+        let locales = ["de_DE".to_string(), "de".to_string()];
+        let app =
+            LinuxAppDiscovery::parse_desktop_file(path.clone(), "files".into(), &locales, &[]);
+        std::fs::remove_file(&path).unwrap();
+
+        let app = app.unwrap();
+        assert_eq!(app.name, "Dateien");
+        assert_eq!(
+            app.keywords,
+            vec![
+                "Dateimanager",
+                "Ordner",
+                "Files",
+                "File Manager",
+                "folder",
+                "explorer",
+                "dateien"
+            ]
+        );
+
+        // Both the translated and the English keywords find the app
+        let apps = vec![app];
+        let clipboard = ClipboardHistory::new(1);
+        for query in ["ordner", "folder"] {
+            let ctx = SearchContext::new(query, 8, &apps, &clipboard);
+            let results = AppProvider.search(&ctx);
+            assert_eq!(results.len(), 1, "{}", query);
+            assert_eq!(results[0].result.name(), "Dateien");
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Live refresh of the application index through inotify
+//!
+//! Every applications directory and its subdirectories are watched, as are
+//! data directories that may still get an applications directory and the
+//! local directories holding AppImages and programs. Once a
+//! burst of events settles, the directories are rescanned and the result is
+//! diffed against the known apps, so only real changes are reported. When
+//! the kernel's event queue overflows, events are lost, so an overflow
+//! counts as a relevant event too.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::os::fd::AsFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor};
+use nova_core::{NovaError, NovaResult, PlatformAppEntry};
+use walkdir::WalkDir;
+
+use super::app_discovery::LinuxAppDiscovery;
+use super::xdg;
+use crate::traits::{AppChange, AppWatcher};
+
+/// Quiet time that ends a burst of events
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest a steady stream of events may delay a refresh
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// What a watch descriptor watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Watched {
+    /// An applications directory or one of its subdirectories
+    Applications,
+    /// A data directory without an applications directory yet
+    DataDir,
+    /// A directory of AppImages and command line programs
+    Local,
+}
+
+pub struct LinuxAppWatcher {
+    inotify: Inotify,
+    watches: HashMap<WatchDescriptor, Watched>,
+    apps: HashMap<String, PlatformAppEntry>,
+    local_dirs: Vec<PathBuf>,
+}
+
+impl LinuxAppWatcher {
+    pub fn new(apps: &[PlatformAppEntry], local_dirs: Vec<PathBuf>) -> NovaResult<Self> {
+        let inotify = Inotify::init(InitFlags::IN_CLOEXEC | InitFlags::IN_NONBLOCK)
+            .map_err(|e| NovaError::Platform(format!("Failed to init inotify: {}", e)))?;
+
+        let mut watcher = Self {
+            inotify,
+            watches: HashMap::new(),
+            apps: apps.iter().map(|a| (a.id.clone(), a.clone())).collect(),
+            local_dirs,
+        };
+        watcher.add_watches();
+        Ok(watcher)
+    }
+
+    /// Watch every applications directory with its subdirectories, and the
+    /// data directories where one may still be created. Directories that
+    /// are already watched keep their watch.
+    fn add_watches(&mut self) {
+        let app_flags = AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO
+            | AddWatchFlags::IN_ATTRIB;
+
+        for dir in xdg::application_dirs() {
+            if dir.is_dir() {
+                let subdirs = WalkDir::new(&dir)
+                    .follow_links(true)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_dir());
+                for subdir in subdirs {
+                    self.add_watch(subdir.path(), app_flags, Watched::Applications);
+                }
+            } else if let Some(data_dir) = dir.parent().filter(|d| d.is_dir()) {
+                let flags = AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO;
+                self.add_watch(data_dir, flags, Watched::DataDir);
+            }
+        }
+
+        for dir in self.local_dirs.clone() {
+            if dir.is_dir() {
+                self.add_watch(&dir, app_flags, Watched::Local);
+            }
+        }
+    }
+
+    fn add_watch(&mut self, path: &Path, flags: AddWatchFlags, kind: Watched) {
+        match self.inotify.add_watch(path, flags) {
+            Ok(wd) => {
+                self.watches.insert(wd, kind);
+            }
+            Err(e) => eprintln!("[Nova] Failed to watch {}: {}", path.display(), e),
+        }
+    }
+
+    /// Check if an event may change the index
+    fn is_relevant(&self, wd: WatchDescriptor, mask: AddWatchFlags, name: Option<&OsStr>) -> bool {
+        // Events were dropped, any of which may have mattered
+        if mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+            return true;
+        }
+        match self.watches.get(&wd) {
+            Some(Watched::Applications) => {
+                mask.contains(AddWatchFlags::IN_ISDIR)
+                    || name.is_some_and(|n| Path::new(n).extension() == Some("desktop".as_ref()))
+            }
+            Some(Watched::DataDir) => name.is_some_and(|n| n == "applications"),
+            Some(Watched::Local) => name.is_some_and(|n| !n.to_string_lossy().starts_with('.')),
+            None => false,
+        }
+    }
+
+    /// Block until a relevant event arrives, then until events stop for
+    /// [`DEBOUNCE`] or [`MAX_DELAY`] has passed
+    fn wait_for_burst(&mut self) -> NovaResult<()> {
+        let mut started: Option<Instant> = None;
+
+        loop {
+            let timeout = match started {
+                Some(start) if start.elapsed() >= MAX_DELAY => return Ok(()),
+                Some(_) => PollTimeout::try_from(DEBOUNCE).unwrap_or(PollTimeout::MAX),
+                None => PollTimeout::NONE,
+            };
+
+            let mut fds = [PollFd::new(self.inotify.as_fd(), PollFlags::POLLIN)];
+            match poll(&mut fds, timeout) {
+                Ok(0) => return Ok(()),
+                Ok(_) | Err(Errno::EINTR) => {}
+                Err(e) => return Err(NovaError::Platform(format!("inotify poll failed: {}", e))),
+            }
+
+            let events = match self.inotify.read_events() {
+                Ok(events) => events,
+                Err(Errno::EAGAIN) => continue,
+                Err(e) => return Err(NovaError::Platform(format!("inotify read failed: {}", e))),
+            };
+            for event in events {
+                // The kernel dropped the watch, e.g. because the directory is gone
+                if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+                    self.watches.remove(&event.wd);
+                    continue;
+                }
+                if started.is_none()
+                    && self.is_relevant(event.wd, event.mask, event.name.as_deref())
+                {
+                    started = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    /// Rescan the application directories and report what differs from the
+    /// known apps
+    fn rescan(&mut self) -> Vec<AppChange> {
+        let scanned: HashMap<String, PlatformAppEntry> = LinuxAppDiscovery::scan(&self.local_dirs)
+            .into_iter()
+            .map(|app| (app.id.clone(), app))
+            .collect();
+
+        let changes = diff(&self.apps, &scanned);
+        self.apps = scanned;
+        changes
+    }
+}
+
+/// Changes that turn the `known` apps into the `scanned` ones
+fn diff(
+    known: &HashMap<String, PlatformAppEntry>,
+    scanned: &HashMap<String, PlatformAppEntry>,
+) -> Vec<AppChange> {
+    let mut changes: Vec<AppChange> = known
+        .keys()
+        .filter(|id| !scanned.contains_key(*id))
+        .map(|id| AppChange::Removed(id.clone()))
+        .collect();
+    changes.extend(
+        scanned
+            .values()
+            .filter(|app| known.get(&app.id) != Some(app))
+            .map(|app| AppChange::Updated(app.clone())),
+    );
+    changes
+}
+
+impl AppWatcher for LinuxAppWatcher {
+    fn next_changes(&mut self) -> NovaResult<Vec<AppChange>> {
+        loop {
+            self.wait_for_burst()?;
+            // New subdirectories and applications directories need watches
+            // too, and after an overflow some may have been missed
+            self.add_watches();
+
+            let changes = self.rescan();
+            if !changes.is_empty() {
+                println!("[Nova] {} applications changed", changes.len());
+                return Ok(changes);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    fn app(id: &str, exec: &str) -> PlatformAppEntry {
+        PlatformAppEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            exec: exec.to_string(),
+            icon: None,
+            description: None,
+            keywords: Vec::new(),
+            desktop_file: None,
+            terminal: false,
+            actions: Vec::new(),
+        }
+    }
+
+    fn by_id(apps: &[PlatformAppEntry]) -> HashMap<String, PlatformAppEntry> {
+        apps.iter().map(|a| (a.id.clone(), a.clone())).collect()
+    }
+
+    fn describe(changes: &[AppChange]) -> Vec<String> {
+        let mut changes: Vec<String> = changes
+            .iter()
+            .map(|change| match change {
+                AppChange::Updated(app) => format!("updated {} {}", app.id, app.exec),
+                AppChange::Removed(id) => format!("removed {}", id),
+            })
+            .collect();
+        changes.sort();
+        changes
+    }
+
+    #[test]
+    fn test_diff() {
+        let known = by_id(&[
+            app("files", "nautilus"),
+            app("gimp", "gimp"),
+            app("vim", "vim"),
+        ]);
+        let scanned = by_id(&[
+            app("files", "nautilus"),
+            app("vim", "vim -p"),
+            app("firefox", "firefox"),
+        ]);
+        assert_eq!(
+            describe(&diff(&known, &scanned)),
+            vec![
+                "removed gimp",
+                "updated firefox firefox",
+                "updated vim vim -p"
+            ]
+        );
+        assert!(diff(&scanned, &scanned).is_empty());
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let dir = std::env::temp_dir().join(format!("nova-watch-kinds-{}", std::process::id()));
+        let (applications, local) = (dir.join("applications"), dir.join("bin"));
+        fs::create_dir_all(&applications).unwrap();
+        fs::create_dir_all(&local).unwrap();
+
+        let mut watcher = LinuxAppWatcher::new(&[], Vec::new()).unwrap();
+        let flags = AddWatchFlags::IN_CREATE;
+        watcher.add_watch(&applications, flags, Watched::Applications);
+        watcher.add_watch(&dir, flags, Watched::DataDir);
+        watcher.add_watch(&local, flags, Watched::Local);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let wd = |kind: Watched| *watcher.watches.iter().find(|(_, k)| **k == kind).unwrap().0;
+        let relevant = |kind: Watched, mask: AddWatchFlags, name: &str| {
+            watcher.is_relevant(wd(kind), mask, Some(OsStr::new(name)))
+        };
+        let create = AddWatchFlags::IN_CREATE;
+        assert!(relevant(Watched::Applications, create, "firefox.desktop"));
+        assert!(!relevant(Watched::Applications, create, "mimeinfo.cache"));
+        assert!(relevant(
+            Watched::Applications,
+            create | AddWatchFlags::IN_ISDIR,
+            "kde"
+        ));
+        assert!(relevant(Watched::DataDir, create, "applications"));
+        assert!(!relevant(Watched::DataDir, create, "icons"));
+        assert!(relevant(Watched::Local, create, "tool"));
+        assert!(!relevant(Watched::Local, create, ".tool.part"));
+        // Lost events always count
+        assert!(relevant(Watched::DataDir, AddWatchFlags::IN_Q_OVERFLOW, ""));
+    }
+
+    #[test]
+    fn test_next_changes() {
+        let dir = std::env::temp_dir().join(format!("nova-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let tool = dir.join("tool");
+
+        let local_dirs = vec![dir.clone()];
+        let apps = LinuxAppDiscovery::scan(&local_dirs);
+        let mut watcher = LinuxAppWatcher::new(&apps, local_dirs).unwrap();
+
+        // A burst of events arrives as one batch once it settles
+        let writer = {
+            let tool = tool.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(100));
+                fs::write(&tool, "#!/bin/sh\n").unwrap();
+                std::thread::sleep(Duration::from_millis(100));
+                fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+            })
+        };
+        let start = Instant::now();
+        let changes = watcher.next_changes().unwrap();
+        writer.join().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200) + DEBOUNCE);
+        let path = tool.to_string_lossy();
+        assert_eq!(
+            describe(&changes),
+            vec![format!("updated {} {}", path, path)]
+        );
+
+        fs::remove_file(&tool).unwrap();
+        let changes = watcher.next_changes().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(describe(&changes), vec![format!("removed {}", path)]);
+    }
+}
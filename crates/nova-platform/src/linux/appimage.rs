@@ -0,0 +1,173 @@
+//! AppImage contents
+//!
+//! A type 2 AppImage is an ELF runtime followed by a SquashFS image whose
+//! root holds the app's desktop entry and icon. The image starts where the
+//! ELF file ends, after its section header table. Type 1 AppImages embed an
+//! ISO 9660 image instead, which isn't read.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use super::squashfs::Image;
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+/// AppImage magic in the ELF identification padding, followed by the type
+const APPIMAGE_MAGIC: &[u8] = b"AI";
+
+/// Icon formats an AppImage may ship, besides `.DirIcon`
+const ICON_EXTENSIONS: &[&str] = &["png", "svg"];
+
+/// Read the AppImage type from a file's ELF header, if it is one
+pub fn appimage_type(path: &Path) -> Option<u8> {
+    let mut header = [0u8; 11];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    header_type(&header)
+}
+
+fn header_type(header: &[u8]) -> Option<u8> {
+    (header.starts_with(ELF_MAGIC) && &header[8..10] == APPIMAGE_MAGIC).then_some(header[10])
+}
+
+/// The image embedded in a type 2 AppImage
+pub struct AppImage {
+    image: Image<BufReader<File>>,
+    root: Vec<String>,
+}
+
+impl AppImage {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut header = [0u8; 64];
+        file.read_exact(&mut header)?;
+        if header_type(&header) != Some(2) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Only type 2 AppImages can be read",
+            ));
+        }
+
+        let offset = elf_size(&header)?;
+        let mut image = Image::open(BufReader::new(file), offset)?;
+        let root = image.root_entries()?;
+        Ok(Self { image, root })
+    }
+
+    /// Contents of the desktop entry at the image root
+    pub fn desktop_entry(&mut self) -> io::Result<String> {
+        let name = self
+            .root
+            .iter()
+            .find(|name| name.ends_with(".desktop"))
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No desktop entry"))?;
+        let bytes = self.image.read_file(&name)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// The icon named by the desktop entry, or else `.DirIcon`, with its
+    /// file extension
+    pub fn icon(&mut self, name: Option<&str>) -> Option<(&'static str, Vec<u8>)> {
+        let named = name.into_iter().flat_map(|name| {
+            ICON_EXTENSIONS
+                .iter()
+                .map(move |ext| format!("{}.{}", name, ext))
+        });
+        for file in named.chain([".DirIcon".to_string()]) {
+            if !self.root.contains(&file) {
+                continue;
+            }
+            if let Ok(bytes) = self.image.read_file(&file) {
+                if let Some(ext) = image_format(&bytes) {
+                    return Some((ext, bytes));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Tell PNG and SVG data apart by their content, since `.DirIcon` has no
+/// extension
+fn image_format(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        return Some("png");
+    }
+    let start = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_lowercase();
+    start.contains("<svg").then_some("svg")
+}
+
+/// Size of an ELF file: its section header table comes last
+fn elf_size(header: &[u8; 64]) -> io::Result<u64> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid ELF header");
+    let little_endian = match header[5] {
+        1 => true,
+        2 => false,
+        _ => return Err(invalid()),
+    };
+    let number = |at: usize, size: usize| {
+        let bytes = &header[at..at + size];
+        let fold = |n: u64, b: &u8| n << 8 | u64::from(*b);
+        if little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        }
+    };
+
+    let (table, entry_size, entries) = match header[4] {
+        // 32-bit
+        1 => (number(0x20, 4), number(0x2E, 2), number(0x30, 2)),
+        // 64-bit
+        2 => (number(0x28, 8), number(0x3A, 2), number(0x3C, 2)),
+        _ => return Err(invalid()),
+    };
+    Ok(table + entry_size * entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::squashfs::tests::{image, Node};
+
+    /// ELF header of a 64-bit little endian type 2 AppImage with one
+    /// section header right after the header
+    fn runtime() -> Vec<u8> {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(ELF_MAGIC);
+        header[4] = 2;
+        header[5] = 1;
+        header[8..11].copy_from_slice(b"AI\x02");
+        header[0x28] = 64;
+        header[0x3A] = 64;
+        header[0x3C] = 1;
+        header.extend([0u8; 64]);
+        header
+    }
+
+    #[test]
+    fn test_read_contents() {
+        let svg = b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>";
+        let mut file = runtime();
+        file.extend(image(&[
+            (
+                "tool.desktop",
+                Node::File(b"[Desktop Entry]\nName=Tool\nIcon=tool\n"),
+            ),
+            ("tool.svg", Node::File(svg)),
+            (".DirIcon", Node::Symlink("tool.svg")),
+            ("AppRun", Node::File(b"#!/bin/sh\n")),
+        ]));
+        let path = std::env::temp_dir().join(format!("nova-{}.AppImage", std::process::id()));
+        std::fs::write(&path, file).unwrap();
+
+        assert_eq!(appimage_type(&path), Some(2));
+        let mut appimage = AppImage::open(&path).unwrap();
+        assert!(appimage.desktop_entry().unwrap().contains("Name=Tool"));
+        assert_eq!(appimage.icon(Some("tool")), Some(("svg", svg.to_vec())));
+        assert_eq!(appimage.icon(Some("other")), Some(("svg", svg.to_vec())));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(appimage_type(Path::new("/nonexistent")), None);
+    }
+}
@@ -0,0 +1,348 @@
+//! Desktop Entry `Exec` key handling
+//!
+//! Implements the quoting and field code rules of the Desktop Entry
+//! Specification. Values are expected after the general string unescaping
+//! (`\s`, `\\`, ...) that the desktop entry parser already applied, so a
+//! literal backslash inside a quoted argument arrives here as `\\`.
+
+use std::path::Path;
+
+/// Values substituted for field codes. Nova launches apps without files or
+/// URLs, so `%f`, `%F`, `%u` and `%U` always expand to nothing.
+#[derive(Debug, Default)]
+pub struct FieldValues<'a> {
+    /// Translated application name, for `%c`
+    pub name: &'a str,
+    /// Icon key, for `%i`
+    pub icon: Option<&'a str>,
+    /// Location of the desktop file, for `%k`
+    pub desktop_file: Option<&'a Path>,
+}
+
+/// Part of an argument: literal text or a field code
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(char),
+}
+
+/// Split an Exec value into arguments and expand its field codes
+pub fn expand(exec: &str, values: &FieldValues) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+
+    for pieces in split(exec)? {
+        // A field code standing alone may expand to no argument or to two
+        if let [Piece::Field(code)] = pieces.as_slice() {
+            match code {
+                'f' | 'F' | 'u' | 'U' | 'd' | 'D' | 'n' | 'N' | 'v' | 'm' => continue,
+                'i' => {
+                    if let Some(icon) = values.icon.filter(|i| !i.is_empty()) {
+                        args.push("--icon".to_string());
+                        args.push(icon.to_string());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let mut arg = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Text(text) => arg.push_str(&text),
+                Piece::Field('c') => arg.push_str(values.name),
+                Piece::Field('i') => arg.push_str(values.icon.unwrap_or_default()),
+                Piece::Field('k') => {
+                    if let Some(path) = values.desktop_file {
+                        arg.push_str(&path.to_string_lossy());
+                    }
+                }
+                // File and URL codes have nothing to expand to; deprecated
+                // and unknown codes are dropped
+                Piece::Field(_) => {}
+            }
+        }
+        args.push(arg);
+    }
+
+    if args.is_empty() {
+        return Err("Empty exec command".to_string());
+    }
+    Ok(args)
+}
+
+/// Tokenize an Exec value into arguments made of text and field codes
+fn split(exec: &str) -> Result<Vec<Vec<Piece>>, String> {
+    let mut args: Vec<Vec<Piece>> = Vec::new();
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut text = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            // Inside quotes only `"`, `` ` ``, `$` and `\` may be escaped;
+            // outside them, be lenient and take the next character as is
+            '\\' => {
+                let next = chars
+                    .next()
+                    .ok_or_else(|| "Exec ends with a backslash".to_string())?;
+                if quoted && !matches!(next, '"' | '`' | '$' | '\\') {
+                    text.push('\\');
+                }
+                text.push(next);
+                in_arg = true;
+            }
+            '%' => {
+                match chars.next() {
+                    Some('%') => text.push('%'),
+                    Some(code) => {
+                        if !text.is_empty() {
+                            pieces.push(Piece::Text(std::mem::take(&mut text)));
+                        }
+                        pieces.push(Piece::Field(code));
+                    }
+                    None => text.push('%'),
+                }
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    args.push(std::mem::take(&mut pieces));
+                    in_arg = false;
+                }
+            }
+            c => {
+                text.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quoted {
+        return Err(format!("Unterminated quote in Exec: {}", exec));
+    }
+    if in_arg {
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        args.push(pieces);
+    }
+    Ok(args)
+}
+
+/// Split leading `NAME=value` arguments off a command line.
+///
+/// The spec doesn't allow them, but some Exec lines set variables that way
+/// instead of going through `env`.
+pub fn split_env(args: &[String]) -> (Vec<(&str, &str)>, &[String]) {
+    let count = args
+        .iter()
+        .take_while(|arg| {
+            arg.split_once('=').is_some_and(|(name, _)| {
+                !name.is_empty()
+                    && !name.starts_with(|c: char| c.is_ascii_digit())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+        })
+        .count();
+
+    let vars = args[..count]
+        .iter()
+        .filter_map(|arg| arg.split_once('='))
+        .collect();
+    (vars, &args[count..])
+}
+
+/// Quote a literal argument, such as a file path, for an Exec value
+pub fn quote(arg: &str) -> String {
+    const RESERVED: &[char] = &[
+        ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(',
+        ')', '`',
+    ];
+    let arg = arg.replace('%', "%%");
+    if arg.is_empty() || arg.contains(RESERVED) {
+        let mut quoted = String::from('"');
+        for c in arg.chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        arg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freedesktop_desktop_entry::DesktopEntry;
+
+    /// Expand the Exec line of a desktop file the way a launch would
+    fn launch_args(desktop: &str) -> Vec<String> {
+        let path = Path::new("/usr/share/applications/test.desktop");
+        let entry = DesktopEntry::from_str(path, desktop, None::<&[&str]>).unwrap();
+        let name = entry.name(&[] as &[&str]).unwrap();
+        let values = FieldValues {
+            name: &name,
+            icon: entry.icon(),
+            desktop_file: Some(path),
+        };
+        expand(entry.exec().unwrap(), &values).unwrap()
+    }
+
+    #[test]
+    fn test_file_codes_are_removed() {
+        let args = launch_args(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Visual Studio Code\n\
+             Exec=/usr/share/code/code --unity-launch %F\n",
+        );
+        assert_eq!(args, vec!["/usr/share/code/code", "--unity-launch"]);
+    }
+
+    #[test]
+    fn test_wine_shortcut() {
+        // Wine writes Windows paths with doubly escaped backslashes
+        let args = launch_args(
+            "[Desktop Entry]\n\
+             Name=Notepad++\n\
+             Exec=env WINEPREFIX=\"/home/me/.wine\" wine C:\\\\\\\\windows\\\\\\\\command\\\\\\\\start.exe \
+             \"/Unix /home/me/.wine/drive_c/Program Files/Notepad++/notepad++.exe\"\n\
+             Type=Application\n",
+        );
+        assert_eq!(
+            args,
+            vec![
+                "env",
+                "WINEPREFIX=/home/me/.wine",
+                "wine",
+                r"C:\windows\command\start.exe",
+                "/Unix /home/me/.wine/drive_c/Program Files/Notepad++/notepad++.exe",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatpak_exports() {
+        let args = launch_args(
+            "[Desktop Entry]\n\
+             Name=OBS Studio\n\
+             Icon=com.obsproject.Studio\n\
+             Exec=/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=obs \
+             --file-forwarding com.obsproject.Studio @@u %U @@\n",
+        );
+        assert_eq!(
+            args,
+            vec![
+                "/usr/bin/flatpak",
+                "run",
+                "--branch=stable",
+                "--arch=x86_64",
+                "--command=obs",
+                "--file-forwarding",
+                "com.obsproject.Studio",
+                "@@u",
+                "@@",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_icon_name_and_location() {
+        let args = launch_args(
+            "[Desktop Entry]\n\
+             Name=XTerm\n\
+             Icon=mini.xterm\n\
+             Exec=xterm %i -T %c -xrm \"XTerm*title: %c\" -class %k\n\
+             Terminal=false\n",
+        );
+        assert_eq!(
+            args,
+            vec![
+                "xterm",
+                "--icon",
+                "mini.xterm",
+                "-T",
+                "XTerm",
+                "-xrm",
+                "XTerm*title: XTerm",
+                "-class",
+                "/usr/share/applications/test.desktop",
+            ]
+        );
+
+        let no_icon = FieldValues {
+            name: "xterm",
+            ..Default::default()
+        };
+        assert_eq!(expand("xterm %i", &no_icon).unwrap(), vec!["xterm"]);
+    }
+
+    #[test]
+    fn test_escapes_inside_quotes() {
+        assert_eq!(
+            expand(
+                r#"sh -c "echo \"\$HOME\" \`date\` \\ done""#,
+                &FieldValues::default()
+            )
+            .unwrap(),
+            vec!["sh", "-c", r#"echo "$HOME" `date` \ done"#]
+        );
+    }
+
+    #[test]
+    fn test_percent_and_deprecated_codes() {
+        assert_eq!(
+            expand("date \"+%%H:%%M\" %d %N %m", &FieldValues::default()).unwrap(),
+            vec!["date", "+%H:%M"]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(expand("app \"unterminated", &FieldValues::default()).is_err());
+        assert!(expand("%U", &FieldValues::default()).is_err());
+        assert!(expand("   ", &FieldValues::default()).is_err());
+    }
+
+    #[test]
+    fn test_env_assignments() {
+        let args: Vec<String> = ["GDK_BACKEND=x11", "LANG=C", "app", "X=1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (vars, command) = split_env(&args);
+        assert_eq!(vars, vec![("GDK_BACKEND", "x11"), ("LANG", "C")]);
+        assert_eq!(command, &args[2..]);
+
+        let args = vec!["--opt=1".to_string()];
+        assert!(split_env(&args).0.is_empty());
+    }
+
+    #[test]
+    fn test_quote() {
+        for arg in [
+            "/opt/tool",
+            "/home/me/My Apps/100% \"real\" $tool`s.AppImage",
+        ] {
+            assert_eq!(
+                expand(&quote(arg), &FieldValues::default()).unwrap(),
+                vec![arg]
+            );
+        }
+        assert_eq!(quote("/opt/tool"), "/opt/tool");
+    }
+}
@@ -0,0 +1,645 @@
+//! Icon lookup following the freedesktop Icon Theme Specification
+//!
+//! Icons are looked up in the user's theme, then in the themes it inherits
+//! from and in `hicolor`, picking the directory that matches the requested
+//! size and scale or else the closest one. Names no theme has are looked up
+//! directly in the base directories, which covers `/usr/share/pixmaps`.
+//!
+//! Found paths are kept in a small index in the cache directory, so later
+//! runs don't walk the theme directories again. The index is written when
+//! a batch of lookups is flushed, not after every icon.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{xdg, xpm};
+use crate::traits::IconLookup;
+
+/// Image formats in the order the spec prefers them
+const EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+/// How the icons of a theme directory may be scaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeType {
+    Fixed,
+    Scalable { min: u32, max: u32 },
+    Threshold(u32),
+}
+
+/// A subdirectory of a theme, as described by its `index.theme` group
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ThemeDir {
+    path: String,
+    size: u32,
+    scale: u32,
+    size_type: SizeType,
+}
+
+impl ThemeDir {
+    fn parse(path: &str, keys: &HashMap<String, String>) -> Option<Self> {
+        let number = |key: &str| keys.get(key).and_then(|v| v.trim().parse::<u32>().ok());
+        let size = number("Size")?;
+        let size_type = match keys.get("Type").map(|t| t.trim()) {
+            Some("Fixed") => SizeType::Fixed,
+            Some("Scalable") => SizeType::Scalable {
+                min: number("MinSize").unwrap_or(size),
+                max: number("MaxSize").unwrap_or(size),
+            },
+            _ => SizeType::Threshold(number("Threshold").unwrap_or(2)),
+        };
+        Some(Self {
+            path: path.to_string(),
+            size,
+            scale: number("Scale").unwrap_or(1),
+            size_type,
+        })
+    }
+
+    /// Sizes this directory covers, in its own scale
+    fn size_range(&self) -> (u32, u32) {
+        match self.size_type {
+            SizeType::Fixed => (self.size, self.size),
+            SizeType::Scalable { min, max } => (min, max),
+            SizeType::Threshold(t) => (self.size.saturating_sub(t), self.size + t),
+        }
+    }
+
+    /// The spec's `DirectoryMatchesSize`
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        let (min, max) = self.size_range();
+        self.scale == scale && (min..=max).contains(&size)
+    }
+
+    /// The spec's `DirectorySizeDistance`, in device pixels
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let (min, max) = self.size_range();
+        let wanted = size * scale;
+        let (min, max) = (min * self.scale, max * self.scale);
+        if wanted < min {
+            min - wanted
+        } else {
+            wanted.saturating_sub(max)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IconTheme {
+    name: String,
+    /// The theme's directory in each base directory that has it
+    roots: Vec<PathBuf>,
+    dirs: Vec<ThemeDir>,
+}
+
+impl IconTheme {
+    /// Load a theme from the first `index.theme` found, returning the
+    /// themes it inherits from too
+    fn load(name: &str, base_dirs: &[PathBuf]) -> Option<(Self, Vec<String>)> {
+        let roots: Vec<PathBuf> = base_dirs
+            .iter()
+            .map(|base| base.join(name))
+            .filter(|root| root.is_dir())
+            .collect();
+        let index = roots
+            .iter()
+            .map(|root| root.join("index.theme"))
+            .find(|index| index.is_file())?;
+        let groups = parse_ini(&fs::read_to_string(index).ok()?);
+        let theme = groups.get("Icon Theme")?;
+
+        let list = |key: &str| -> Vec<String> {
+            theme
+                .get(key)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let mut dir_names = list("Directories");
+        for dir in list("ScaledDirectories") {
+            if !dir_names.contains(&dir) {
+                dir_names.push(dir);
+            }
+        }
+        let dirs = dir_names
+            .iter()
+            .filter_map(|dir| ThemeDir::parse(dir, groups.get(dir)?))
+            .collect();
+
+        let theme = Self {
+            name: name.to_string(),
+            roots,
+            dirs,
+        };
+        Some((theme, list("Inherits")))
+    }
+
+    /// The spec's `LookupIcon`: an exact size match, else the closest size
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        for dir in self.dirs.iter().filter(|d| d.matches_size(size, scale)) {
+            if let Some(path) = self.find_file(dir, name) {
+                return Some(path);
+            }
+        }
+
+        let mut closest: Option<(u32, PathBuf)> = None;
+        for dir in &self.dirs {
+            let distance = dir.size_distance(size, scale);
+            if closest.as_ref().is_some_and(|(best, _)| *best <= distance) {
+                continue;
+            }
+            if let Some(path) = self.find_file(dir, name) {
+                closest = Some((distance, path));
+            }
+        }
+        closest.map(|(_, path)| path)
+    }
+
+    fn find_file(&self, dir: &ThemeDir, name: &str) -> Option<PathBuf> {
+        self.roots.iter().find_map(|root| {
+            EXTENSIONS
+                .iter()
+                .map(|ext| root.join(&dir.path).join(format!("{}.{}", name, ext)))
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+/// Resolved icon paths, with the found ones persisted between runs
+#[derive(Debug, Default)]
+struct IconCache {
+    /// Index file in the cache directory
+    file: Option<PathBuf>,
+    /// The configured theme the index was written for
+    theme: String,
+    found: HashMap<String, PathBuf>,
+    missing: HashSet<String>,
+    /// Whether `found` changed since the index was written
+    changed: bool,
+}
+
+impl IconCache {
+    /// Read the index, which only holds while the theme stays the same
+    fn load(file: Option<PathBuf>, theme: &str) -> Self {
+        let mut found = HashMap::new();
+        if let Some(content) = file.as_ref().and_then(|f| fs::read_to_string(f).ok()) {
+            let mut lines = content.lines();
+            if lines.next() == Some(&format!("theme\t{}", theme)) {
+                found = lines
+                    .filter_map(|line| line.split_once('\t'))
+                    .map(|(key, path)| (key.to_string(), PathBuf::from(path)))
+                    .collect();
+            }
+        }
+        Self {
+            file,
+            theme: theme.to_string(),
+            found,
+            missing: HashSet::new(),
+            changed: false,
+        }
+    }
+
+    /// Write the index if anything was found since it was last written
+    fn save(&mut self) {
+        if !std::mem::take(&mut self.changed) {
+            return;
+        }
+        let Some(file) = &self.file else { return };
+        let mut content = format!("theme\t{}\n", self.theme);
+        for (key, path) in &self.found {
+            content.push_str(&format!("{}\t{}\n", key, path.display()));
+        }
+        let written = file
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(file, content));
+        if let Err(e) = written {
+            eprintln!("[Nova] Failed to write icon cache: {}", e);
+        }
+    }
+}
+
+/// Icon lookup in the freedesktop icon themes
+pub struct FreedesktopIcons {
+    base_dirs: Vec<PathBuf>,
+    /// The user's theme, the themes it inherits from, and hicolor last
+    themes: Vec<IconTheme>,
+    /// Where converted XPM images and the index go
+    cache_dir: Option<PathBuf>,
+    cache: Mutex<IconCache>,
+}
+
+impl FreedesktopIcons {
+    pub fn new() -> Self {
+        let cache_dir = dirs::cache_dir().map(|dir| dir.join("nova").join("icons"));
+        Self::with_theme(&current_theme(), xdg::icon_dirs(), cache_dir)
+    }
+
+    fn with_theme(theme: &str, base_dirs: Vec<PathBuf>, cache_dir: Option<PathBuf>) -> Self {
+        let mut themes = Vec::new();
+        add_theme(theme, &base_dirs, &mut themes);
+        if !themes.iter().any(|t| t.name == "hicolor") {
+            if let Some((hicolor, _)) = IconTheme::load("hicolor", &base_dirs) {
+                themes.push(hicolor);
+            }
+        }
+
+        let index = cache_dir.as_ref().map(|dir| dir.join("index"));
+        let cache = IconCache::load(index, theme);
+        Self {
+            base_dirs,
+            themes,
+            cache_dir,
+            cache: Mutex::new(cache),
+        }
+    }
+
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        self.themes
+            .iter()
+            .find_map(|theme| theme.lookup(name, size, scale))
+            .or_else(|| {
+                // Icons outside any theme, such as those in /usr/share/pixmaps
+                self.base_dirs.iter().find_map(|base| {
+                    EXTENSIONS
+                        .iter()
+                        .map(|ext| base.join(format!("{}.{}", name, ext)))
+                        .find(|path| path.is_file())
+                })
+            })
+    }
+
+    /// A PNG or SVG version of an icon file, converting XPM images
+    fn drawable(&self, path: PathBuf) -> Option<PathBuf> {
+        if path.extension().and_then(|e| e.to_str()) != Some("xpm") {
+            return Some(path);
+        }
+
+        let name = path
+            .to_string_lossy()
+            .trim_start_matches('/')
+            .replace('/', "_");
+        let png = self.cache_dir.as_ref()?.join(format!("{}.png", name));
+        if png.is_file() {
+            return Some(png);
+        }
+        match convert_xpm(&path, &png) {
+            Ok(()) => Some(png),
+            Err(e) => {
+                eprintln!("[Nova] Failed to convert {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+impl Default for FreedesktopIcons {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IconLookup for FreedesktopIcons {
+    fn find_icon(&self, name: &str, size: u16, scale: u16) -> Option<PathBuf> {
+        let path = Path::new(name);
+        if path.is_absolute() {
+            return path
+                .is_file()
+                .then(|| path.to_path_buf())
+                .and_then(|p| self.drawable(p));
+        }
+        if name.is_empty() || name.contains('/') {
+            return None;
+        }
+        // Some entries name the file instead of the icon
+        let name = EXTENSIONS
+            .iter()
+            .find_map(|ext| name.strip_suffix(&format!(".{}", ext)))
+            .unwrap_or(name);
+
+        let key = format!("{}@{}x{}", name, size, scale.max(1));
+        {
+            let cache = self.cache.lock().ok()?;
+            if cache.missing.contains(&key) {
+                return None;
+            }
+            if let Some(path) = cache.found.get(&key).filter(|p| p.is_file()) {
+                return Some(path.clone());
+            }
+        }
+
+        let found = self
+            .lookup(name, size as u32, scale.max(1) as u32)
+            .and_then(|path| self.drawable(path));
+
+        let mut cache = self.cache.lock().ok()?;
+        match &found {
+            Some(path) => {
+                cache.found.insert(key, path.clone());
+                cache.changed = true;
+            }
+            // Not persisted, so newly installed icons show up next run
+            None => {
+                cache.missing.insert(key);
+            }
+        }
+        found
+    }
+
+    fn flush(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.save();
+        }
+    }
+}
+
+/// Add a theme and, depth first, the themes it inherits from
+fn add_theme(name: &str, base_dirs: &[PathBuf], themes: &mut Vec<IconTheme>) {
+    if themes.iter().any(|t| t.name == name) {
+        return;
+    }
+    let Some((theme, parents)) = IconTheme::load(name, base_dirs) else {
+        return;
+    };
+    themes.push(theme);
+    for parent in parents {
+        add_theme(&parent, base_dirs, themes);
+    }
+}
+
+/// Name of the user's icon theme
+fn current_theme() -> String {
+    let config = dirs::config_dir().unwrap_or_default();
+    let configured = ["gtk-4.0/settings.ini", "gtk-3.0/settings.ini"]
+        .iter()
+        .find_map(|file| ini_value(&config.join(file), "Settings", "gtk-icon-theme-name"))
+        .or_else(|| ini_value(&config.join("kdeglobals"), "Icons", "Theme"));
+    if let Some(theme) = configured {
+        return theme;
+    }
+
+    let desktops = xdg::current_desktops();
+    let running = |name: &str| desktops.iter().any(|d| d.eq_ignore_ascii_case(name));
+    if running("KDE") {
+        return "breeze".to_string();
+    }
+    if running("GNOME") || running("Unity") || running("Budgie") {
+        // GNOME keeps its settings in dconf rather than settings.ini
+        let theme = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .trim()
+                    .trim_matches('\'')
+                    .to_string()
+            })
+            .filter(|theme| !theme.is_empty());
+        return theme.unwrap_or_else(|| "Adwaita".to_string());
+    }
+    "hicolor".to_string()
+}
+
+/// Read a key of an INI style file such as `settings.ini` or `kdeglobals`
+pub(super) fn ini_value(path: &Path, group: &str, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    parse_ini(&content)
+        .get(group)?
+        .get(key)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Parse an INI style file into its groups
+fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut groups: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = Some(name.to_string());
+            continue;
+        }
+        if let (Some(group), Some((key, value))) = (&current, line.split_once('=')) {
+            groups
+                .entry(group.clone())
+                .or_default()
+                .entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+    groups
+}
+
+fn convert_xpm(source: &Path, target: &Path) -> Result<(), String> {
+    let content = fs::read(source).map_err(|e| e.to_string())?;
+    let image = xpm::decode(&String::from_utf8_lossy(&content))?;
+
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let file = fs::File::create(target).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.rgba))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory with the given files
+    fn tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("nova-icons-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    const HICOLOR: &str = "[Icon Theme]\n\
+        Name=Hicolor\n\
+        Directories=16x16/apps,48x48/apps,scalable/apps\n\
+        ScaledDirectories=48x48@2/apps\n\
+        [16x16/apps]\n\
+        Size=16\n\
+        Type=Threshold\n\
+        [48x48/apps]\n\
+        Size=48\n\
+        Type=Fixed\n\
+        [48x48@2/apps]\n\
+        Size=48\n\
+        Scale=2\n\
+        Type=Fixed\n\
+        [scalable/apps]\n\
+        Size=128\n\
+        MinSize=8\n\
+        MaxSize=512\n\
+        Type=Scalable\n";
+
+    const PAPIRUS: &str = "[Icon Theme]\n\
+        Name=Papirus\n\
+        Inherits=breeze,hicolor\n\
+        Directories=24x24/apps\n\
+        [24x24/apps]\n\
+        Size=24\n";
+
+    #[test]
+    fn test_directory_matching() {
+        let groups = parse_ini(HICOLOR);
+        let dir = |name: &str| ThemeDir::parse(name, &groups[name]).unwrap();
+
+        let threshold = dir("16x16/apps");
+        assert!(threshold.matches_size(18, 1));
+        assert!(!threshold.matches_size(19, 1));
+        assert!(!threshold.matches_size(16, 2));
+
+        let fixed = dir("48x48@2/apps");
+        assert!(fixed.matches_size(48, 2));
+        assert_eq!(fixed.size_distance(32, 2), 32);
+
+        let scalable = dir("scalable/apps");
+        assert!(scalable.matches_size(256, 1));
+        assert_eq!(scalable.size_distance(1024, 1), 512);
+    }
+
+    #[test]
+    fn test_lookup_order() {
+        let user = tree(
+            "user",
+            &[
+                ("Papirus/index.theme", PAPIRUS),
+                ("Papirus/24x24/apps/firefox.svg", ""),
+            ],
+        );
+        let system = tree(
+            "system",
+            &[
+                ("hicolor/index.theme", HICOLOR),
+                ("hicolor/16x16/apps/firefox.png", ""),
+                ("hicolor/16x16/apps/gimp.png", ""),
+                ("hicolor/48x48/apps/gimp.png", ""),
+                ("hicolor/48x48@2/apps/gimp.png", ""),
+                ("hicolor/scalable/apps/gimp.svg", ""),
+                ("hicolor/scalable/apps/inkscape.svg", ""),
+                ("Papirus/24x24/apps/inkscape.png", ""),
+                ("pixmaps/xterm.png", ""),
+            ],
+        );
+        let icons = FreedesktopIcons::with_theme(
+            "Papirus",
+            vec![user.clone(), system.clone(), system.join("pixmaps")],
+            None,
+        );
+        let find = |name: &str, size: u16, scale: u16| {
+            icons
+                .find_icon(name, size, scale)
+                .map(|p| {
+                    p.strip_prefix(&system)
+                        .or(p.strip_prefix(&user))
+                        .unwrap()
+                        .to_path_buf()
+                })
+                .map(|p| p.to_string_lossy().to_string())
+        };
+
+        // The user's theme wins over hicolor, even at a worse size
+        assert_eq!(
+            find("firefox", 16, 1).as_deref(),
+            Some("Papirus/24x24/apps/firefox.svg")
+        );
+        // A theme's files may be spread over several base directories
+        assert_eq!(
+            find("inkscape", 24, 1).as_deref(),
+            Some("Papirus/24x24/apps/inkscape.png")
+        );
+        // Exact matches first, then the closest size
+        assert_eq!(
+            find("gimp", 48, 1).as_deref(),
+            Some("hicolor/48x48/apps/gimp.png")
+        );
+        assert_eq!(
+            find("gimp", 48, 2).as_deref(),
+            Some("hicolor/48x48@2/apps/gimp.png")
+        );
+        assert_eq!(
+            find("gimp", 256, 1).as_deref(),
+            Some("hicolor/scalable/apps/gimp.svg")
+        );
+        assert_eq!(
+            find("gimp.png", 14, 1).as_deref(),
+            Some("hicolor/16x16/apps/gimp.png")
+        );
+        // Unthemed icons
+        assert_eq!(find("xterm", 32, 1).as_deref(), Some("pixmaps/xterm.png"));
+        assert_eq!(find("missing", 32, 1), None);
+        assert_eq!(find("../hicolor/index", 32, 1), None);
+
+        let _ = fs::remove_dir_all(user);
+        let _ = fs::remove_dir_all(system);
+    }
+
+    #[test]
+    fn test_cache_and_xpm() {
+        let root = tree(
+            "cache",
+            &[("pixmaps/old.xpm", "\"1 1 1 1\", \". c #fff\", \".\"")],
+        );
+        let cache_dir = root.join("cache");
+        let icons = FreedesktopIcons::with_theme(
+            "hicolor",
+            vec![root.join("pixmaps")],
+            Some(cache_dir.clone()),
+        );
+
+        let png = icons.find_icon("old", 32, 1).unwrap();
+        assert!(png.starts_with(&cache_dir));
+        assert_eq!(png.extension().unwrap(), "png");
+        assert!(!cache_dir.join("index").exists());
+        icons.flush();
+
+        let index = fs::read_to_string(cache_dir.join("index")).unwrap();
+        assert!(index.starts_with("theme\thicolor\n"));
+        assert!(index.contains(&format!("old@32x1\t{}", png.display())));
+
+        // The index is only used for the theme it was written for
+        let cache = IconCache::load(Some(cache_dir.join("index")), "hicolor");
+        assert_eq!(cache.found.get("old@32x1"), Some(&png));
+        assert!(IconCache::load(Some(cache_dir.join("index")), "breeze")
+            .found
+            .is_empty());
+
+        // A configured theme that isn't installed keys the index too
+        let icons = FreedesktopIcons::with_theme(
+            "breeze",
+            vec![root.join("pixmaps")],
+            Some(cache_dir.clone()),
+        );
+        icons.find_icon("old", 32, 1).unwrap();
+        icons.flush();
+        let cache = IconCache::load(Some(cache_dir.join("index")), "breeze");
+        assert_eq!(cache.found.get("old@32x1"), Some(&png));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
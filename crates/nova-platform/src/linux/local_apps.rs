@@ -0,0 +1,289 @@
+//! Apps without an installed desktop entry: AppImages and command line
+//! programs kept in user directories such as `~/Applications` and
+//! `~/.local/bin`
+//!
+//! AppImages are shown with the name, description and icon of the desktop
+//! entry they embed, and their icons are extracted to the cache directory.
+//! Other programs are listed by file name and run in a terminal. Only
+//! executable files are listed, so a downloaded AppImage shows up once it
+//! is marked executable.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use freedesktop_desktop_entry::DesktopEntry;
+use nova_core::PlatformAppEntry;
+
+use super::app_discovery;
+use super::appimage::{self, AppImage};
+use super::exec::{self, FieldValues};
+use super::xdg;
+
+/// Architecture suffixes of AppImage file names
+const ARCHITECTURES: &[&str] = &[
+    "x86", "x64", "amd64", "i386", "i686", "aarch64", "arm64", "armhf",
+];
+
+/// Index the executables in `dirs`, skipping programs that an indexed
+/// desktop entry already launches
+pub fn scan(
+    dirs: &[PathBuf],
+    indexed: &[PlatformAppEntry],
+    locales: &[String],
+) -> Vec<PlatformAppEntry> {
+    let launched: HashSet<String> = indexed
+        .iter()
+        .filter_map(|app| program(&app.exec))
+        .collect();
+    let icon_dir = dirs::cache_dir().map(|dir| dir.join("nova").join("appimages"));
+    let mut names = HashSet::new();
+    let mut entries = Vec::new();
+
+    for dir in dirs {
+        let Ok(dir_entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = dir_entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let (Some(path_str), Some(file_name)) =
+                (path.to_str(), path.file_name().and_then(|n| n.to_str()))
+            else {
+                continue;
+            };
+            if file_name.starts_with('.') || !xdg::is_executable(&path) {
+                continue;
+            }
+            if launched.contains(file_name) || launched.contains(path_str) {
+                continue;
+            }
+            // Earlier directories shadow programs of the same name, as on PATH
+            if !names.insert(file_name.to_string()) {
+                continue;
+            }
+
+            entries.push(match appimage::appimage_type(&path) {
+                Some(_) => appimage_entry(&path, locales, icon_dir.as_deref()),
+                None => program_entry(&path),
+            });
+        }
+    }
+    entries
+}
+
+/// The program an Exec line runs
+fn program(exec: &str) -> Option<String> {
+    let args = exec::expand(exec, &FieldValues::default()).ok()?;
+    let (_, mut args) = exec::split_env(&args);
+    if args.first().is_some_and(|arg| arg == "env") {
+        args = exec::split_env(&args[1..]).1;
+    }
+    args.first().cloned()
+}
+
+/// An AppImage, described by its embedded desktop entry when it can be read
+fn appimage_entry(path: &Path, locales: &[String], icon_dir: Option<&Path>) -> PlatformAppEntry {
+    let path_str = path.to_string_lossy();
+    let name = appimage_name(path);
+    let mut app = PlatformAppEntry {
+        id: path_str.to_string(),
+        keywords: name.split_whitespace().map(|s| s.to_lowercase()).collect(),
+        name,
+        exec: exec::quote(&path_str),
+        icon: None,
+        description: Some("AppImage".to_string()),
+        desktop_file: None,
+        terminal: false,
+        actions: Vec::new(),
+    };
+
+    let contents = AppImage::open(path).and_then(|mut appimage| {
+        let desktop_entry = appimage.desktop_entry()?;
+        Ok((appimage, desktop_entry))
+    });
+    let (mut appimage, content) = match contents {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("[Nova] Failed to read AppImage {}: {}", path.display(), e);
+            return app;
+        }
+    };
+    let Ok(entry) = DesktopEntry::from_str(path, &content, Some(locales)) else {
+        return app;
+    };
+
+    if let Some(name) = entry.name(locales) {
+        app.name = name.to_string();
+    }
+    app.keywords = app_discovery::keywords(&entry, &app.name, locales);
+    if let Some(comment) = entry.comment(locales) {
+        app.description = Some(comment.to_string());
+    }
+    app.terminal = entry.terminal();
+    app.icon = icon_dir.and_then(|dir| cached_icon(&mut appimage, entry.icon(), path, dir));
+    app
+}
+
+/// Extract an AppImage's icon into `dir`. Files are named after the
+/// AppImage's path and modification time, so an updated AppImage gets its
+/// icon extracted again.
+fn cached_icon(
+    appimage: &mut AppImage,
+    name: Option<&str>,
+    path: &Path,
+    dir: &Path,
+) -> Option<String> {
+    let modified = path.metadata().and_then(|m| m.modified()).ok();
+    let mut hasher = DefaultHasher::new();
+    (path, modified).hash(&mut hasher);
+    let stem = format!("{:016x}", hasher.finish());
+
+    for ext in ["png", "svg"] {
+        let file = dir.join(format!("{}.{}", stem, ext));
+        if file.is_file() {
+            return Some(file.to_string_lossy().into_owned());
+        }
+    }
+
+    let (ext, bytes) = appimage.icon(name)?;
+    let file = dir.join(format!("{}.{}", stem, ext));
+    if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&file, bytes)) {
+        eprintln!("[Nova] Failed to cache AppImage icon: {}", e);
+        return None;
+    }
+    Some(file.to_string_lossy().into_owned())
+}
+
+/// Name of an AppImage from its file name, without version and
+/// architecture: `Obsidian-1.5.3-x86_64.AppImage` becomes `Obsidian`
+fn appimage_name(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = match file_name.len().checked_sub(".AppImage".len()) {
+        Some(end)
+            if file_name
+                .get(end..)
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(".AppImage")) =>
+        {
+            &file_name[..end]
+        }
+        _ => &file_name,
+    };
+
+    let words: Vec<&str> = stem
+        .split(['-', '_'])
+        .take_while(|part| {
+            !part.starts_with(|c: char| c.is_ascii_digit())
+                && !ARCHITECTURES.contains(&part.to_lowercase().as_str())
+        })
+        .filter(|part| !part.is_empty())
+        .collect();
+    if words.is_empty() {
+        stem.to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+/// A command line program, run in a terminal
+fn program_entry(path: &Path) -> PlatformAppEntry {
+    let path_str = path.to_string_lossy();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let location = match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path_str.to_string(),
+    };
+
+    PlatformAppEntry {
+        id: path_str.to_string(),
+        name,
+        exec: exec::quote(&path_str),
+        icon: Some("utilities-terminal".to_string()),
+        description: Some(format!("{} \u{2014} runs in terminal", location)),
+        keywords: Vec::new(),
+        desktop_file: None,
+        terminal: true,
+        actions: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_appimage_name() {
+        let name = |file: &str| appimage_name(Path::new(file));
+        assert_eq!(name("Obsidian-1.5.3.AppImage"), "Obsidian");
+        assert_eq!(name("Nextcloud-3.10.1-x86_64.AppImage"), "Nextcloud");
+        assert_eq!(name("balenaEtcher-1.18.11-x64.appimage"), "balenaEtcher");
+        assert_eq!(name("Some_Tool-x86_64.AppImage"), "Some Tool");
+        assert_eq!(name("2048.AppImage"), "2048");
+    }
+
+    #[test]
+    fn test_scan_programs() {
+        let root = std::env::temp_dir().join(format!("nova-local-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (first, second) = (root.join("bin"), root.join("cargo"));
+        let files = [
+            (first.join("tool"), 0o755),
+            (first.join("notes.txt"), 0o644),
+            (first.join(".hidden"), 0o755),
+            (second.join("tool"), 0o755),
+            (second.join("firefox"), 0o755),
+            (second.join("rg"), 0o755),
+        ];
+        for (path, mode) in &files {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(path, fs::Permissions::from_mode(*mode)).unwrap();
+        }
+
+        let firefox = PlatformAppEntry {
+            id: "firefox".to_string(),
+            name: "Firefox".to_string(),
+            exec: "env MOZ_ENABLE_WAYLAND=1 firefox %u".to_string(),
+            icon: None,
+            description: None,
+            keywords: Vec::new(),
+            desktop_file: None,
+            terminal: false,
+            actions: Vec::new(),
+        };
+        let entries = scan(&[first.clone(), second.clone()], &[firefox], &[]);
+        fs::remove_dir_all(&root).unwrap();
+
+        let found: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.id.as_str()))
+            .collect();
+        let tool = first.join("tool");
+        let rg = second.join("rg");
+        assert_eq!(
+            found,
+            vec![
+                ("tool", tool.to_str().unwrap()),
+                ("rg", rg.to_str().unwrap())
+            ]
+        );
+        assert!(entries.iter().all(|e| e.terminal));
+        assert!(entries[0]
+            .description
+            .as_deref()
+            .is_some_and(|d| d.ends_with("runs in terminal")));
+    }
+}
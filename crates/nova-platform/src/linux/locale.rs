@@ -0,0 +1,142 @@
+//! User locale detection for localized desktop entry keys
+
+/// Locales to look up localized keys with, most preferred first.
+///
+/// Follows the gettext lookup order: `LANGUAGE` (a colon-separated list)
+/// when a locale is set, then the first of `LC_ALL`, `LC_MESSAGES` and
+/// `LANG`. Each locale is expanded into the forms desktop entries may use,
+/// so `de_DE.UTF-8@euro` also matches `de_DE@euro`, `de_DE`, `de@euro`
+/// and `de`.
+pub fn user_locales() -> Vec<String> {
+    locales_from(|var| std::env::var(var).ok())
+}
+
+/// [`user_locales`] with the environment read through `var`
+fn locales_from(var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let primary = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty());
+
+    let mut names: Vec<String> = Vec::new();
+    // LANGUAGE is ignored for the "C" locale, as gettext does
+    if primary.as_deref().is_some_and(|p| !is_c_locale(p)) {
+        if let Some(language) = var("LANGUAGE") {
+            names.extend(language.split(':').map(|s| s.to_string()));
+        }
+    }
+    names.extend(primary);
+
+    let mut locales = Vec::new();
+    for name in names.iter().filter(|n| !n.is_empty() && !is_c_locale(n)) {
+        for variant in variants(name) {
+            if !locales.contains(&variant) {
+                locales.push(variant);
+            }
+        }
+    }
+    locales
+}
+
+fn is_c_locale(name: &str) -> bool {
+    name == "C" || name == "POSIX" || name.starts_with("C.")
+}
+
+/// Forms of `lang_COUNTRY.ENCODING@MODIFIER` to try, most specific first
+fn variants(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    // The encoding never appears in desktop entry keys
+    let rest = rest.split('.').next().unwrap_or(rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+
+    let mut variants = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{}@{}", lang, modifier));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locales(env: &[(&str, &str)]) -> Vec<String> {
+        locales_from(|name| {
+            env.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            locales(&[("LANG", "fr_FR.UTF-8"), ("LC_MESSAGES", "de_DE.UTF-8")]),
+            vec!["de_DE", "de"]
+        );
+        assert_eq!(
+            locales(&[
+                ("LANG", "fr_FR"),
+                ("LC_MESSAGES", "de_DE"),
+                ("LC_ALL", "es")
+            ]),
+            vec!["es"]
+        );
+        // Empty variables are skipped
+        assert_eq!(
+            locales(&[("LANG", "fr_FR"), ("LC_ALL", "")]),
+            vec!["fr_FR", "fr"]
+        );
+        // LANGUAGE comes first, without repeating locales
+        assert_eq!(
+            locales(&[("LANG", "de_DE.UTF-8"), ("LANGUAGE", "pt_BR:de")]),
+            vec!["pt_BR", "pt", "de", "de_DE"]
+        );
+        assert!(locales(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_c_locale() {
+        assert!(is_c_locale("C"));
+        assert!(is_c_locale("POSIX"));
+        assert!(is_c_locale("C.UTF-8"));
+        assert!(!is_c_locale("ca_ES"));
+
+        // LANGUAGE is ignored unless a real locale is set
+        assert!(locales(&[("LANG", "C.UTF-8"), ("LANGUAGE", "de")]).is_empty());
+        assert!(locales(&[("LC_ALL", "POSIX"), ("LANG", "de_DE")]).is_empty());
+        assert!(locales(&[("LANGUAGE", "de")]).is_empty());
+        assert_eq!(
+            locales(&[("LANG", "de_DE"), ("LANGUAGE", "C:fr")]),
+            vec!["fr", "de_DE", "de"]
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        assert_eq!(
+            variants("de_DE.UTF-8@euro"),
+            vec!["de_DE@euro", "de_DE", "de@euro", "de"]
+        );
+        assert_eq!(
+            variants("sr_RS@latin"),
+            vec!["sr_RS@latin", "sr_RS", "sr@latin", "sr"]
+        );
+        assert_eq!(variants("pt_BR.UTF-8"), vec!["pt_BR", "pt"]);
+        assert_eq!(variants("de@euro"), vec!["de@euro", "de"]);
+        assert_eq!(variants("fr"), vec!["fr"]);
+    }
+}
@@ -1,19 +1,39 @@
 mod app_discovery;
+mod app_watcher;
+mod appimage;
+mod exec;
+mod icons;
+mod local_apps;
+mod locale;
+mod notifications;
+mod squashfs;
+mod startup;
 mod system_commands;
+mod systemd;
+mod window_manager;
+mod xdg;
+mod xpm;
 
-use crate::shared::{ArboardClipboard, AutoLaunchAutostart, NotifyRustNotifications, OpenCrateOpener};
+use nova_core::Config;
+
+use crate::shared::{ArboardClipboard, AutoLaunchAutostart, OpenCrateOpener};
 use crate::traits::Platform;
 
 pub use app_discovery::LinuxAppDiscovery;
+pub use icons::FreedesktopIcons;
+pub use notifications::LinuxNotifications;
 pub use system_commands::LinuxSystemCommands;
+pub use window_manager::LinuxWindowManager;
 
-pub fn create_platform() -> Platform {
+pub fn create_platform(config: &Config) -> Platform {
     Platform {
-        apps: Box::new(LinuxAppDiscovery::new()),
-        system: Box::new(LinuxSystemCommands),
+        apps: Box::new(LinuxAppDiscovery::new(config.apps.local_dirs())),
+        windows: Box::new(LinuxWindowManager::new()),
+        system: Box::new(LinuxSystemCommands::new()),
         clipboard: Box::new(ArboardClipboard::new().expect("Failed to init clipboard")),
-        notifications: Box::new(NotifyRustNotifications),
+        notifications: Box::new(LinuxNotifications),
         opener: Box::new(OpenCrateOpener),
         autostart: Box::new(AutoLaunchAutostart::new().expect("Failed to init autostart")),
+        icons: Box::new(FreedesktopIcons::new()),
     }
 }
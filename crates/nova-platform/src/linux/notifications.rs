@@ -0,0 +1,269 @@
+//! Desktop notifications
+//!
+//! Plain notifications go through notify-rust. Countdowns talk to the
+//! notification server directly: they replace their notification every
+//! second and listen for its Cancel button at the same time, which
+//! notify-rust's handles can't do together.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use nova_core::NovaResult;
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type;
+use zbus::zvariant::Value;
+use zbus::MatchRule;
+
+use super::system_commands::bus::{self, Object};
+use crate::shared::NotifyRustNotifications;
+use crate::traits::Notifications;
+
+pub(super) const NOTIFICATIONS: Object = Object {
+    service: "org.freedesktop.Notifications",
+    path: "/org/freedesktop/Notifications",
+    interface: "org.freedesktop.Notifications",
+};
+
+/// Key of the countdown's Cancel button
+const CANCEL: &str = "cancel";
+
+/// Urgency that keeps the notification up until it is replaced
+const CRITICAL: u8 = 2;
+
+pub struct LinuxNotifications;
+
+impl Notifications for LinuxNotifications {
+    fn show(&self, title: &str, body: &str) -> NovaResult<()> {
+        NotifyRustNotifications.show(title, body)
+    }
+
+    fn countdown(&self, title: &str, seconds: u32) -> NovaResult<bool> {
+        count_down(&bus::session()?, title, seconds, Duration::from_secs(1))
+    }
+}
+
+/// Signals the notification server sends about a notification
+enum Signal {
+    ActionInvoked(String),
+    Closed,
+}
+
+/// Count down in a notification, replacing it every `tick`. The
+/// notification closing for any reason, such as the user dismissing it or
+/// the server dropping it, cancels the countdown like its Cancel button
+/// does, so a countdown never runs out unseen.
+fn count_down(session: &Connection, title: &str, seconds: u32, tick: Duration) -> NovaResult<bool> {
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface(NOTIFICATIONS.interface)
+        .and_then(|rule| rule.path(NOTIFICATIONS.path))
+        .map(|rule| rule.build())
+        .map_err(|e| bus::error("AddMatch", e))?;
+    // Listen before showing the notification so no signal is missed
+    let signals = MessageIterator::for_match_rule(rule, session, None)
+        .map_err(|e| bus::error("AddMatch", e))?;
+
+    let mut remaining = seconds.max(1);
+    let id = notify(session, 0, title, remaining)?;
+
+    let (cancel, cancelled) = mpsc::channel();
+    std::thread::spawn(move || {
+        for message in signals.flatten() {
+            match read_signal(&message, id) {
+                Some(Signal::ActionInvoked(key)) if key == CANCEL => {
+                    let _ = cancel.send(());
+                }
+                // Nova only closes the notification once the countdown is over
+                Some(Signal::Closed) => {
+                    let _ = cancel.send(());
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let ran_out = loop {
+        match cancelled.recv_timeout(tick) {
+            Ok(()) => break false,
+            Err(RecvTimeoutError::Timeout) => {}
+            // The notification closed without being cancelled
+            Err(RecvTimeoutError::Disconnected) => std::thread::sleep(tick),
+        }
+        remaining -= 1;
+        if remaining == 0 {
+            break true;
+        }
+        notify(session, id, title, remaining)?;
+    };
+
+    // Closing ends the listening thread too
+    NOTIFICATIONS.call(session, "CloseNotification", &(id,))?;
+    Ok(ran_out)
+}
+
+/// Show the countdown, replacing notification `replaces` unless it is 0
+fn notify(session: &Connection, replaces: u32, title: &str, remaining: u32) -> NovaResult<u32> {
+    let unit = if remaining == 1 { "second" } else { "seconds" };
+    let body = format!("Runs in {} {}", remaining, unit);
+    let hints = HashMap::from([("urgency", Value::from(CRITICAL))]);
+    NOTIFICATIONS.call_for(
+        session,
+        "Notify",
+        &(
+            "Nova",
+            replaces,
+            "",
+            title,
+            body.as_str(),
+            vec![CANCEL, "Cancel"],
+            hints,
+            0i32,
+        ),
+    )
+}
+
+/// Read a signal about notification `id`
+fn read_signal(message: &zbus::Message, id: u32) -> Option<Signal> {
+    let header = message.header();
+    let body = message.body();
+    match header.member()?.as_str() {
+        "ActionInvoked" => {
+            let (notification, key): (u32, String) = body.deserialize().ok()?;
+            (notification == id).then_some(Signal::ActionInvoked(key))
+        }
+        "NotificationClosed" => {
+            let (notification, _reason): (u32, u32) = body.deserialize().ok()?;
+            (notification == id).then_some(Signal::Closed)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use zbus::object_server::SignalEmitter;
+    use zbus::zvariant::OwnedValue;
+
+    use super::*;
+    use crate::linux::system_commands::bus::tests::TestBus;
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    const ID: u32 = 7;
+
+    /// `NotificationClosed` reason of notifications that expired
+    const EXPIRED: u32 = 1;
+
+    /// Notification server that presses Cancel on its `cancel_at`th call
+    /// and lets the notification expire on its `expire_at`th one
+    struct Server {
+        calls: Calls,
+        cancel_at: usize,
+        expire_at: usize,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Server {
+        #[allow(clippy::too_many_arguments)]
+        async fn notify(
+            &self,
+            _app_name: String,
+            replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            body: String,
+            actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> u32 {
+            let call = format!("Notify {} {}: {} {:?}", replaces_id, summary, body, actions);
+            let count = {
+                let mut calls = self.calls.lock().unwrap();
+                calls.push(call);
+                calls.len()
+            };
+            if count == self.cancel_at {
+                Self::action_invoked(&emitter, ID, CANCEL).await.unwrap();
+            }
+            if count == self.expire_at {
+                Self::notification_closed(&emitter, ID, EXPIRED)
+                    .await
+                    .unwrap();
+            }
+            ID
+        }
+
+        async fn close_notification(
+            &self,
+            id: u32,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) {
+            self.calls.lock().unwrap().push(format!("Close {}", id));
+            Self::notification_closed(&emitter, id, 3).await.unwrap();
+        }
+
+        #[zbus(signal)]
+        async fn action_invoked(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            action_key: &str,
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn notification_closed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            reason: u32,
+        ) -> zbus::Result<()>;
+    }
+
+    fn run_countdown(bus: &TestBus, cancel_at: usize, expire_at: usize) -> (bool, Vec<String>) {
+        let calls = Calls::default();
+        let server = Server {
+            calls: calls.clone(),
+            cancel_at,
+            expire_at,
+        };
+        let _server = bus.serve(NOTIFICATIONS.service, NOTIFICATIONS.path, server);
+
+        let tick = Duration::from_millis(100);
+        let ran_out = count_down(&bus.connect(), "Shut Down", 3, tick).unwrap();
+        let calls = calls.lock().unwrap().clone();
+        (ran_out, calls)
+    }
+
+    #[test]
+    fn test_countdown() {
+        let Some(bus) = TestBus::start() else {
+            return;
+        };
+
+        let (ran_out, calls) = run_countdown(&bus, 0, 0);
+        assert!(ran_out);
+        assert_eq!(
+            calls,
+            vec![
+                "Notify 0 Shut Down: Runs in 3 seconds [\"cancel\", \"Cancel\"]",
+                "Notify 7 Shut Down: Runs in 2 seconds [\"cancel\", \"Cancel\"]",
+                "Notify 7 Shut Down: Runs in 1 second [\"cancel\", \"Cancel\"]",
+                "Close 7",
+            ]
+        );
+
+        let (ran_out, calls) = run_countdown(&bus, 2, 0);
+        assert!(!ran_out);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[2], "Close 7");
+
+        // A notification closed by the server cancels too
+        let (ran_out, calls) = run_countdown(&bus, 0, 1);
+        assert!(!ran_out);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1], "Close 7");
+    }
+}
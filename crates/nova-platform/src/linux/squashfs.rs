@@ -0,0 +1,635 @@
+//! Read-only SquashFS access
+//!
+//! Covers what reading a few small files out of an image takes: the
+//! superblock, directory lookups with symlinks, and regular files made of
+//! data blocks and a tail fragment. Blocks are either stored uncompressed or
+//! gzip compressed; images using another compressor fail with an error once
+//! a compressed block is read.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use flate2::read::ZlibDecoder;
+
+const MAGIC: u32 = 0x7371_7368;
+const SUPERBLOCK_SIZE: usize = 96;
+const GZIP: u16 = 1;
+
+/// Uncompressed size of a metadata block
+const METADATA_BLOCK_SIZE: usize = 8192;
+/// Set in a metadata block header when the block is stored uncompressed
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+/// Set in a data block size when the block is stored uncompressed
+const DATA_UNCOMPRESSED: u32 = 1 << 24;
+/// Fragment index of files without a tail fragment
+const NO_FRAGMENT: u32 = 0xFFFF_FFFF;
+/// Size of a fragment table entry
+const FRAGMENT_ENTRY_SIZE: u64 = 16;
+
+/// Most symlinks followed while resolving one path
+const MAX_SYMLINKS: usize = 8;
+/// Largest file [`Image::read_file`] reads
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+/// The superblock fields needed for reading
+struct Superblock {
+    block_size: u32,
+    compressor: u16,
+    root_inode: u64,
+    inode_table: u64,
+    directory_table: u64,
+    fragment_table: u64,
+}
+
+enum Inode {
+    Directory {
+        block: u64,
+        offset: usize,
+        /// Size of the listing, plus 3 for the implicit `.` and `..`
+        size: usize,
+    },
+    File {
+        blocks_start: u64,
+        size: u64,
+        fragment: u32,
+        fragment_offset: usize,
+        block_sizes: Vec<u32>,
+    },
+    Symlink {
+        target: String,
+    },
+    Other,
+}
+
+/// Position in a metadata table, loading blocks as they are read
+struct Cursor {
+    /// Position of the next block to load
+    next_block: u64,
+    data: Vec<u8>,
+    at: usize,
+}
+
+/// A SquashFS image, possibly embedded at an offset in a larger file
+pub struct Image<R> {
+    reader: R,
+    offset: u64,
+    superblock: Superblock,
+}
+
+impl<R: Read + Seek> Image<R> {
+    /// Open the image starting at `offset` in `reader`
+    pub fn open(mut reader: R, offset: u64) -> io::Result<Self> {
+        let mut bytes = [0u8; SUPERBLOCK_SIZE];
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut bytes)?;
+
+        if u32_at(&bytes, 0) != MAGIC {
+            return Err(invalid("Not a SquashFS image"));
+        }
+        if u16_at(&bytes, 28) != 4 {
+            return Err(invalid("Unsupported SquashFS version"));
+        }
+        let superblock = Superblock {
+            block_size: u32_at(&bytes, 12),
+            compressor: u16_at(&bytes, 20),
+            root_inode: u64_at(&bytes, 32),
+            inode_table: u64_at(&bytes, 64),
+            directory_table: u64_at(&bytes, 72),
+            fragment_table: u64_at(&bytes, 80),
+        };
+        if !superblock.block_size.is_power_of_two() {
+            return Err(invalid("Invalid SquashFS block size"));
+        }
+
+        Ok(Self {
+            reader,
+            offset,
+            superblock,
+        })
+    }
+
+    /// Names of the entries in the root directory
+    pub fn root_entries(&mut self) -> io::Result<Vec<String>> {
+        let root = self.inode(self.superblock.root_inode)?;
+        Ok(self
+            .entries(&root)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// Read a regular file by its path from the root, following symlinks
+    pub fn read_file(&mut self, path: &str) -> io::Result<Vec<u8>> {
+        let Inode::File {
+            blocks_start,
+            size,
+            fragment,
+            fragment_offset,
+            block_sizes,
+        } = self.resolve(path)?
+        else {
+            return Err(invalid(format!("Not a regular file: {}", path)));
+        };
+        if size > MAX_FILE_SIZE {
+            return Err(invalid(format!("File too large: {}", path)));
+        }
+
+        let block_size = self.superblock.block_size as usize;
+        let mut data = Vec::with_capacity(size as usize);
+        let mut position = blocks_start;
+        for stored in block_sizes {
+            let length = stored & !DATA_UNCOMPRESSED;
+            // Blocks of zeros aren't stored
+            if length == 0 {
+                data.resize(data.len() + block_size, 0);
+                continue;
+            }
+            let raw = self.read_at(position, length as usize)?;
+            position += u64::from(length);
+            if stored & DATA_UNCOMPRESSED != 0 {
+                data.extend(raw);
+            } else {
+                data.extend(self.decompress(&raw, block_size)?);
+            }
+        }
+
+        if fragment != NO_FRAGMENT {
+            let tail = (size % u64::from(self.superblock.block_size)) as usize;
+            let block = self.fragment_block(fragment)?;
+            let bytes = block
+                .get(fragment_offset..fragment_offset + tail)
+                .ok_or_else(|| invalid("Fragment out of range"))?;
+            data.extend_from_slice(bytes);
+        }
+
+        data.truncate(size as usize);
+        Ok(data)
+    }
+
+    fn read_at(&mut self, position: u64, length: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0u8; length];
+        self.reader.seek(SeekFrom::Start(self.offset + position))?;
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn decompress(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        if self.superblock.compressor != GZIP {
+            let name = match self.superblock.compressor {
+                2 => "lzma",
+                3 => "lzo",
+                4 => "xz",
+                5 => "lz4",
+                6 => "zstd",
+                _ => "unknown",
+            };
+            return Err(invalid(format!(
+                "Unsupported SquashFS compression: {}",
+                name
+            )));
+        }
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(data)
+            .take(limit as u64)
+            .read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    /// Read the metadata block at `position`, returning its data and the
+    /// position of the block after it
+    fn metadata_block(&mut self, position: u64) -> io::Result<(Vec<u8>, u64)> {
+        let header = self.read_at(position, 2)?;
+        let header = u16_at(&header, 0);
+        let length = (header & !METADATA_UNCOMPRESSED) as usize;
+        if length == 0 || length > METADATA_BLOCK_SIZE {
+            return Err(invalid("Invalid SquashFS metadata block"));
+        }
+
+        let raw = self.read_at(position + 2, length)?;
+        let data = if header & METADATA_UNCOMPRESSED != 0 {
+            raw
+        } else {
+            self.decompress(&raw, METADATA_BLOCK_SIZE)?
+        };
+        if data.is_empty() {
+            return Err(invalid("Empty SquashFS metadata block"));
+        }
+        Ok((data, position + 2 + length as u64))
+    }
+
+    fn cursor(&self, block: u64, offset: usize) -> Cursor {
+        Cursor {
+            next_block: block,
+            data: Vec::new(),
+            at: offset,
+        }
+    }
+
+    fn take(&mut self, cursor: &mut Cursor, length: usize) -> io::Result<Vec<u8>> {
+        while cursor.data.len() < cursor.at + length {
+            let (block, next) = self.metadata_block(cursor.next_block)?;
+            cursor.data.extend(block);
+            cursor.next_block = next;
+        }
+        let bytes = cursor.data[cursor.at..cursor.at + length].to_vec();
+        cursor.at += length;
+        Ok(bytes)
+    }
+
+    /// Read the inode a reference points to: the metadata block's position
+    /// in the inode table in the upper bits, the offset in the block below
+    fn inode(&mut self, reference: u64) -> io::Result<Inode> {
+        let block = self.superblock.inode_table + (reference >> 16);
+        let mut cursor = self.cursor(block, (reference & 0xFFFF) as usize);
+
+        let header = self.take(&mut cursor, 16)?;
+        let inode = match u16_at(&header, 0) {
+            // Basic directory
+            1 => {
+                let fields = self.take(&mut cursor, 16)?;
+                Inode::Directory {
+                    block: u64::from(u32_at(&fields, 0)),
+                    size: usize::from(u16_at(&fields, 8)),
+                    offset: usize::from(u16_at(&fields, 10)),
+                }
+            }
+            // Extended directory
+            8 => {
+                let fields = self.take(&mut cursor, 24)?;
+                Inode::Directory {
+                    size: u32_at(&fields, 4) as usize,
+                    block: u64::from(u32_at(&fields, 8)),
+                    offset: usize::from(u16_at(&fields, 18)),
+                }
+            }
+            // Basic and extended file
+            kind @ (2 | 9) => {
+                let (blocks_start, size, fragment, fragment_offset) = if kind == 2 {
+                    let fields = self.take(&mut cursor, 16)?;
+                    (
+                        u64::from(u32_at(&fields, 0)),
+                        u64::from(u32_at(&fields, 12)),
+                        u32_at(&fields, 4),
+                        u32_at(&fields, 8),
+                    )
+                } else {
+                    let fields = self.take(&mut cursor, 40)?;
+                    (
+                        u64_at(&fields, 0),
+                        u64_at(&fields, 8),
+                        u32_at(&fields, 28),
+                        u32_at(&fields, 32),
+                    )
+                };
+                if size > MAX_FILE_SIZE {
+                    // Not read anyway, so skip the block list
+                    return Ok(Inode::File {
+                        blocks_start,
+                        size,
+                        fragment,
+                        fragment_offset: 0,
+                        block_sizes: Vec::new(),
+                    });
+                }
+
+                let block_size = u64::from(self.superblock.block_size);
+                let count = if fragment == NO_FRAGMENT {
+                    size.div_ceil(block_size)
+                } else {
+                    size / block_size
+                };
+                let sizes = self.take(&mut cursor, count as usize * 4)?;
+                Inode::File {
+                    blocks_start,
+                    size,
+                    fragment,
+                    fragment_offset: fragment_offset as usize,
+                    block_sizes: sizes.chunks(4).map(|s| u32_at(s, 0)).collect(),
+                }
+            }
+            // Basic and extended symlink
+            3 | 10 => {
+                let fields = self.take(&mut cursor, 8)?;
+                let target = self.take(&mut cursor, u32_at(&fields, 4) as usize)?;
+                Inode::Symlink {
+                    target: String::from_utf8_lossy(&target).into_owned(),
+                }
+            }
+            _ => Inode::Other,
+        };
+        Ok(inode)
+    }
+
+    /// Names and inode references of a directory's entries
+    fn entries(&mut self, directory: &Inode) -> io::Result<Vec<(String, u64)>> {
+        let Inode::Directory {
+            block,
+            offset,
+            size,
+        } = directory
+        else {
+            return Err(invalid("Not a directory"));
+        };
+
+        let truncated = || invalid("Truncated SquashFS directory");
+        let mut remaining = size.saturating_sub(3);
+        let mut cursor = self.cursor(self.superblock.directory_table + block, *offset);
+        let mut entries = Vec::new();
+        while remaining > 0 {
+            // Entries come in runs sharing an inode metadata block
+            let header = self.take(&mut cursor, 12)?;
+            remaining = remaining.checked_sub(12).ok_or_else(truncated)?;
+            let count = u32_at(&header, 0) as usize + 1;
+            let inode_block = u64::from(u32_at(&header, 4));
+
+            for _ in 0..count {
+                let entry = self.take(&mut cursor, 8)?;
+                let name_size = usize::from(u16_at(&entry, 6)) + 1;
+                let name = self.take(&mut cursor, name_size)?;
+                remaining = remaining.checked_sub(8 + name_size).ok_or_else(truncated)?;
+                entries.push((
+                    String::from_utf8_lossy(&name).into_owned(),
+                    inode_block << 16 | u64::from(u16_at(&entry, 0)),
+                ));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Find the inode at a path, following symlinks
+    fn resolve(&mut self, path: &str) -> io::Result<Inode> {
+        let mut remaining: VecDeque<String> = components(path).collect();
+        // Inode references from the root down to the current directory
+        let mut parents = vec![self.superblock.root_inode];
+        let mut symlinks = 0;
+
+        while let Some(name) = remaining.pop_front() {
+            if name == ".." {
+                if parents.len() > 1 {
+                    parents.pop();
+                }
+                continue;
+            }
+
+            let directory = self.inode(*parents.last().unwrap())?;
+            let reference = self
+                .entries(&directory)?
+                .into_iter()
+                .find(|(entry, _)| *entry == name)
+                .map(|(_, reference)| reference)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, name.clone()))?;
+
+            match self.inode(reference)? {
+                Inode::Symlink { target } => {
+                    symlinks += 1;
+                    if symlinks > MAX_SYMLINKS {
+                        return Err(invalid(format!("Too many symlinks: {}", path)));
+                    }
+                    if target.starts_with('/') {
+                        parents.truncate(1);
+                    }
+                    for component in components(&target).rev() {
+                        remaining.push_front(component);
+                    }
+                }
+                _ => parents.push(reference),
+            }
+        }
+
+        self.inode(*parents.last().unwrap())
+    }
+
+    /// Decompressed fragment block holding the tails of several files
+    fn fragment_block(&mut self, fragment: u32) -> io::Result<Vec<u8>> {
+        // The table is a list of metadata block positions, each block
+        // holding 512 entries
+        let per_block = METADATA_BLOCK_SIZE as u64 / FRAGMENT_ENTRY_SIZE;
+        let index = u64::from(fragment);
+        let pointer = self.read_at(self.superblock.fragment_table + index / per_block * 8, 8)?;
+        let mut cursor = self.cursor(
+            u64_at(&pointer, 0),
+            ((index % per_block) * FRAGMENT_ENTRY_SIZE) as usize,
+        );
+        let entry = self.take(&mut cursor, FRAGMENT_ENTRY_SIZE as usize)?;
+
+        let stored = u32_at(&entry, 8);
+        let raw = self.read_at(u64_at(&entry, 0), (stored & !DATA_UNCOMPRESSED) as usize)?;
+        if stored & DATA_UNCOMPRESSED != 0 {
+            Ok(raw)
+        } else {
+            self.decompress(&raw, self.superblock.block_size as usize)
+        }
+    }
+}
+
+fn components(path: &str) -> impl DoubleEndedIterator<Item = String> + '_ {
+    path.split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .map(|c| c.to_string())
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::io::{Cursor as IoCursor, Write};
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    /// File in a test image: a regular file with contents, or a symlink
+    pub enum Node<'a> {
+        File(&'a [u8]),
+        Symlink(&'a str),
+    }
+
+    /// Build a gzip image whose root directory holds `nodes`. File data is
+    /// split over compressed and uncompressed blocks and a fragment, while
+    /// metadata is stored uncompressed.
+    pub fn image(nodes: &[(&str, Node)]) -> Vec<u8> {
+        const BLOCK_SIZE: u32 = 4096;
+        let compress = |data: &[u8]| {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        };
+
+        let mut nodes: Vec<&(&str, Node)> = nodes.iter().collect();
+        nodes.sort_by_key(|(name, _)| *name);
+
+        let mut data = Vec::new();
+        let mut fragments = Vec::new();
+        let mut inodes = Vec::new();
+        let mut references = Vec::new();
+        for (i, (_, node)) in nodes.iter().enumerate() {
+            references.push(inodes.len() as u16);
+            let number = i as u32 + 2;
+            match node {
+                Node::File(contents) => {
+                    let full = contents.len() / BLOCK_SIZE as usize;
+                    let blocks_start = SUPERBLOCK_SIZE as u32 + data.len() as u32;
+                    let mut sizes = Vec::new();
+                    for (b, block) in contents.chunks(BLOCK_SIZE as usize).take(full).enumerate() {
+                        if b % 2 == 0 {
+                            let compressed = compress(block);
+                            sizes.push(compressed.len() as u32);
+                            data.extend(compressed);
+                        } else {
+                            sizes.push(block.len() as u32 | DATA_UNCOMPRESSED);
+                            data.extend_from_slice(block);
+                        }
+                    }
+                    let tail = &contents[full * BLOCK_SIZE as usize..];
+                    let (fragment, offset) = if tail.is_empty() {
+                        (NO_FRAGMENT, 0)
+                    } else {
+                        let offset = fragments.len() as u32;
+                        fragments.extend_from_slice(tail);
+                        (0, offset)
+                    };
+
+                    inodes.extend(2u16.to_le_bytes());
+                    inodes.extend([0u8; 10]);
+                    inodes.extend(number.to_le_bytes());
+                    inodes.extend(blocks_start.to_le_bytes());
+                    inodes.extend(fragment.to_le_bytes());
+                    inodes.extend(offset.to_le_bytes());
+                    inodes.extend((contents.len() as u32).to_le_bytes());
+                    for size in sizes {
+                        inodes.extend(size.to_le_bytes());
+                    }
+                }
+                Node::Symlink(target) => {
+                    inodes.extend(3u16.to_le_bytes());
+                    inodes.extend([0u8; 10]);
+                    inodes.extend(number.to_le_bytes());
+                    inodes.extend(1u32.to_le_bytes());
+                    inodes.extend((target.len() as u32).to_le_bytes());
+                    inodes.extend(target.as_bytes());
+                }
+            }
+        }
+
+        let fragment_start = SUPERBLOCK_SIZE as u64 + data.len() as u64;
+        let compressed_fragments = compress(&fragments);
+        data.extend(&compressed_fragments);
+
+        let mut listing = Vec::new();
+        listing.extend((nodes.len() as u32 - 1).to_le_bytes());
+        listing.extend(0u32.to_le_bytes());
+        listing.extend(2u32.to_le_bytes());
+        for ((name, node), reference) in nodes.iter().zip(&references) {
+            let kind: u16 = match node {
+                Node::File(_) => 2,
+                Node::Symlink(_) => 3,
+            };
+            listing.extend(reference.to_le_bytes());
+            listing.extend(0u16.to_le_bytes());
+            listing.extend(kind.to_le_bytes());
+            listing.extend((name.len() as u16 - 1).to_le_bytes());
+            listing.extend(name.as_bytes());
+        }
+
+        let root_reference = inodes.len() as u64;
+        inodes.extend(1u16.to_le_bytes());
+        inodes.extend([0u8; 10]);
+        inodes.extend(1u32.to_le_bytes());
+        inodes.extend(0u32.to_le_bytes());
+        inodes.extend(2u32.to_le_bytes());
+        inodes.extend((listing.len() as u16 + 3).to_le_bytes());
+        inodes.extend(0u16.to_le_bytes());
+        inodes.extend(1u32.to_le_bytes());
+
+        let metadata = |bytes: &[u8]| {
+            let mut block = (bytes.len() as u16 | METADATA_UNCOMPRESSED)
+                .to_le_bytes()
+                .to_vec();
+            block.extend_from_slice(bytes);
+            block
+        };
+        let inode_table = SUPERBLOCK_SIZE as u64 + data.len() as u64;
+        data.extend(metadata(&inodes));
+        let directory_table = SUPERBLOCK_SIZE as u64 + data.len() as u64;
+        data.extend(metadata(&listing));
+        let fragment_entries = SUPERBLOCK_SIZE as u64 + data.len() as u64;
+        let mut entry = fragment_start.to_le_bytes().to_vec();
+        entry.extend((compressed_fragments.len() as u32).to_le_bytes());
+        entry.extend(0u32.to_le_bytes());
+        data.extend(metadata(&entry));
+        let fragment_table = SUPERBLOCK_SIZE as u64 + data.len() as u64;
+        data.extend(fragment_entries.to_le_bytes());
+
+        let mut superblock = Vec::new();
+        superblock.extend(MAGIC.to_le_bytes());
+        superblock.extend((nodes.len() as u32 + 1).to_le_bytes());
+        superblock.extend(0u32.to_le_bytes());
+        superblock.extend(BLOCK_SIZE.to_le_bytes());
+        superblock.extend(1u32.to_le_bytes());
+        superblock.extend(GZIP.to_le_bytes());
+        superblock.extend(12u16.to_le_bytes());
+        superblock.extend(0u16.to_le_bytes());
+        superblock.extend(1u16.to_le_bytes());
+        superblock.extend(4u16.to_le_bytes());
+        superblock.extend(0u16.to_le_bytes());
+        superblock.extend(root_reference.to_le_bytes());
+        superblock.extend((SUPERBLOCK_SIZE as u64 + data.len() as u64).to_le_bytes());
+        superblock.extend(u64::MAX.to_le_bytes());
+        superblock.extend(u64::MAX.to_le_bytes());
+        superblock.extend(inode_table.to_le_bytes());
+        superblock.extend(directory_table.to_le_bytes());
+        superblock.extend(fragment_table.to_le_bytes());
+        superblock.extend(u64::MAX.to_le_bytes());
+        superblock.extend(data);
+        superblock
+    }
+
+    #[test]
+    fn test_read_files() {
+        let large: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let bytes = image(&[
+            ("app.desktop", Node::File(b"[Desktop Entry]\nName=App\n")),
+            ("large.bin", Node::File(&large)),
+            (".DirIcon", Node::Symlink("./app.desktop")),
+            ("loop", Node::Symlink("loop")),
+        ]);
+
+        // Embedded after other data, as in an AppImage
+        let mut file = vec![0x7f; 100];
+        file.extend(bytes);
+        let mut image = Image::open(IoCursor::new(file), 100).unwrap();
+
+        assert_eq!(
+            image.root_entries().unwrap(),
+            vec![".DirIcon", "app.desktop", "large.bin", "loop"]
+        );
+        assert_eq!(
+            image.read_file("app.desktop").unwrap(),
+            b"[Desktop Entry]\nName=App\n"
+        );
+        assert_eq!(image.read_file("/large.bin").unwrap(), large);
+        assert_eq!(
+            image.read_file(".DirIcon").unwrap(),
+            image.read_file("../app.desktop").unwrap()
+        );
+        assert!(image.read_file("loop").is_err());
+        assert_eq!(
+            image.read_file("missing").unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! Startup notification for launched apps
+//!
+//! The launcher hands the new app a token, which the app passes back to the
+//! compositor or window manager when its first window maps so that window
+//! gets focus. On Wayland the token comes from the compositor through
+//! xdg-activation and is passed as `XDG_ACTIVATION_TOKEN`; on X11 Nova makes
+//! up a startup id, announces it to the window manager and passes it as
+//! `DESKTOP_STARTUP_ID`. X11 apps running under XWayland read
+//! `DESKTOP_STARTUP_ID`, so on Wayland both variables carry the token.
+//! Compositors that only honour tokens tied to a surface and input event
+//! get no token, as Nova can't provide those. A compositor or X server that doesn't answer in time gets the app
+//! launched without a token.
+
+use std::os::fd::{AsFd, BorrowedFd};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{delegate_noop, Connection, Dispatch, QueueHandle};
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_token_v1::{
+    self, XdgActivationTokenV1,
+};
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
+use x11rb::connection::Connection as _;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConnectionExt, CreateWindowAux, EventMask, PropMode, Window,
+    WindowClass,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::xdg;
+
+const ACTIVATION_TOKEN: &str = "XDG_ACTIVATION_TOKEN";
+const STARTUP_ID: &str = "DESKTOP_STARTUP_ID";
+
+/// Longest to wait for the display server's answer
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The app being started, as shown by the desktop shell while it loads
+pub struct Launchee<'a> {
+    /// Desktop file id of the app
+    pub app_id: &'a str,
+    pub name: &'a str,
+    pub icon: Option<&'a str>,
+    /// Program run by the exec line
+    pub bin: &'a str,
+    /// Window class the app's first window will have, from `StartupWMClass`
+    pub wm_class: Option<&'a str>,
+    pub desktop_file: Option<&'a Path>,
+}
+
+/// A startup sequence in progress
+pub enum StartupNotification {
+    Wayland { token: String },
+    X11 { id: String },
+}
+
+impl StartupNotification {
+    /// Start a startup sequence for the app, if the session supports one
+    pub fn begin(launchee: &Launchee) -> Option<Self> {
+        let result = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            if !accepts_bare_tokens(&xdg::current_desktops()) {
+                return None;
+            }
+            activation_token(launchee.app_id).map(|token| Self::Wayland { token })
+        } else if std::env::var_os("DISPLAY").is_some() {
+            X11Session::connect()
+                .and_then(|session| session.begin(launchee))
+                .map(|id| Self::X11 { id })
+        } else {
+            return None;
+        };
+
+        result
+            .map_err(|e| eprintln!("[Nova] Startup notification failed: {}", e))
+            .ok()
+    }
+
+    /// End the sequence of an app that failed to start
+    pub fn cancel(self) {
+        if let Self::X11 { id } = self {
+            let message = format!("remove: ID={}", quote(&id));
+            if let Err(e) = X11Session::connect().and_then(|session| session.send(&message)) {
+                eprintln!("[Nova] Failed to cancel startup notification: {}", e);
+            }
+        }
+    }
+}
+
+/// Pass the startup token to a command. Without one, the variables Nova
+/// itself was started with are removed, since they belong to Nova's own
+/// startup.
+pub fn set_env(command: &mut Command, startup: Option<&StartupNotification>) {
+    match startup {
+        Some(StartupNotification::Wayland { token }) => {
+            command.env(ACTIVATION_TOKEN, token).env(STARTUP_ID, token);
+        }
+        Some(StartupNotification::X11 { id }) => {
+            command.env_remove(ACTIVATION_TOKEN).env(STARTUP_ID, id);
+        }
+        None => {
+            command.env_remove(ACTIVATION_TOKEN).env_remove(STARTUP_ID);
+        }
+    }
+}
+
+struct TokenState {
+    token: Option<String>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for TokenState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgActivationTokenV1, ()> for TokenState {
+    fn event(
+        state: &mut Self,
+        _: &XdgActivationTokenV1,
+        event: xdg_activation_token_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_activation_token_v1::Event::Done { token } = event {
+            state.token = Some(token);
+        }
+    }
+}
+
+delegate_noop!(TokenState: XdgActivationV1);
+
+/// Whether the desktop's compositor focuses windows for tokens requested
+/// without a surface and input serial.
+///
+/// Nova's window belongs to iced, whose Wayland connection Nova can't use,
+/// so its requests can't name the surface and input serial that triggered
+/// the launch. Mutter (GNOME) and KWin (KDE) don't grant focus for such
+/// tokens, so none is requested there and the app starts without one.
+fn accepts_bare_tokens(desktops: &[String]) -> bool {
+    !desktops
+        .iter()
+        .any(|d| d.eq_ignore_ascii_case("GNOME") || d.eq_ignore_ascii_case("KDE"))
+}
+
+/// Ask the compositor for an activation token for the app, without a
+/// surface or input serial; see [`accepts_bare_tokens`]
+fn activation_token(app_id: &str) -> Result<String, String> {
+    let wayland_error = |e: &dyn std::fmt::Display| format!("Wayland request failed: {}", e);
+
+    let connection = Connection::connect_to_env().map_err(|e| wayland_error(&e))?;
+    let (globals, mut queue) =
+        registry_queue_init::<TokenState>(&connection).map_err(|e| wayland_error(&e))?;
+    let handle = queue.handle();
+    let activation: XdgActivationV1 = globals
+        .bind(&handle, 1..=1, ())
+        .map_err(|_| "Compositor doesn't support xdg-activation".to_string())?;
+
+    let request = activation.get_activation_token(&handle, ());
+    if !app_id.is_empty() {
+        request.set_app_id(app_id.to_string());
+    }
+    request.commit();
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut state = TokenState { token: None };
+    let token = loop {
+        queue
+            .dispatch_pending(&mut state)
+            .map_err(|e| wayland_error(&e))?;
+        if let Some(token) = state.token.take() {
+            break token;
+        }
+        queue.flush().map_err(|e| wayland_error(&e))?;
+        // No guard means events are queued already
+        let Some(guard) = queue.prepare_read() else {
+            continue;
+        };
+        wait_readable(guard.connection_fd(), deadline)?;
+        guard.read().map_err(|e| wayland_error(&e))?;
+    };
+    request.destroy();
+    activation.destroy();
+    Ok(token)
+}
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_STARTUP_INFO_BEGIN,
+        _NET_STARTUP_INFO,
+    }
+}
+
+fn x11_error(e: impl std::fmt::Display) -> String {
+    format!("X11 request failed: {}", e)
+}
+
+/// Connection with a window to send startup messages from
+struct X11Session {
+    conn: RustConnection,
+    screen: usize,
+    root: Window,
+    window: Window,
+    atoms: Atoms,
+}
+
+impl X11Session {
+    fn connect() -> Result<Self, String> {
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let root = conn.setup().roots[screen].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        let window = conn.generate_id().map_err(x11_error)?;
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            -100,
+            -100,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_ONLY,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new()
+                .override_redirect(1)
+                .event_mask(EventMask::PROPERTY_CHANGE),
+        )
+        .map_err(x11_error)?;
+        Ok(Self {
+            conn,
+            screen,
+            root,
+            window,
+            atoms,
+        })
+    }
+
+    /// Announce a new startup sequence, returning its id
+    fn begin(&self, launchee: &Launchee) -> Result<String, String> {
+        let id = startup_id(launchee.bin, self.server_time()?);
+        self.send(&new_message(&id, self.screen, launchee))?;
+        Ok(id)
+    }
+
+    /// Current server time, which window managers compare with the user's
+    /// last input for focus stealing prevention. Changing a property on our
+    /// window makes the server report it.
+    fn server_time(&self) -> Result<u32, String> {
+        self.conn
+            .change_property8(
+                PropMode::APPEND,
+                self.window,
+                AtomEnum::WM_NAME,
+                AtomEnum::STRING,
+                &[],
+            )
+            .map_err(x11_error)?;
+        self.conn.flush().map_err(x11_error)?;
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            while let Some(event) = self.conn.poll_for_event().map_err(x11_error)? {
+                if let Event::PropertyNotify(event) = event {
+                    if event.window == self.window {
+                        return Ok(event.time);
+                    }
+                }
+            }
+            wait_readable(self.conn.stream().as_fd(), deadline)?;
+        }
+    }
+
+    /// Broadcast a startup message to the root window
+    fn send(&self, message: &str) -> Result<(), String> {
+        for (i, data) in client_messages(message).into_iter().enumerate() {
+            let kind = if i == 0 {
+                self.atoms._NET_STARTUP_INFO_BEGIN
+            } else {
+                self.atoms._NET_STARTUP_INFO
+            };
+            let event = ClientMessageEvent::new(8, self.window, kind, data);
+            self.conn
+                .send_event(false, self.root, EventMask::PROPERTY_CHANGE, event)
+                .map_err(x11_error)?;
+        }
+        self.conn.flush().map_err(x11_error)?;
+        Ok(())
+    }
+}
+
+impl Drop for X11Session {
+    fn drop(&mut self) {
+        let _ = self.conn.destroy_window(self.window);
+        let _ = self.conn.flush();
+    }
+}
+
+/// Block until `fd` has data to read, failing once `deadline` passes
+fn wait_readable(fd: BorrowedFd, deadline: Instant) -> Result<(), String> {
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let timeout = PollTimeout::try_from(remaining).unwrap_or(PollTimeout::MAX);
+        let mut fds = [PollFd::new(fd, PollFlags::POLLIN)];
+        match poll(&mut fds, timeout) {
+            Ok(0) => return Err("Display server didn't answer in time".to_string()),
+            Ok(_) => return Ok(()),
+            Err(Errno::EINTR) => {}
+            Err(e) => return Err(format!("Polling the display server failed: {}", e)),
+        }
+    }
+}
+
+/// Unique startup id, ending in the time of the launch as the
+/// specification asks
+fn startup_id(bin: &str, time: u32) -> String {
+    static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+    let bin = Path::new(bin)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(bin);
+    format!(
+        "nova-{}-{}-{}_TIME{}",
+        std::process::id(),
+        bin,
+        SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        time
+    )
+}
+
+/// The `new:` message starting a sequence
+fn new_message(id: &str, screen: usize, launchee: &Launchee) -> String {
+    let screen = screen.to_string();
+    let desktop_file = launchee
+        .desktop_file
+        .map(|path| path.to_string_lossy().into_owned());
+    let fields = [
+        ("ID", Some(id)),
+        ("NAME", Some(launchee.name)),
+        ("SCREEN", Some(screen.as_str())),
+        ("BIN", Some(launchee.bin)),
+        ("ICON", launchee.icon),
+        ("WMCLASS", launchee.wm_class),
+        ("APPLICATION_ID", desktop_file.as_deref()),
+    ];
+
+    let mut message = "new:".to_string();
+    for (key, value) in fields {
+        if let Some(value) = value {
+            message.push_str(&format!(" {}={}", key, quote(value)));
+        }
+    }
+    message
+}
+
+/// Quote a message value holding spaces, quotes or backslashes
+fn quote(value: &str) -> String {
+    if value.is_empty() || value.contains([' ', '"', '\\']) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split a message into the 20 byte payloads of client messages, the last
+/// one holding the terminating nul
+fn client_messages(message: &str) -> Vec<[u8; 20]> {
+    let mut bytes = message.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+        .chunks(20)
+        .map(|chunk| {
+            let mut data = [0u8; 20];
+            data[..chunk.len()].copy_from_slice(chunk);
+            data
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_message() {
+        let launchee = Launchee {
+            app_id: "org.gnome.TextEditor",
+            name: "Text Editor",
+            icon: Some("org.gnome.TextEditor"),
+            bin: "/usr/bin/gnome-text-editor",
+            wm_class: None,
+            desktop_file: Some(Path::new(
+                "/usr/share/applications/org.gnome.TextEditor.desktop",
+            )),
+        };
+        let id = startup_id(launchee.bin, 1234);
+        assert!(id.starts_with("nova-"));
+        assert!(id.ends_with("-gnome-text-editor-0_TIME1234"));
+
+        assert_eq!(
+            new_message("nova-1_TIME5", 0, &launchee),
+            "new: ID=nova-1_TIME5 NAME=\"Text Editor\" SCREEN=0 \
+             BIN=/usr/bin/gnome-text-editor ICON=org.gnome.TextEditor \
+             APPLICATION_ID=/usr/share/applications/org.gnome.TextEditor.desktop"
+        );
+        assert_eq!(quote(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+    }
+
+    #[test]
+    fn test_client_messages() {
+        let messages = client_messages("remove: ID=nova-42_TIME0");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(&messages[0], b"remove: ID=nova-42_T");
+        assert_eq!(&messages[1][..6], b"IME0\0\0");
+
+        // A message filling the last payload needs one more for the nul
+        assert_eq!(client_messages(&"x".repeat(20)).len(), 2);
+        assert_eq!(client_messages(&"x".repeat(19)).len(), 1);
+    }
+
+    #[test]
+    fn test_accepts_bare_tokens() {
+        let desktops =
+            |list: &[&str]| -> Vec<String> { list.iter().map(|d| d.to_string()).collect() };
+        assert!(accepts_bare_tokens(&desktops(&["sway"])));
+        assert!(accepts_bare_tokens(&desktops(&["Hyprland"])));
+        assert!(accepts_bare_tokens(&desktops(&[])));
+        assert!(!accepts_bare_tokens(&desktops(&["ubuntu", "GNOME"])));
+        assert!(!accepts_bare_tokens(&desktops(&["KDE"])));
+    }
+
+    #[test]
+    fn test_wait_readable() {
+        use std::io::Write;
+        use std::os::unix::net::UnixStream;
+
+        let (mut server, client) = UnixStream::pair().unwrap();
+        let deadline = Instant::now() + Duration::from_millis(50);
+        assert!(wait_readable(client.as_fd(), deadline).is_err());
+
+        server.write_all(b"x").unwrap();
+        assert!(wait_readable(client.as_fd(), Instant::now() + TIMEOUT).is_ok());
+    }
+}
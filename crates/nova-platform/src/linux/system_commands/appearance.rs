@@ -0,0 +1,66 @@
+//! Switching between the dark and light style
+//!
+//! GNOME and the desktops following its settings keep the preference in
+//! the `color-scheme` key, which the settings portal passes on to apps.
+//! Plasma applies a whole color scheme instead.
+
+use std::process::Command;
+
+use nova_core::{NovaError, NovaResult};
+
+use super::gsettings;
+use crate::linux::{icons, xdg};
+
+const INTERFACE_SCHEMA: &str = "org.gnome.desktop.interface";
+
+const PLASMA_DARK: &str = "BreezeDark";
+const PLASMA_LIGHT: &str = "BreezeLight";
+
+/// Where the session keeps its style preference
+pub enum Appearance {
+    Gnome,
+    Plasma,
+}
+
+impl Appearance {
+    pub fn detect() -> Option<Self> {
+        let desktops = xdg::current_desktops();
+        if desktops.iter().any(|d| d.eq_ignore_ascii_case("KDE")) {
+            return xdg::is_installed("plasma-apply-colorscheme").then_some(Appearance::Plasma);
+        }
+        // The key exists since GNOME 42
+        gsettings::get(INTERFACE_SCHEMA, "color-scheme").map(|_| Appearance::Gnome)
+    }
+
+    pub fn toggle_dark_mode(&self) -> NovaResult<()> {
+        match self {
+            Appearance::Gnome => {
+                let dark = gsettings::get(INTERFACE_SCHEMA, "color-scheme")
+                    .is_some_and(|scheme| scheme == "'prefer-dark'");
+                let scheme = if dark { "'default'" } else { "'prefer-dark'" };
+                gsettings::set(INTERFACE_SCHEMA, "color-scheme", scheme)
+            }
+            Appearance::Plasma => {
+                let kdeglobals = dirs::config_dir()
+                    .map(|dir| dir.join("kdeglobals"))
+                    .and_then(|path| icons::ini_value(&path, "General", "ColorScheme"));
+                let dark = kdeglobals.is_some_and(|scheme| scheme.contains("Dark"));
+                let scheme = if dark { PLASMA_LIGHT } else { PLASMA_DARK };
+                let status = Command::new("plasma-apply-colorscheme")
+                    .arg(scheme)
+                    .status()
+                    .map_err(|e| {
+                        NovaError::Platform(format!("Failed to apply color scheme: {}", e))
+                    })?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(NovaError::Platform(format!(
+                        "Failed to apply color scheme {}",
+                        scheme
+                    )))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Output volume of the default sink
+//!
+//! PipeWire and PulseAudio have no D-Bus interface, so their command line
+//! clients are used: `wpctl` for PipeWire, else `pactl`, which also talks to
+//! PipeWire's PulseAudio server.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use nova_core::{NovaError, NovaResult};
+
+use crate::linux::xdg;
+
+/// Volume change per step, in percent
+const STEP: u32 = 5;
+
+pub enum Mixer {
+    WirePlumber,
+    PulseAudio,
+}
+
+impl Mixer {
+    /// Pick a client whose sound server is running
+    pub fn detect() -> Option<Self> {
+        let runtime = PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?);
+        if runtime.join("pipewire-0").exists() && xdg::is_installed("wpctl") {
+            return Some(Mixer::WirePlumber);
+        }
+        if runtime.join("pulse").join("native").exists() && xdg::is_installed("pactl") {
+            return Some(Mixer::PulseAudio);
+        }
+        None
+    }
+
+    /// Raise the volume by a step, up to 100%
+    pub fn volume_up(&self) -> NovaResult<()> {
+        match self {
+            Mixer::WirePlumber => run(
+                "wpctl",
+                &[
+                    "set-volume",
+                    "-l",
+                    "1",
+                    "@DEFAULT_AUDIO_SINK@",
+                    &format!("{}%+", STEP),
+                ],
+            ),
+            Mixer::PulseAudio => {
+                let volume = pactl_volume()?;
+                let target = (volume + STEP).min(100).max(volume);
+                run(
+                    "pactl",
+                    &["set-sink-volume", "@DEFAULT_SINK@", &format!("{}%", target)],
+                )
+            }
+        }
+    }
+
+    pub fn volume_down(&self) -> NovaResult<()> {
+        match self {
+            Mixer::WirePlumber => run(
+                "wpctl",
+                &["set-volume", "@DEFAULT_AUDIO_SINK@", &format!("{}%-", STEP)],
+            ),
+            Mixer::PulseAudio => run(
+                "pactl",
+                &["set-sink-volume", "@DEFAULT_SINK@", &format!("-{}%", STEP)],
+            ),
+        }
+    }
+
+    pub fn toggle_mute(&self) -> NovaResult<()> {
+        match self {
+            Mixer::WirePlumber => run("wpctl", &["set-mute", "@DEFAULT_AUDIO_SINK@", "toggle"]),
+            Mixer::PulseAudio => run("pactl", &["set-sink-mute", "@DEFAULT_SINK@", "toggle"]),
+        }
+    }
+}
+
+/// Volume of the default sink in percent, from its first channel
+fn pactl_volume() -> NovaResult<u32> {
+    let output = Command::new("pactl")
+        .args(["get-sink-volume", "@DEFAULT_SINK@"])
+        .output()
+        .map_err(|e| NovaError::Platform(format!("Failed to run pactl: {}", e)))?;
+    parse_pactl_volume(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| NovaError::Platform("Failed to read the volume".to_string()))
+}
+
+/// Parse `Volume: front-left: 32768 /  50% / -18.06 dB,   front-right: ...`
+fn parse_pactl_volume(output: &str) -> Option<u32> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix('%'))
+        .and_then(|percent| percent.parse().ok())
+}
+
+fn run(program: &str, args: &[&str]) -> NovaResult<()> {
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| NovaError::Platform(format!("Failed to run {}: {}", program, e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(NovaError::Platform(format!(
+            "{} failed: {}",
+            program, status
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pactl_volume() {
+        let output = "Volume: front-left: 32768 /  50% / -18.06 dB,   \
+                      front-right: 32768 /  50% / -18.06 dB\n        balance 0.00\n";
+        assert_eq!(parse_pactl_volume(output), Some(50));
+        assert_eq!(parse_pactl_volume(""), None);
+    }
+}
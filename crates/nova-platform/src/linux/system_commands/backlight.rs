@@ -0,0 +1,104 @@
+//! Screen backlight
+//!
+//! Brightness is read from sysfs and written through logind, which lets
+//! the session's user change it without write access to sysfs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use nova_core::{NovaError, NovaResult};
+
+use super::logind::Logind;
+
+const SYSFS_BACKLIGHT: &str = "/sys/class/backlight";
+
+/// Number of steps between off and full brightness
+const STEPS: u32 = 20;
+
+pub struct Backlight {
+    name: String,
+    path: PathBuf,
+}
+
+impl Backlight {
+    pub fn find() -> Option<Self> {
+        find_in(Path::new(SYSFS_BACKLIGHT))
+    }
+
+    /// Change the brightness by a step, up or down
+    pub fn step(&self, logind: &Logind, up: bool) -> NovaResult<()> {
+        let read = |file: &str| {
+            fs::read_to_string(self.path.join(file))
+                .ok()
+                .and_then(|value| value.trim().parse::<u32>().ok())
+                .ok_or_else(|| {
+                    NovaError::Platform(format!("Failed to read {} of {}", file, self.name))
+                })
+        };
+        let brightness = stepped(read("brightness")?, read("max_brightness")?, up);
+        logind.set_brightness(&self.name, brightness)
+    }
+}
+
+/// Pick the device that controls the panel, preferring firmware interfaces
+/// over platform drivers over raw registers, as the kernel recommends
+fn find_in(dir: &Path) -> Option<Backlight> {
+    let rank = |path: &Path| match fs::read_to_string(path.join("type")).as_deref() {
+        Ok("firmware\n") => 0,
+        Ok("platform\n") => 1,
+        _ => 2,
+    };
+    let mut devices: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.join("max_brightness").exists())
+        .collect();
+    devices.sort_by_key(|path| (rank(path), path.clone()));
+
+    let path = devices.into_iter().next()?;
+    let name = path.file_name()?.to_str()?.to_string();
+    Some(Backlight { name, path })
+}
+
+/// Brightness one step from `current`, never turning the backlight off
+fn stepped(current: u32, max: u32, up: bool) -> u32 {
+    let step = (max / STEPS).max(1);
+    if up {
+        current.saturating_add(step).min(max)
+    } else {
+        current.saturating_sub(step).max(1).min(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stepped() {
+        assert_eq!(stepped(500, 1000, true), 550);
+        assert_eq!(stepped(980, 1000, true), 1000);
+        assert_eq!(stepped(500, 1000, false), 450);
+        assert_eq!(stepped(30, 1000, false), 1);
+        // Devices with few levels step one level at a time
+        assert_eq!(stepped(3, 7, true), 4);
+        assert_eq!(stepped(1, 7, false), 1);
+    }
+
+    #[test]
+    fn test_find_prefers_firmware() {
+        let root = std::env::temp_dir().join(format!("nova-backlight-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (name, kind) in [("acpi_video0", "firmware"), ("intel_backlight", "raw")] {
+            let device = root.join(name);
+            fs::create_dir_all(&device).unwrap();
+            fs::write(device.join("type"), format!("{}\n", kind)).unwrap();
+            fs::write(device.join("max_brightness"), "100\n").unwrap();
+        }
+
+        let found = find_in(&root).map(|backlight| backlight.name);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found.as_deref(), Some("acpi_video0"));
+    }
+}
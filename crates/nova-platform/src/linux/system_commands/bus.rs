@@ -0,0 +1,172 @@
+//! Method calls and properties on D-Bus services
+
+use nova_core::{NovaError, NovaResult};
+use serde::Serialize;
+use zbus::blocking::Connection;
+use zbus::zvariant::{DynamicType, OwnedValue, Type, Value};
+
+/// A service's object and interface
+#[derive(Debug, Clone, Copy)]
+pub struct Object<'a> {
+    pub service: &'a str,
+    pub path: &'a str,
+    pub interface: &'a str,
+}
+
+impl Object<'_> {
+    /// Call a method, discarding its reply
+    pub fn call<B>(&self, connection: &Connection, method: &str, body: &B) -> NovaResult<()>
+    where
+        B: Serialize + DynamicType,
+    {
+        self.send(connection, method, body).map(|_| ())
+    }
+
+    /// Call a method and read its reply
+    pub fn call_for<B, R>(&self, connection: &Connection, method: &str, body: &B) -> NovaResult<R>
+    where
+        B: Serialize + DynamicType,
+        R: for<'d> serde::Deserialize<'d> + Type,
+    {
+        let reply = self.send(connection, method, body)?;
+        reply.body().deserialize().map_err(|e| error(method, e))
+    }
+
+    /// Read a property
+    pub fn property<T>(&self, connection: &Connection, name: &str) -> NovaResult<T>
+    where
+        T: TryFrom<OwnedValue>,
+    {
+        let value: OwnedValue =
+            self.properties()
+                .call_for(connection, "Get", &(self.interface, name))?;
+        T::try_from(value)
+            .map_err(|_| NovaError::Platform(format!("Unexpected type of property {}", name)))
+    }
+
+    /// Write a property
+    pub fn set_property<'v>(
+        &self,
+        connection: &Connection,
+        name: &str,
+        value: impl Into<Value<'v>>,
+    ) -> NovaResult<()> {
+        self.properties()
+            .call(connection, "Set", &(self.interface, name, value.into()))
+    }
+
+    fn properties(&self) -> Object<'_> {
+        Object {
+            interface: "org.freedesktop.DBus.Properties",
+            ..*self
+        }
+    }
+
+    fn send<B>(&self, connection: &Connection, method: &str, body: &B) -> NovaResult<zbus::Message>
+    where
+        B: Serialize + DynamicType,
+    {
+        connection
+            .call_method(
+                Some(self.service),
+                self.path,
+                Some(self.interface),
+                method,
+                body,
+            )
+            .map_err(|e| error(method, e))
+    }
+}
+
+/// Connect to the session bus
+pub fn session() -> NovaResult<Connection> {
+    Connection::session()
+        .map_err(|e| NovaError::Platform(format!("Failed to connect to the session bus: {}", e)))
+}
+
+/// Check whether a service is running, or can be started by the bus
+pub fn has_service(connection: &Connection, service: &str) -> bool {
+    let bus = Object {
+        service: "org.freedesktop.DBus",
+        path: "/org/freedesktop/DBus",
+        interface: "org.freedesktop.DBus",
+    };
+    bus.call_for(connection, "NameHasOwner", &(service,))
+        .unwrap_or(false)
+        || bus
+            .call_for::<_, Vec<String>>(connection, "ListActivatableNames", &())
+            .is_ok_and(|names| names.iter().any(|name| name == service))
+}
+
+/// Describe a failed call or subscription
+pub fn error(method: &str, e: zbus::Error) -> NovaError {
+    NovaError::Platform(format!("D-Bus call {} failed: {}", method, e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+
+    use zbus::blocking::connection::Builder;
+    use zbus::object_server::Interface;
+
+    use super::*;
+
+    /// A session `dbus-daemon` of its own, stopped when dropped
+    pub struct TestBus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl TestBus {
+        /// Start a bus, or `None` when `dbus-daemon` isn't installed
+        pub fn start() -> Option<Self> {
+            let spawned = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn();
+            let Ok(mut daemon) = spawned else {
+                eprintln!("dbus-daemon is not installed; skipping");
+                return None;
+            };
+
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            Some(Self {
+                daemon,
+                address: address.trim().to_string(),
+            })
+        }
+
+        pub fn connect(&self) -> Connection {
+            Builder::address(self.address.as_str())
+                .unwrap()
+                .build()
+                .unwrap()
+        }
+
+        /// Own `service` on the bus and serve `interface` at `path`. The
+        /// service lives as long as the returned connection.
+        pub fn serve<I: Interface>(&self, service: &str, path: &str, interface: I) -> Connection {
+            Builder::address(self.address.as_str())
+                .unwrap()
+                .name(service.to_string())
+                .unwrap()
+                .serve_at(path.to_string(), interface)
+                .unwrap()
+                .build()
+                .unwrap()
+        }
+    }
+
+    impl Drop for TestBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+}
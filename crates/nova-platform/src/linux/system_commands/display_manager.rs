@@ -0,0 +1,76 @@
+//! Switching users through the display manager
+//!
+//! There is no common interface for showing the login screen next to a
+//! running session: LightDM and GDM each have their own, and Plasma shows
+//! its own user switcher for SDDM.
+
+use nova_core::NovaResult;
+
+use super::bus::{self, Object};
+use super::logind::Logind;
+use super::session::KSMSERVER;
+use crate::linux::xdg;
+
+const GDM: Object = Object {
+    service: "org.gnome.DisplayManager",
+    path: "/org/gnome/DisplayManager/LocalDisplayFactory",
+    interface: "org.gnome.DisplayManager.LocalDisplayFactory",
+};
+
+const LIGHTDM: &str = "org.freedesktop.DisplayManager";
+
+/// Interface used to show the login screen
+pub enum Greeter {
+    /// LightDM, with the object path of the session's seat
+    LightDm(String),
+    Gdm,
+    Plasma,
+}
+
+impl Greeter {
+    /// Find the greeter of the session, if its seat can run another one
+    pub fn detect(logind: &Logind) -> Option<Self> {
+        if !logind.can_multi_session() {
+            return None;
+        }
+        let desktops = xdg::current_desktops();
+        if desktops.iter().any(|d| d.eq_ignore_ascii_case("KDE")) {
+            let session = bus::session().ok()?;
+            if bus::has_service(&session, KSMSERVER.service) {
+                return Some(Greeter::Plasma);
+            }
+        }
+
+        let system = logind.connection();
+        if let Ok(seat) = std::env::var("XDG_SEAT_PATH") {
+            if bus::has_service(system, LIGHTDM) {
+                return Some(Greeter::LightDm(seat));
+            }
+        }
+        bus::has_service(system, GDM.service).then_some(Greeter::Gdm)
+    }
+
+    pub fn switch_user(&self, logind: &Logind) -> NovaResult<()> {
+        match self {
+            Greeter::LightDm(seat) => {
+                let seat = Object {
+                    service: LIGHTDM,
+                    path: seat,
+                    interface: "org.freedesktop.DisplayManager.Seat",
+                };
+                seat.call(logind.connection(), "SwitchToGreeter", &())
+            }
+            Greeter::Gdm => GDM
+                .call_for::<_, zbus::zvariant::OwnedObjectPath>(
+                    logind.connection(),
+                    "CreateTransientDisplay",
+                    &(),
+                )
+                .map(|_| ()),
+            Greeter::Plasma => {
+                let session = bus::session()?;
+                KSMSERVER.call(&session, "openSwitchUserDialog", &())
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! GNOME settings
+//!
+//! Settings live in dconf, whose database and change protocol are binary
+//! GVariant data, so they are read and written with the `gsettings` tool.
+
+use std::process::Command;
+
+use nova_core::{NovaError, NovaResult};
+
+/// Read a key, as printed by `gsettings`: strings are single-quoted
+pub fn get(schema: &str, key: &str) -> Option<String> {
+    let output = Command::new("gsettings")
+        .args(["get", schema, key])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Write a key, given in `gsettings` syntax
+pub fn set(schema: &str, key: &str, value: &str) -> NovaResult<()> {
+    let status = Command::new("gsettings")
+        .args(["set", schema, key, value])
+        .status()
+        .map_err(|e| NovaError::Platform(format!("Failed to run gsettings: {}", e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(NovaError::Platform(format!(
+            "Failed to set {} {}",
+            schema, key
+        )))
+    }
+}
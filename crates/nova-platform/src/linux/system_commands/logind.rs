@@ -0,0 +1,195 @@
+//! systemd-logind: power actions, locking and backlight brightness
+//!
+//! logind checks the polkit policy of each action itself, so calls are made
+//! interactive and a password prompt appears when the policy asks for one.
+
+use nova_core::{NovaError, NovaResult};
+use zbus::blocking::Connection;
+
+use super::bus::Object;
+
+const MANAGER: Object = Object {
+    service: "org.freedesktop.login1",
+    path: "/org/freedesktop/login1",
+    interface: "org.freedesktop.login1.Manager",
+};
+
+/// The session Nova runs in
+const SESSION: Object = Object {
+    service: "org.freedesktop.login1",
+    path: "/org/freedesktop/login1/session/auto",
+    interface: "org.freedesktop.login1.Session",
+};
+
+/// The seat of Nova's session
+const SEAT: Object = Object {
+    service: "org.freedesktop.login1",
+    path: "/org/freedesktop/login1/seat/auto",
+    interface: "org.freedesktop.login1.Seat",
+};
+
+/// Power actions of the manager, each with a matching `Can*` method
+#[derive(Debug, Clone, Copy)]
+pub enum PowerAction {
+    Suspend,
+    HybridSleep,
+    Hibernate,
+    Reboot,
+    PowerOff,
+}
+
+impl PowerAction {
+    fn method(self) -> &'static str {
+        match self {
+            PowerAction::Suspend => "Suspend",
+            PowerAction::HybridSleep => "HybridSleep",
+            PowerAction::Hibernate => "Hibernate",
+            PowerAction::Reboot => "Reboot",
+            PowerAction::PowerOff => "PowerOff",
+        }
+    }
+}
+
+pub struct Logind {
+    connection: Connection,
+}
+
+impl Logind {
+    /// Connect to logind on the system bus
+    pub fn connect() -> NovaResult<Self> {
+        let connection = Connection::system().map_err(|e| {
+            NovaError::Platform(format!("Failed to connect to the system bus: {}", e))
+        })?;
+        Ok(Self::new(connection))
+    }
+
+    /// Talk to logind over an open connection
+    pub fn new(connection: Connection) -> Self {
+        Self { connection }
+    }
+
+    /// Check whether the user may run a power action, possibly after
+    /// authenticating, and the hardware supports it
+    pub fn can(&self, action: PowerAction) -> bool {
+        let answer: NovaResult<String> =
+            MANAGER.call_for(&self.connection, &format!("Can{}", action.method()), &());
+        matches!(answer.as_deref(), Ok("yes" | "challenge"))
+    }
+
+    pub fn power(&self, action: PowerAction) -> NovaResult<()> {
+        MANAGER.call(&self.connection, action.method(), &(true,))
+    }
+
+    /// Ask the session's screen locker to lock
+    pub fn lock_session(&self) -> NovaResult<()> {
+        SESSION.call(&self.connection, "Lock", &())
+    }
+
+    /// End the session, stopping all of its processes
+    pub fn terminate_session(&self) -> NovaResult<()> {
+        SESSION.call(&self.connection, "Terminate", &())
+    }
+
+    /// Set a backlight device's brightness, in the device's own units
+    pub fn set_brightness(&self, device: &str, brightness: u32) -> NovaResult<()> {
+        SESSION.call(
+            &self.connection,
+            "SetBrightness",
+            &("backlight", device, brightness),
+        )
+    }
+
+    /// Check whether the seat can run more than one session, which
+    /// switching users needs
+    pub fn can_multi_session(&self) -> bool {
+        SEAT.property(&self.connection, "CanMultiSession")
+            .unwrap_or(false)
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::linux::system_commands::bus::tests::TestBus;
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct Manager(Calls);
+
+    #[zbus::interface(name = "org.freedesktop.login1.Manager")]
+    impl Manager {
+        fn can_suspend(&self) -> String {
+            "challenge".to_string()
+        }
+
+        fn can_hibernate(&self) -> String {
+            "no".to_string()
+        }
+
+        fn power_off(&self, interactive: bool) {
+            let call = format!("PowerOff {}", interactive);
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    struct Session(Calls);
+
+    #[zbus::interface(name = "org.freedesktop.login1.Session")]
+    impl Session {
+        fn lock(&self) {
+            self.0.lock().unwrap().push("Lock".to_string());
+        }
+
+        fn set_brightness(&self, subsystem: &str, name: &str, brightness: u32) {
+            let call = format!("SetBrightness {} {} {}", subsystem, name, brightness);
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    struct Seat;
+
+    #[zbus::interface(name = "org.freedesktop.login1.Seat")]
+    impl Seat {
+        #[zbus(property)]
+        fn can_multi_session(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_logind_calls() {
+        let Some(bus) = TestBus::start() else {
+            return;
+        };
+        let calls = Calls::default();
+        let service = bus.serve(MANAGER.service, MANAGER.path, Manager(calls.clone()));
+        let server = service.object_server();
+        server.at(SESSION.path, Session(calls.clone())).unwrap();
+        server.at(SEAT.path, Seat).unwrap();
+
+        let logind = Logind::new(bus.connect());
+        assert!(logind.can(PowerAction::Suspend));
+        assert!(!logind.can(PowerAction::Hibernate));
+        // Methods the service lacks count as unsupported
+        assert!(!logind.can(PowerAction::Reboot));
+        assert!(logind.can_multi_session());
+
+        logind.power(PowerAction::PowerOff).unwrap();
+        logind.lock_session().unwrap();
+        logind.set_brightness("intel_backlight", 120).unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "PowerOff true",
+                "Lock",
+                "SetBrightness backlight intel_backlight 120"
+            ]
+        );
+    }
+}
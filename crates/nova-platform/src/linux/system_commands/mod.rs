@@ -0,0 +1,158 @@
+//! System commands
+//!
+//! Power commands go through systemd-logind over D-Bus, logging out through
+//! the desktop's session manager and switching users through the display
+//! manager; desktop settings through the service that owns them.
+//! Each command is only offered when the session has what it needs, which
+//! is checked once when the commands are listed.
+
+mod appearance;
+mod audio;
+mod backlight;
+pub(super) mod bus;
+mod display_manager;
+mod gsettings;
+mod logind;
+mod notifications;
+mod screen;
+mod session;
+mod trash;
+
+use std::sync::OnceLock;
+
+use nova_core::{NovaError, NovaResult, SystemCommand};
+
+use crate::traits::SystemCommands;
+use appearance::Appearance;
+use audio::Mixer;
+use backlight::Backlight;
+use display_manager::Greeter;
+use logind::{Logind, PowerAction};
+use notifications::DoNotDisturb;
+use screen::Screen;
+use session::Desktop;
+
+pub struct LinuxSystemCommands {
+    session: OnceLock<Session>,
+}
+
+/// What the session offers, detected on first use
+struct Session {
+    desktop: Desktop,
+    logind: Option<Logind>,
+    greeter: Option<Greeter>,
+    screen: Option<Screen>,
+    do_not_disturb: Option<DoNotDisturb>,
+    appearance: Option<Appearance>,
+    mixer: Option<Mixer>,
+    backlight: Option<Backlight>,
+}
+
+impl LinuxSystemCommands {
+    pub fn new() -> Self {
+        Self {
+            session: OnceLock::new(),
+        }
+    }
+
+    fn session(&self) -> &Session {
+        self.session.get_or_init(|| {
+            let logind = Logind::connect()
+                .map_err(|e| eprintln!("[Nova] logind is unavailable: {}", e))
+                .ok();
+            Session {
+                desktop: Desktop::detect(),
+                greeter: logind.as_ref().and_then(Greeter::detect),
+                screen: Screen::detect(),
+                do_not_disturb: DoNotDisturb::detect(),
+                appearance: Appearance::detect(),
+                mixer: Mixer::detect(),
+                backlight: Backlight::find(),
+                logind,
+            }
+        })
+    }
+}
+
+impl Default for LinuxSystemCommands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemCommands for LinuxSystemCommands {
+    fn supported(&self) -> Vec<SystemCommand> {
+        let session = self.session();
+        let logind = session.logind.as_ref();
+        let can = |action| logind.is_some_and(|logind| logind.can(action));
+
+        SystemCommand::ALL
+            .into_iter()
+            .filter(|command| match command {
+                SystemCommand::Lock => logind.is_some(),
+                SystemCommand::LockAndSuspend | SystemCommand::Sleep => can(PowerAction::Suspend),
+                SystemCommand::HybridSleep => can(PowerAction::HybridSleep),
+                SystemCommand::Hibernate => can(PowerAction::Hibernate),
+                SystemCommand::Logout => session.desktop.can_logout(logind),
+                SystemCommand::SwitchUser => session.greeter.is_some(),
+                SystemCommand::Restart => can(PowerAction::Reboot),
+                SystemCommand::Shutdown => can(PowerAction::PowerOff),
+                SystemCommand::ScreenOff => session.screen.is_some(),
+                SystemCommand::ToggleDoNotDisturb => session.do_not_disturb.is_some(),
+                SystemCommand::ToggleDarkMode => session.appearance.is_some(),
+                SystemCommand::VolumeUp | SystemCommand::VolumeDown | SystemCommand::ToggleMute => {
+                    session.mixer.is_some()
+                }
+                SystemCommand::BrightnessUp | SystemCommand::BrightnessDown => {
+                    logind.is_some() && session.backlight.is_some()
+                }
+                SystemCommand::EmptyTrash => true,
+            })
+            .collect()
+    }
+
+    fn execute(&self, command: SystemCommand) -> NovaResult<()> {
+        let session = self.session();
+        let logind = || require(&session.logind, command);
+
+        match command {
+            SystemCommand::Lock => logind()?.lock_session(),
+            SystemCommand::LockAndSuspend => {
+                let logind = logind()?;
+                logind.lock_session()?;
+                logind.power(PowerAction::Suspend)
+            }
+            SystemCommand::Sleep => logind()?.power(PowerAction::Suspend),
+            SystemCommand::HybridSleep => logind()?.power(PowerAction::HybridSleep),
+            SystemCommand::Hibernate => logind()?.power(PowerAction::Hibernate),
+            SystemCommand::Logout => session.desktop.logout(session.logind.as_ref()),
+            SystemCommand::SwitchUser => require(&session.greeter, command)?.switch_user(logind()?),
+            SystemCommand::Restart => logind()?.power(PowerAction::Reboot),
+            SystemCommand::Shutdown => logind()?.power(PowerAction::PowerOff),
+            SystemCommand::ScreenOff => require(&session.screen, command)?.turn_off(),
+            SystemCommand::ToggleDoNotDisturb => {
+                require(&session.do_not_disturb, command)?.toggle()
+            }
+            SystemCommand::ToggleDarkMode => {
+                require(&session.appearance, command)?.toggle_dark_mode()
+            }
+            SystemCommand::VolumeUp => require(&session.mixer, command)?.volume_up(),
+            SystemCommand::VolumeDown => require(&session.mixer, command)?.volume_down(),
+            SystemCommand::ToggleMute => require(&session.mixer, command)?.toggle_mute(),
+            SystemCommand::BrightnessUp => {
+                require(&session.backlight, command)?.step(logind()?, true)
+            }
+            SystemCommand::BrightnessDown => {
+                require(&session.backlight, command)?.step(logind()?, false)
+            }
+            SystemCommand::EmptyTrash => trash::empty(),
+        }
+    }
+}
+
+/// The part of the session a command needs, if the session has it
+fn require<T>(part: &Option<T>, command: SystemCommand) -> NovaResult<&T> {
+    part.as_ref().ok_or_else(|| {
+        NovaError::Platform(format!("{:?} is not supported in this session", command))
+    })
+}
@@ -0,0 +1,107 @@
+//! Do Not Disturb
+//!
+//! The notification specification has no do-not-disturb switch, so it is
+//! toggled through the running notification server's own interface, found
+//! from the name the server reports.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use nova_core::NovaResult;
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+use super::bus::{self, Object};
+use super::gsettings;
+use crate::linux::notifications::NOTIFICATIONS;
+
+const DUNST: Object = Object {
+    interface: "org.dunstproject.cmd0",
+    ..NOTIFICATIONS
+};
+
+const SWAYNC: Object = Object {
+    service: "org.erikreider.swaync.cc",
+    path: "/org/erikreider/swaync/cc",
+    interface: "org.erikreider.swaync.cc",
+};
+
+const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+
+/// Notification servers with a do-not-disturb mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Server {
+    Dunst,
+    SwayNotificationCenter,
+    GnomeShell,
+    Plasma,
+}
+
+pub struct DoNotDisturb {
+    server: Server,
+    /// Plasma drops an inhibition when the connection that asked for it
+    /// closes, so the connection is kept along with the inhibition cookie
+    plasma_inhibition: Mutex<Option<(Connection, u32)>>,
+}
+
+impl DoNotDisturb {
+    /// Ask the session's notification server for its name
+    pub fn detect() -> Option<Self> {
+        let connection = Connection::session().ok()?;
+        let (name, _vendor, _version, _spec_version): (String, String, String, String) =
+            NOTIFICATIONS
+                .call_for(&connection, "GetServerInformation", &())
+                .ok()?;
+        let server = match name.as_str() {
+            "dunst" => Server::Dunst,
+            "SwayNotificationCenter" => Server::SwayNotificationCenter,
+            "gnome-shell" => Server::GnomeShell,
+            "Plasma" => Server::Plasma,
+            _ => return None,
+        };
+        Some(Self {
+            server,
+            plasma_inhibition: Mutex::new(None),
+        })
+    }
+
+    pub fn toggle(&self) -> NovaResult<()> {
+        match self.server {
+            Server::Dunst => {
+                let connection = bus::session()?;
+                let paused: bool = DUNST.property(&connection, "paused")?;
+                DUNST.set_property(&connection, "paused", !paused)
+            }
+            Server::SwayNotificationCenter => {
+                let connection = bus::session()?;
+                SWAYNC
+                    .call_for::<_, bool>(&connection, "ToggleDnd", &())
+                    .map(|_| ())
+            }
+            Server::GnomeShell => {
+                let shown = gsettings::get(GNOME_SCHEMA, "show-banners")
+                    .is_none_or(|value| value == "true");
+                gsettings::set(GNOME_SCHEMA, "show-banners", &(!shown).to_string())
+            }
+            Server::Plasma => {
+                let mut inhibition = self.plasma_inhibition.lock().unwrap();
+                match inhibition.take() {
+                    Some((connection, cookie)) => {
+                        NOTIFICATIONS.call(&connection, "UnInhibit", &(cookie,))
+                    }
+                    None => {
+                        let connection = bus::session()?;
+                        let hints: HashMap<&str, Value> = HashMap::new();
+                        let cookie: u32 = NOTIFICATIONS.call_for(
+                            &connection,
+                            "Inhibit",
+                            &("nova", "Do Not Disturb", hints),
+                        )?;
+                        *inhibition = Some((connection, cookie));
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Turning the displays off
+//!
+//! Plasma's power management has a shortcut for this; X11 servers put the
+//! monitors to sleep through DPMS. Both wake the displays on the next
+//! input. sway and Hyprland keep outputs off until told otherwise, so they
+//! aren't offered.
+
+use std::thread;
+use std::time::Duration;
+
+use nova_core::{NovaError, NovaResult};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::dpms::{self, ConnectionExt, DPMSMode};
+
+use super::bus::{self, Object};
+use crate::linux::xdg;
+
+const POWERDEVIL: Object = Object {
+    service: "org.kde.kglobalaccel",
+    path: "/component/org_kde_powerdevil",
+    interface: "org.kde.kglobalaccel.Component",
+};
+
+/// Time for the key that ran the command to be released, which would
+/// otherwise wake the displays right away
+const RELEASE_DELAY: Duration = Duration::from_millis(500);
+
+pub enum Screen {
+    Plasma,
+    X11,
+}
+
+impl Screen {
+    pub fn detect() -> Option<Self> {
+        let desktops = xdg::current_desktops();
+        if desktops.iter().any(|d| d.eq_ignore_ascii_case("KDE")) {
+            let session = bus::session().ok()?;
+            return bus::has_service(&session, POWERDEVIL.service).then_some(Screen::Plasma);
+        }
+        if std::env::var_os("WAYLAND_DISPLAY").is_some_and(|d| !d.is_empty()) {
+            return None;
+        }
+        let (conn, _) = x11rb::connect(None).ok()?;
+        conn.extension_information(dpms::X11_EXTENSION_NAME)
+            .ok()??;
+        let reply = conn.dpms_capable().ok()?.reply().ok()?;
+        reply.capable.then_some(Screen::X11)
+    }
+
+    /// Turn the displays off after a short delay, in the background
+    pub fn turn_off(&self) -> NovaResult<()> {
+        let turn_off = match self {
+            Screen::Plasma => plasma_turn_off,
+            Screen::X11 => x11_turn_off,
+        };
+        thread::spawn(move || {
+            thread::sleep(RELEASE_DELAY);
+            if let Err(e) = turn_off() {
+                eprintln!("[Nova] Failed to turn off the screen: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+fn plasma_turn_off() -> NovaResult<()> {
+    let session = bus::session()?;
+    POWERDEVIL.call(&session, "invokeShortcut", &("Turn Off Screen",))
+}
+
+fn x11_turn_off() -> NovaResult<()> {
+    let (conn, _) = x11rb::connect(None).map_err(x11_error)?;
+    // Forcing a level fails while DPMS is disabled
+    conn.dpms_enable().map_err(x11_error)?;
+    conn.dpms_force_level(DPMSMode::OFF)
+        .map_err(x11_error)?
+        .check()
+        .map_err(x11_error)?;
+    conn.flush().map_err(x11_error)
+}
+
+fn x11_error(e: impl std::fmt::Display) -> NovaError {
+    NovaError::Platform(format!("X11 request failed: {}", e))
+}
@@ -0,0 +1,245 @@
+//! Logging out of the desktop session
+//!
+//! Ending the logind session stops every process at once, so apps get no
+//! chance to save. Each desktop's own session manager is asked instead:
+//! GNOME's and Plasma's over D-Bus, Xfce's through `xfce4-session-logout`,
+//! and sway, i3 and Hyprland through their IPC, as `swaymsg exit` and
+//! `hyprctl dispatch exit` do. logind is only the fallback for sessions
+//! with none of these.
+
+use std::process::Command;
+
+use nova_core::{NovaError, NovaResult};
+use zbus::blocking::Connection;
+
+use super::bus::{self, Object};
+use super::logind::Logind;
+use crate::linux::window_manager::{hyprland::Hyprland, i3::I3};
+use crate::linux::xdg;
+
+/// GNOME's session manager, also run by Cinnamon, MATE and Budgie
+const GNOME_SESSION: Object = Object {
+    service: "org.gnome.SessionManager",
+    path: "/org/gnome/SessionManager",
+    interface: "org.gnome.SessionManager",
+};
+
+/// `Logout` mode that skips the confirmation dialog
+const GNOME_LOGOUT_NO_CONFIRMATION: u32 = 1;
+
+/// Plasma's shutdown service, since Plasma 5.25
+const PLASMA_SHUTDOWN: Object = Object {
+    service: "org.kde.Shutdown",
+    path: "/Shutdown",
+    interface: "org.kde.Shutdown",
+};
+
+/// Plasma's session manager
+pub const KSMSERVER: Object = Object {
+    service: "org.kde.ksmserver",
+    path: "/KSMServer",
+    interface: "org.kde.KSMServerInterface",
+};
+
+/// Desktop session Nova runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Desktop {
+    Gnome,
+    Plasma,
+    Xfce,
+    Sway,
+    I3,
+    Hyprland,
+    Other,
+}
+
+impl Desktop {
+    pub fn detect() -> Self {
+        let session_type = std::env::var("XDG_SESSION_TYPE").ok();
+        Self::from_env(&xdg::current_desktops(), session_type.as_deref(), |name| {
+            std::env::var_os(name).is_some_and(|value| !value.is_empty())
+        })
+    }
+
+    /// Pick the desktop from `XDG_CURRENT_DESKTOP`, else from the IPC
+    /// variables of compositors started without a display manager, which
+    /// often leave it unset
+    fn from_env(
+        desktops: &[String],
+        session_type: Option<&str>,
+        is_set: impl Fn(&str) -> bool,
+    ) -> Self {
+        for desktop in desktops {
+            match desktop.to_ascii_lowercase().as_str() {
+                "gnome" | "unity" | "budgie" | "x-cinnamon" | "mate" => return Desktop::Gnome,
+                "kde" => return Desktop::Plasma,
+                "xfce" => return Desktop::Xfce,
+                "sway" => return Desktop::Sway,
+                "i3" => return Desktop::I3,
+                "hyprland" => return Desktop::Hyprland,
+                _ => {}
+            }
+        }
+        match session_type {
+            Some("wayland") if is_set("HYPRLAND_INSTANCE_SIGNATURE") => Desktop::Hyprland,
+            Some("wayland") if is_set("SWAYSOCK") => Desktop::Sway,
+            Some("x11") if is_set("I3SOCK") => Desktop::I3,
+            _ => Desktop::Other,
+        }
+    }
+
+    /// Whether [`logout`](Self::logout) has a way to end the session: a
+    /// known desktop's own logout, else logind
+    pub fn can_logout(self, logind: Option<&Logind>) -> bool {
+        self != Desktop::Other || logind.is_some()
+    }
+
+    /// Log out, letting apps save their state where the desktop allows it
+    pub fn logout(self, logind: Option<&Logind>) -> NovaResult<()> {
+        match self {
+            Desktop::Gnome => gnome_logout(&bus::session()?),
+            Desktop::Plasma => plasma_logout(&bus::session()?),
+            Desktop::Xfce => {
+                let status = Command::new("xfce4-session-logout")
+                    .arg("--logout")
+                    .status()
+                    .map_err(|e| NovaError::Platform(format!("Failed to log out: {}", e)))?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(NovaError::Platform(format!(
+                        "xfce4-session-logout failed: {}",
+                        status
+                    )))
+                }
+            }
+            Desktop::Sway | Desktop::I3 => I3::from_env()
+                .ok_or_else(|| NovaError::Platform("No i3 IPC socket found".to_string()))?
+                .run_command("exit"),
+            Desktop::Hyprland => Hyprland::from_env()
+                .ok_or_else(|| NovaError::Platform("No Hyprland socket found".to_string()))?
+                .dispatch("exit"),
+            Desktop::Other => logind
+                .ok_or_else(|| NovaError::Platform("No session manager found".to_string()))?
+                .terminate_session(),
+        }
+    }
+}
+
+fn gnome_logout(session: &Connection) -> NovaResult<()> {
+    GNOME_SESSION.call(session, "Logout", &(GNOME_LOGOUT_NO_CONFIRMATION,))
+}
+
+fn plasma_logout(session: &Connection) -> NovaResult<()> {
+    if bus::has_service(session, PLASMA_SHUTDOWN.service) {
+        return PLASMA_SHUTDOWN.call(session, "logout", &());
+    }
+    // No confirmation, log out only, at the default time
+    KSMSERVER.call(session, "logout", &(0i32, 0i32, 0i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::linux::system_commands::bus::tests::TestBus;
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct GnomeSession(Calls);
+
+    #[zbus::interface(name = "org.gnome.SessionManager")]
+    impl GnomeSession {
+        fn logout(&self, mode: u32) {
+            self.0.lock().unwrap().push(format!("Logout {}", mode));
+        }
+    }
+
+    struct Ksmserver(Calls);
+
+    #[zbus::interface(name = "org.kde.KSMServerInterface")]
+    impl Ksmserver {
+        #[zbus(name = "logout")]
+        fn logout(&self, confirm: i32, kind: i32, mode: i32) {
+            let call = format!("ksmserver logout {} {} {}", confirm, kind, mode);
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    struct PlasmaShutdown(Calls);
+
+    #[zbus::interface(name = "org.kde.Shutdown")]
+    impl PlasmaShutdown {
+        #[zbus(name = "logout")]
+        fn logout(&self) {
+            self.0.lock().unwrap().push("Shutdown logout".to_string());
+        }
+    }
+
+    #[test]
+    fn test_detect_desktop() {
+        let unset = |_: &str| false;
+        for (names, desktop) in [
+            ("ubuntu:GNOME", Desktop::Gnome),
+            ("X-Cinnamon", Desktop::Gnome),
+            ("KDE", Desktop::Plasma),
+            ("XFCE", Desktop::Xfce),
+            ("sway", Desktop::Sway),
+            ("Hyprland", Desktop::Hyprland),
+        ] {
+            let desktops: Vec<String> = names.split(':').map(|d| d.to_string()).collect();
+            assert_eq!(
+                Desktop::from_env(&desktops, None, unset),
+                desktop,
+                "{}",
+                names
+            );
+        }
+
+        let sway = |name: &str| name == "SWAYSOCK";
+        assert_eq!(Desktop::from_env(&[], Some("wayland"), sway), Desktop::Sway);
+        // sway's variable means nothing outside a Wayland session
+        assert_eq!(Desktop::from_env(&[], Some("x11"), sway), Desktop::Other);
+        assert_eq!(Desktop::from_env(&[], Some("tty"), unset), Desktop::Other);
+    }
+
+    #[test]
+    fn test_can_logout() {
+        assert!(Desktop::Sway.can_logout(None));
+        assert!(Desktop::Gnome.can_logout(None));
+        assert!(!Desktop::Other.can_logout(None));
+    }
+
+    #[test]
+    fn test_logout_calls() {
+        let Some(bus) = TestBus::start() else {
+            return;
+        };
+        let calls = Calls::default();
+        let client = bus.connect();
+
+        let _gnome = bus.serve(
+            GNOME_SESSION.service,
+            GNOME_SESSION.path,
+            GnomeSession(calls.clone()),
+        );
+        gnome_logout(&client).unwrap();
+
+        // Plasma before 5.25 only has ksmserver
+        let ksmserver = bus.serve(KSMSERVER.service, KSMSERVER.path, Ksmserver(calls.clone()));
+        plasma_logout(&client).unwrap();
+        let _shutdown = bus.serve(
+            PLASMA_SHUTDOWN.service,
+            PLASMA_SHUTDOWN.path,
+            PlasmaShutdown(calls.clone()),
+        );
+        plasma_logout(&client).unwrap();
+        drop(ksmserver);
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["Logout 1", "ksmserver logout 0 0 0", "Shutdown logout"]
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Emptying the trash
+//!
+//! Following the freedesktop trash specification, trashed files live in
+//! `$XDG_DATA_HOME/Trash` and, for other file systems, in `.Trash/$uid` or
+//! `.Trash-$uid` at the top of each mount. A trash directory holds the
+//! files, their `.trashinfo` records and a cache of directory sizes.
+//!
+//! Mounts may be writable by other users, so trash directories there are
+//! only trusted as the specification allows: `.Trash` must be a real
+//! directory with the sticky bit set, and the per-user directories real
+//! directories owned by the user. None of them may be symlinks.
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use nova_core::{NovaError, NovaResult};
+
+/// Delete every trashed file the user can reach
+pub fn empty() -> NovaResult<()> {
+    let mut failed = 0;
+    for trash in trash_dirs() {
+        if let Err(e) = empty_dir(&trash) {
+            eprintln!("[Nova] Failed to empty {}: {}", trash.display(), e);
+            failed += 1;
+        }
+    }
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(NovaError::Platform(format!(
+            "Failed to empty {} trash directories",
+            failed
+        )))
+    }
+}
+
+/// Sticky bit of a file mode
+const STICKY: u32 = 0o1000;
+
+/// Existing trash directories: the home trash, then those of other mounts
+fn trash_dirs() -> Vec<PathBuf> {
+    let uid = nix::unistd::getuid().as_raw();
+    let mounts = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    let mount_trashes = mount_points(&mounts)
+        .into_iter()
+        .flat_map(|top| mount_trashes(&top, uid));
+
+    dirs::data_dir()
+        .map(|dir| dir.join("Trash"))
+        .filter(|dir| dir.is_dir())
+        .into_iter()
+        .chain(mount_trashes)
+        .collect()
+}
+
+/// The user's trash directories at the top of a mount that are safe to
+/// empty
+fn mount_trashes(top: &Path, uid: u32) -> Vec<PathBuf> {
+    let owned = |meta: &fs::Metadata| meta.uid() == uid;
+    let mut trashes = Vec::new();
+
+    let shared = top.join(".Trash");
+    if is_real_dir(&shared, |meta| meta.mode() & STICKY != 0) {
+        let trash = shared.join(uid.to_string());
+        if is_real_dir(&trash, owned) {
+            trashes.push(trash);
+        }
+    }
+    let trash = top.join(format!(".Trash-{}", uid));
+    if is_real_dir(&trash, owned) {
+        trashes.push(trash);
+    }
+    trashes
+}
+
+/// Check that a path is a directory rather than a symlink to one, and that
+/// its metadata passes `check`
+fn is_real_dir(path: &Path, check: impl Fn(&fs::Metadata) -> bool) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_dir() && check(&meta))
+}
+
+/// Mount points listed in a mounts table, which escapes spaces and other
+/// special characters in octal
+fn mount_points(mounts: &str) -> Vec<PathBuf> {
+    mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|point| PathBuf::from(unescape_octal(point)))
+        .collect()
+}
+
+fn unescape_octal(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let code = tail
+            .get(..3)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match code {
+            Some(code) if byte == b'\\' => {
+                bytes.push(code);
+                rest = &tail[3..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Delete the contents of one trash directory, keeping its layout
+fn empty_dir(trash: &Path) -> io::Result<()> {
+    // Records go first, so an interrupted run leaves no records of
+    // deleted files
+    for sub in ["info", "files", "expunged"] {
+        // A symlink here could point anywhere, such as another user's home
+        let dir = trash.join(sub);
+        if !is_real_dir(&dir, |_| true) {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    match fs::remove_file(trash.join("directorysizes")) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mount_points() {
+        let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                      /dev/sda1 /run/media/me/My\\040Disk vfat rw 0 0\n";
+        assert_eq!(
+            mount_points(mounts),
+            vec![PathBuf::from("/"), PathBuf::from("/run/media/me/My Disk")]
+        );
+    }
+
+    #[test]
+    fn test_empty_dir() {
+        let trash = std::env::temp_dir().join(format!("nova-trash-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&trash);
+        fs::create_dir_all(trash.join("files").join("photos")).unwrap();
+        fs::create_dir_all(trash.join("info")).unwrap();
+        fs::write(trash.join("files").join("notes.txt"), "notes").unwrap();
+        fs::write(trash.join("files").join("photos").join("a.png"), "png").unwrap();
+        fs::write(
+            trash.join("info").join("notes.txt.trashinfo"),
+            "[Trash Info]",
+        )
+        .unwrap();
+        fs::write(trash.join("directorysizes"), "3 0 photos\n").unwrap();
+
+        empty_dir(&trash).unwrap();
+        let left = |sub: &str| fs::read_dir(trash.join(sub)).unwrap().count();
+        assert_eq!((left("files"), left("info")), (0, 0));
+        assert!(!trash.join("directorysizes").exists());
+        fs::remove_dir_all(&trash).unwrap();
+    }
+
+    #[test]
+    fn test_empty_dir_skips_symlinks() {
+        let root = std::env::temp_dir().join(format!("nova-trash-link-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let victim = root.join("home");
+        let trash = root.join("mount").join(".Trash-1000");
+        fs::create_dir_all(&victim).unwrap();
+        fs::create_dir_all(&trash).unwrap();
+        fs::write(victim.join("thesis.tex"), "chapter 1").unwrap();
+        std::os::unix::fs::symlink(&victim, trash.join("files")).unwrap();
+
+        empty_dir(&trash).unwrap();
+        assert!(victim.join("thesis.tex").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_mount_trashes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let top = std::env::temp_dir().join(format!("nova-trash-mount-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&top);
+        let uid = nix::unistd::getuid().as_raw();
+        let shared = top.join(".Trash");
+        fs::create_dir_all(shared.join(uid.to_string())).unwrap();
+        assert!(mount_trashes(&top, uid).is_empty());
+
+        // Only a sticky `.Trash` is trusted
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o1777)).unwrap();
+        assert_eq!(mount_trashes(&top, uid), vec![shared.join(uid.to_string())]);
+        // Nor is a per-user trash owned by someone else
+        let other = uid + 1;
+        fs::create_dir(shared.join(other.to_string())).unwrap();
+        fs::create_dir(top.join(format!(".Trash-{}", other))).unwrap();
+        assert!(mount_trashes(&top, other).is_empty());
+
+        // A symlinked per-user trash is skipped
+        let elsewhere = top.join("elsewhere");
+        fs::create_dir(&elsewhere).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, top.join(format!(".Trash-{}", uid))).unwrap();
+        assert_eq!(mount_trashes(&top, uid).len(), 1);
+        fs::remove_dir_all(&top).unwrap();
+    }
+}
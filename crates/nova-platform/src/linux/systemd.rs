@@ -0,0 +1,77 @@
+//! Transient systemd scopes for launched apps
+//!
+//! Desktop shells run each app in its own scope of the user's service
+//! manager, so the app isn't stopped along with the launcher's unit and its
+//! resources are accounted to the app. Scopes are named as described in
+//! <https://systemd.io/DESKTOP_ENVIRONMENTS/>:
+//! `app-<launcher>-<ApplicationID>-<RANDOM>.scope`.
+
+use nova_core::{NovaError, NovaResult};
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+/// Move a freshly spawned process into a new scope named after the app
+pub fn move_to_scope(app_id: &str, pid: u32) -> NovaResult<()> {
+    let dbus_error =
+        |e: zbus::Error| NovaError::Platform(format!("Failed to create systemd scope: {}", e));
+
+    let connection = Connection::session().map_err(dbus_error)?;
+    let properties: Vec<(&str, Value)> = vec![
+        ("PIDs", Value::from(vec![pid])),
+        // Drop the unit once the app exits, even when it failed
+        ("CollectMode", Value::from("inactive-or-failed")),
+    ];
+    let auxiliary: Vec<(&str, Vec<(&str, Value)>)> = Vec::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.systemd1"),
+            "/org/freedesktop/systemd1",
+            Some("org.freedesktop.systemd1.Manager"),
+            "StartTransientUnit",
+            &(scope_name(app_id, pid), "fail", properties, auxiliary),
+        )
+        .map_err(dbus_error)?;
+    Ok(())
+}
+
+/// Unit name of an app's scope, using the pid as the random part
+fn scope_name(app_id: &str, pid: u32) -> String {
+    format!("app-nova-{}-{}.scope", escape(app_id), pid)
+}
+
+/// Escape a string for use in a unit name as `systemd-escape` does; `-`
+/// separates the parts of the name, so it is escaped too
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, byte) in value.bytes().enumerate() {
+        let keep =
+            byte.is_ascii_alphanumeric() || byte == b':' || byte == b'_' || (byte == b'.' && i > 0);
+        if keep {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_name() {
+        assert_eq!(
+            scope_name("org.gnome.Nautilus", 4242),
+            "app-nova-org.gnome.Nautilus-4242.scope"
+        );
+        assert_eq!(
+            scope_name("google-chrome", 7),
+            "app-nova-google\\x2dchrome-7.scope"
+        );
+        assert_eq!(
+            scope_name(".hidden app", 1),
+            "app-nova-\\x2ehidden\\x20app-1.scope"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Hyprland through its request socket
+//!
+//! Requests are plain text (`j/clients`, `dispatch ...`); the `j/` prefix
+//! asks for a JSON reply.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use nova_core::{NovaError, NovaResult, OpenWindow};
+use serde_json::Value;
+
+pub struct Hyprland {
+    socket: PathBuf,
+}
+
+impl Hyprland {
+    /// Find the request socket of the running Hyprland instance
+    pub fn from_env() -> Option<Self> {
+        let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+        // Hyprland moved its sockets from /tmp to the runtime dir in 0.40
+        let runtime = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+        let socket = runtime
+            .into_iter()
+            .chain([PathBuf::from("/tmp")])
+            .map(|dir| dir.join("hypr").join(&signature).join(".socket.sock"))
+            .find(|path| path.exists())?;
+        Some(Self { socket })
+    }
+
+    pub fn list_windows(&self) -> NovaResult<Vec<OpenWindow>> {
+        let reply = self.request("j/clients")?;
+        let clients: Value = serde_json::from_str(&reply)
+            .map_err(|e| NovaError::Platform(format!("Invalid Hyprland reply: {}", e)))?;
+        Ok(windows_from_clients(&clients))
+    }
+
+    pub fn focus_window(&self, id: &str) -> NovaResult<()> {
+        self.dispatch(&format!("focuswindow address:{}", id))
+    }
+
+    /// Run a dispatcher, as `hyprctl dispatch` does
+    pub fn dispatch(&self, dispatcher: &str) -> NovaResult<()> {
+        let reply = self.request(&format!("dispatch {}", dispatcher))?;
+        if reply.trim() == "ok" {
+            Ok(())
+        } else {
+            Err(NovaError::Platform(format!(
+                "Dispatch {} failed: {}",
+                dispatcher,
+                reply.trim()
+            )))
+        }
+    }
+
+    fn request(&self, request: &str) -> NovaResult<String> {
+        let ipc_error =
+            |e: std::io::Error| NovaError::Platform(format!("Hyprland IPC failed: {}", e));
+
+        let mut stream = UnixStream::connect(&self.socket).map_err(ipc_error)?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .map_err(ipc_error)?;
+        stream.write_all(request.as_bytes()).map_err(ipc_error)?;
+
+        // Hyprland closes the connection after replying
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).map_err(ipc_error)?;
+        Ok(reply)
+    }
+}
+
+/// Windows of a `j/clients` reply, most recently focused first
+fn windows_from_clients(clients: &Value) -> Vec<OpenWindow> {
+    let mut clients: Vec<&Value> = clients
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|client| client["mapped"].as_bool() != Some(false))
+        .collect();
+    clients.sort_by_key(|client| client["focusHistoryID"].as_i64().unwrap_or(i64::MAX));
+
+    clients
+        .into_iter()
+        .filter_map(|client| {
+            Some(OpenWindow {
+                id: client["address"].as_str()?.to_string(),
+                title: client["title"].as_str().unwrap_or_default().to_string(),
+                app_id: client["class"].as_str().unwrap_or_default().to_string(),
+                workspace: client["workspace"]["name"]
+                    .as_str()
+                    .filter(|name| !name.is_empty())
+                    .map(|name| name.to_string()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_from_clients() {
+        let clients: Value = serde_json::from_str(
+            r#"[
+                {"address": "0x5a1b", "mapped": true, "hidden": false,
+                 "workspace": {"id": 1, "name": "1"}, "class": "kitty",
+                 "title": "~/src", "focusHistoryID": 1},
+                {"address": "0x5a2c", "mapped": false, "hidden": true,
+                 "workspace": {"id": -1, "name": ""}, "class": "",
+                 "title": "", "focusHistoryID": 3},
+                {"address": "0x5a3d", "mapped": true, "hidden": false,
+                 "workspace": {"id": -98, "name": "special:magic"},
+                 "class": "org.gnome.Nautilus", "title": "Downloads",
+                 "focusHistoryID": 0}
+            ]"#,
+        )
+        .unwrap();
+
+        let windows = windows_from_clients(&clients);
+        assert_eq!(
+            windows,
+            vec![
+                OpenWindow {
+                    id: "0x5a3d".to_string(),
+                    title: "Downloads".to_string(),
+                    app_id: "org.gnome.Nautilus".to_string(),
+                    workspace: Some("special:magic".to_string()),
+                },
+                OpenWindow {
+                    id: "0x5a1b".to_string(),
+                    title: "~/src".to_string(),
+                    app_id: "kitty".to_string(),
+                    workspace: Some("1".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! sway and i3 through the i3 IPC protocol
+//!
+//! Messages are the `i3-ipc` magic, the payload length and the message type
+//! as native-endian 32-bit integers, then a JSON payload.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use nova_core::{NovaError, NovaResult, OpenWindow};
+use serde_json::Value;
+
+const MAGIC: &[u8] = b"i3-ipc";
+const RUN_COMMAND: u32 = 0;
+const GET_TREE: u32 = 4;
+
+/// Workspace holding i3's scratchpad
+const SCRATCHPAD: &str = "__i3_scratch";
+
+pub struct I3 {
+    socket: PathBuf,
+}
+
+impl I3 {
+    /// Find the IPC socket of a running sway or i3
+    pub fn from_env() -> Option<Self> {
+        let socket = ["SWAYSOCK", "I3SOCK"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .or_else(|| {
+                // i3 sessions started without I3SOCK still answer here
+                std::env::var_os("DISPLAY")?;
+                let output = std::process::Command::new("i3")
+                    .arg("--get-socketpath")
+                    .output()
+                    .ok()
+                    .filter(|o| o.status.success())?;
+                let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+                path.exists().then_some(path)
+            })?;
+        Some(Self { socket })
+    }
+
+    pub fn list_windows(&self) -> NovaResult<Vec<OpenWindow>> {
+        let tree = self.request(GET_TREE, "")?;
+        Ok(windows_from_tree(&tree))
+    }
+
+    pub fn focus_window(&self, id: &str) -> NovaResult<()> {
+        let id: u64 = id
+            .parse()
+            .map_err(|_| NovaError::Platform(format!("Invalid window id: {}", id)))?;
+        self.run_command(&format!("[con_id={}] focus", id))
+    }
+
+    /// Run a command, as `swaymsg` or `i3-msg` do
+    pub fn run_command(&self, command: &str) -> NovaResult<()> {
+        let reply = self.request(RUN_COMMAND, command)?;
+
+        // One outcome per command, each with an error message on failure
+        let failure = reply
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|outcome| outcome["success"] != Value::Bool(true));
+        match failure {
+            Some(outcome) => Err(NovaError::Platform(format!(
+                "Command {} failed: {}",
+                command,
+                outcome["error"].as_str().unwrap_or("unknown error")
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn request(&self, kind: u32, payload: &str) -> NovaResult<Value> {
+        let ipc_error = |e: std::io::Error| NovaError::Platform(format!("i3 IPC failed: {}", e));
+
+        let mut stream = UnixStream::connect(&self.socket).map_err(ipc_error)?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .map_err(ipc_error)?;
+
+        let mut message = Vec::with_capacity(14 + payload.len());
+        message.extend_from_slice(MAGIC);
+        message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(payload.as_bytes());
+        stream.write_all(&message).map_err(ipc_error)?;
+
+        let mut header = [0u8; 14];
+        stream.read_exact(&mut header).map_err(ipc_error)?;
+        if &header[..6] != MAGIC {
+            return Err(NovaError::Platform("Invalid i3 IPC reply".to_string()));
+        }
+        let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]);
+        let mut body = vec![0u8; length as usize];
+        stream.read_exact(&mut body).map_err(ipc_error)?;
+
+        serde_json::from_slice(&body)
+            .map_err(|e| NovaError::Platform(format!("Invalid i3 IPC reply: {}", e)))
+    }
+}
+
+/// Collect the windows of a `GET_TREE` reply
+fn windows_from_tree(tree: &Value) -> Vec<OpenWindow> {
+    let mut windows = Vec::new();
+    collect(tree, None, &mut windows);
+    windows
+}
+
+fn collect(node: &Value, workspace: Option<&str>, windows: &mut Vec<OpenWindow>) {
+    let workspace = match node["type"].as_str() {
+        Some("workspace") => node["name"].as_str().filter(|name| *name != SCRATCHPAD),
+        _ => workspace,
+    };
+
+    // sway gives Wayland windows an app id; X11 windows have a class
+    let app_id = node["app_id"]
+        .as_str()
+        .or_else(|| node["window_properties"]["class"].as_str());
+    let is_window = node["pid"].is_number() || node["window"].is_number();
+    if let (true, Some(id)) = (is_window, node["id"].as_u64()) {
+        windows.push(OpenWindow {
+            id: id.to_string(),
+            title: node["name"].as_str().unwrap_or_default().to_string(),
+            app_id: app_id.unwrap_or_default().to_string(),
+            workspace: workspace.map(|w| w.to_string()),
+        });
+    }
+
+    for key in ["nodes", "floating_nodes"] {
+        for child in node[key].as_array().into_iter().flatten() {
+            collect(child, workspace, windows);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_from_tree() {
+        let tree: Value = serde_json::from_str(
+            r#"{
+                "id": 1, "type": "root", "name": "root",
+                "nodes": [{
+                    "id": 2, "type": "output", "name": "__i3",
+                    "nodes": [{
+                        "id": 3, "type": "workspace", "name": "__i3_scratch",
+                        "nodes": [],
+                        "floating_nodes": [{
+                            "id": 4, "type": "floating_con", "name": "scratch term",
+                            "pid": 10, "app_id": "foot"
+                        }]
+                    }]
+                }, {
+                    "id": 5, "type": "output", "name": "DP-1",
+                    "nodes": [{
+                        "id": 6, "type": "workspace", "name": "2: web",
+                        "nodes": [{
+                            "id": 7, "type": "con", "name": null,
+                            "nodes": [{
+                                "id": 8, "type": "con", "name": "Inbox - Mozilla Firefox",
+                                "pid": 11, "app_id": "firefox"
+                            }, {
+                                "id": 9, "type": "con", "name": "Slack",
+                                "window": 4194307, "app_id": null,
+                                "window_properties": {"class": "Slack", "instance": "slack"}
+                            }]
+                        }],
+                        "floating_nodes": []
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let windows = windows_from_tree(&tree);
+        let summary: Vec<(&str, &str, &str, Option<&str>)> = windows
+            .iter()
+            .map(|w| {
+                (
+                    w.id.as_str(),
+                    w.title.as_str(),
+                    w.app_id.as_str(),
+                    w.workspace.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("4", "scratch term", "foot", None),
+                ("8", "Inbox - Mozilla Firefox", "firefox", Some("2: web")),
+                ("9", "Slack", "Slack", Some("2: web")),
+            ]
+        );
+    }
+}
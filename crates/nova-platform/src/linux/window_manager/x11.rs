@@ -0,0 +1,187 @@
+//! X11 windows through the Extended Window Manager Hints
+//!
+//! The window manager lists managed windows in `_NET_CLIENT_LIST_STACKING`
+//! on the root window and activates one when sent a `_NET_ACTIVE_WINDOW`
+//! client message.
+
+use nova_core::{NovaError, NovaResult, OpenWindow};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, GetPropertyReply, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_ACTIVE_WINDOW,
+        _NET_DESKTOP_NAMES,
+        _NET_WM_DESKTOP,
+        _NET_WM_NAME,
+        _NET_WM_STATE,
+        _NET_WM_STATE_SKIP_TASKBAR,
+        _NET_WM_WINDOW_TYPE,
+        _NET_WM_WINDOW_TYPE_DESKTOP,
+        _NET_WM_WINDOW_TYPE_DOCK,
+        UTF8_STRING,
+    }
+}
+
+/// `_NET_WM_DESKTOP` of windows shown on every desktop
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+fn x11_error(e: impl std::fmt::Display) -> NovaError {
+    NovaError::Platform(format!("X11 request failed: {}", e))
+}
+
+struct Session {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl Session {
+    fn connect() -> NovaResult<Self> {
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        let root = conn.setup().roots[screen].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    fn property(
+        &self,
+        window: Window,
+        property: u32,
+        kind: impl Into<u32>,
+    ) -> Option<GetPropertyReply> {
+        self.conn
+            .get_property(false, window, property, kind, 0, u32::MAX / 4)
+            .ok()?
+            .reply()
+            .ok()
+            .filter(|reply| reply.value_len > 0)
+    }
+
+    fn u32s(&self, window: Window, property: u32, kind: impl Into<u32>) -> Vec<u32> {
+        self.property(window, property, kind)
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    /// Title from `_NET_WM_NAME`, or the legacy `WM_NAME`
+    fn title(&self, window: Window) -> String {
+        self.property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING)
+            .or_else(|| self.property(window, AtomEnum::WM_NAME.into(), AtomEnum::STRING))
+            .map(|reply| String::from_utf8_lossy(&reply.value).into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Class part of `WM_CLASS`, which holds the instance and the class
+    fn class(&self, window: Window) -> String {
+        self.property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING)
+            .map(|reply| {
+                let value = String::from_utf8_lossy(&reply.value).into_owned();
+                let mut parts = value.split('\0');
+                let instance = parts.next().unwrap_or_default();
+                parts
+                    .next()
+                    .filter(|class| !class.is_empty())
+                    .unwrap_or(instance)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check if a pager should list the window
+    fn is_listed(&self, window: Window) -> bool {
+        let types = self.u32s(window, self.atoms._NET_WM_WINDOW_TYPE, AtomEnum::ATOM);
+        let states = self.u32s(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM);
+        !types.iter().any(|t| {
+            *t == self.atoms._NET_WM_WINDOW_TYPE_DOCK
+                || *t == self.atoms._NET_WM_WINDOW_TYPE_DESKTOP
+        }) && !states.contains(&self.atoms._NET_WM_STATE_SKIP_TASKBAR)
+    }
+}
+
+pub fn list_windows() -> NovaResult<Vec<OpenWindow>> {
+    let session = Session::connect()?;
+    let atoms = &session.atoms;
+
+    // The stacking order puts the most recently raised window last
+    let mut clients = session.u32s(
+        session.root,
+        atoms._NET_CLIENT_LIST_STACKING,
+        AtomEnum::WINDOW,
+    );
+    if clients.is_empty() {
+        clients = session.u32s(session.root, atoms._NET_CLIENT_LIST, AtomEnum::WINDOW);
+    } else {
+        clients.reverse();
+    }
+
+    let desktop_names: Vec<String> = session
+        .property(session.root, atoms._NET_DESKTOP_NAMES, atoms.UTF8_STRING)
+        .map(|reply| {
+            String::from_utf8_lossy(&reply.value)
+                .split('\0')
+                .map(|name| name.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let windows = clients
+        .into_iter()
+        .filter(|window| session.is_listed(*window))
+        .map(|window| {
+            let desktop = session
+                .u32s(window, atoms._NET_WM_DESKTOP, AtomEnum::CARDINAL)
+                .first()
+                .copied()
+                .filter(|d| *d != ALL_DESKTOPS);
+            OpenWindow {
+                id: window.to_string(),
+                title: session.title(window),
+                app_id: session.class(window),
+                workspace: desktop.map(|d| {
+                    desktop_names
+                        .get(d as usize)
+                        .filter(|name| !name.is_empty())
+                        .cloned()
+                        .unwrap_or_else(|| (d + 1).to_string())
+                }),
+            }
+        })
+        .collect();
+    Ok(windows)
+}
+
+pub fn focus_window(id: &str) -> NovaResult<()> {
+    let window: Window = id
+        .parse()
+        .map_err(|_| NovaError::Platform(format!("Invalid window id: {}", id)))?;
+    let session = Session::connect()?;
+
+    // Source 2 marks the request as coming from a pager, which window
+    // managers honour even when they prevent focus stealing
+    let event = ClientMessageEvent::new(
+        32,
+        window,
+        session.atoms._NET_ACTIVE_WINDOW,
+        [2, x11rb::CURRENT_TIME, 0, 0, 0],
+    );
+    session
+        .conn
+        .send_event(
+            false,
+            session.root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )
+        .map_err(x11_error)?;
+    session.conn.flush().map_err(x11_error)?;
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! XDG base directory and desktop entry lookup rules
+//!
+//! Follows the Desktop Entry and Base Directory specifications: entries are
+//! looked up in `$XDG_DATA_HOME/applications` first, then in each of
+//! `$XDG_DATA_DIRS`, and an entry's id comes from its path below the
+//! `applications` directory.
+
+use std::path::{Path, PathBuf};
+
+/// Directories holding desktop entries, most important first
+pub fn application_dirs() -> Vec<PathBuf> {
+    env_data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Base directories of icon themes, most important first
+pub fn icon_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut dirs = vec![home.join(".icons")];
+    dirs.extend(env_data_dirs().into_iter().map(|dir| dir.join("icons")));
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+    dirs
+}
+
+fn env_data_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    data_dirs(
+        std::env::var("XDG_DATA_HOME").ok().as_deref(),
+        std::env::var("XDG_DATA_DIRS").ok().as_deref(),
+        &home,
+    )
+}
+
+/// Data directories in precedence order.
+///
+/// Flatpak and Snap add their export directories to `XDG_DATA_DIRS` from a
+/// login script, which sessions started some other way miss; they are added
+/// here when absent, user exports before system ones.
+fn data_dirs(data_home: Option<&str>, data_dirs: Option<&str>, home: &Path) -> Vec<PathBuf> {
+    let data_home = data_home
+        .filter(|d| Path::new(d).is_absolute())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local/share"));
+
+    let mut system: Vec<PathBuf> = data_dirs
+        .filter(|d| !d.is_empty())
+        .unwrap_or("/usr/local/share:/usr/share")
+        .split(':')
+        .map(PathBuf::from)
+        .filter(|d| d.is_absolute())
+        .collect();
+    system.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    system.push(PathBuf::from("/var/lib/snapd/desktop"));
+
+    let mut dirs = vec![data_home.clone(), data_home.join("flatpak/exports/share")];
+    for dir in system {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Desktop file id of an entry below `applications_dir`, without the
+/// `.desktop` suffix: `kde4/konsole.desktop` becomes `kde4-konsole`
+pub fn desktop_file_id(applications_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(applications_dir).ok()?;
+    let id = relative.to_str()?.strip_suffix(".desktop")?;
+    Some(id.replace('/', "-"))
+}
+
+/// Names of the running desktop environments, from `XDG_CURRENT_DESKTOP`
+pub fn current_desktops() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(|d| d.to_string())
+        .collect()
+}
+
+/// Check `OnlyShowIn` and `NotShowIn` against the running desktops.
+///
+/// `OnlyShowIn` hides the entry unless one of the desktops is listed;
+/// otherwise `NotShowIn` hides it if one of them is.
+pub fn shown_in(
+    only_show_in: Option<Vec<&str>>,
+    not_show_in: Option<Vec<&str>>,
+    desktops: &[String],
+) -> bool {
+    let listed = |list: &[&str]| {
+        desktops
+            .iter()
+            .any(|desktop| list.iter().any(|d| d.eq_ignore_ascii_case(desktop)))
+    };
+
+    if let Some(only) = only_show_in {
+        return listed(&only);
+    }
+    !not_show_in.is_some_and(|not| listed(&not))
+}
+
+/// Check a `TryExec` value: an absolute path or a program on `PATH` that
+/// is executable
+pub fn is_installed(program: &str) -> bool {
+    let path = Path::new(program);
+    if path.is_absolute() {
+        return is_executable(path);
+    }
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(program)))
+    })
+}
+
+/// Check if a path is an executable file, following symlinks
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_dirs() {
+        let home = Path::new("/home/me");
+        let dirs = data_dirs(None, None, home);
+        assert_eq!(
+            dirs,
+            vec![
+                PathBuf::from("/home/me/.local/share"),
+                PathBuf::from("/home/me/.local/share/flatpak/exports/share"),
+                PathBuf::from("/usr/local/share"),
+                PathBuf::from("/usr/share"),
+                PathBuf::from("/var/lib/flatpak/exports/share"),
+                PathBuf::from("/var/lib/snapd/desktop"),
+            ]
+        );
+
+        // Relative paths are invalid and ignored; exports aren't repeated
+        let dirs = data_dirs(
+            Some("relative"),
+            Some("/var/lib/flatpak/exports/share:/opt/share:share"),
+            home,
+        );
+        assert_eq!(
+            dirs,
+            vec![
+                PathBuf::from("/home/me/.local/share"),
+                PathBuf::from("/home/me/.local/share/flatpak/exports/share"),
+                PathBuf::from("/var/lib/flatpak/exports/share"),
+                PathBuf::from("/opt/share"),
+                PathBuf::from("/var/lib/snapd/desktop"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_desktop_file_id() {
+        let dir = Path::new("/usr/share/applications");
+        assert_eq!(
+            desktop_file_id(dir, &dir.join("firefox.desktop")).as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(
+            desktop_file_id(dir, &dir.join("kde4/konsole.desktop")).as_deref(),
+            Some("kde4-konsole")
+        );
+        assert_eq!(desktop_file_id(dir, Path::new("/tmp/a.desktop")), None);
+    }
+
+    #[test]
+    fn test_shown_in() {
+        let gnome = vec!["ubuntu".to_string(), "GNOME".to_string()];
+        assert!(shown_in(None, None, &gnome));
+        assert!(shown_in(Some(vec!["GNOME", ""]), None, &gnome));
+        assert!(!shown_in(Some(vec!["KDE"]), None, &gnome));
+        assert!(!shown_in(None, Some(vec!["GNOME"]), &gnome));
+        assert!(shown_in(None, Some(vec!["XFCE"]), &gnome));
+        assert!(!shown_in(Some(vec!["KDE"]), None, &[]));
+    }
+}
@@ -0,0 +1,200 @@
+//! Decoder for XPM images
+//!
+//! Old applications still install XPM icons to `/usr/share/pixmaps`. The UI
+//! can't draw them, so they are decoded here and written out as PNG.
+
+/// A decoded image: width, height and RGBA pixels
+#[derive(Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Decode an XPM file. Only the `c` (color) visual of each color is used.
+pub fn decode(content: &str) -> Result<Image, String> {
+    let mut strings = strings(content).into_iter();
+
+    let header = strings.next().ok_or("XPM has no values")?;
+    let values: Vec<usize> = header
+        .split_whitespace()
+        .take(4)
+        .map(|v| {
+            v.parse()
+                .map_err(|_| format!("Invalid XPM values: {}", header))
+        })
+        .collect::<Result<_, _>>()?;
+    let [width, height, colors, cpp] = values[..] else {
+        return Err(format!("Invalid XPM values: {}", header));
+    };
+    if cpp == 0 || width == 0 || height == 0 || width * height > 1024 * 1024 {
+        return Err(format!("Unsupported XPM size: {}", header));
+    }
+
+    let mut palette = std::collections::HashMap::new();
+    for _ in 0..colors {
+        let line = strings.next().ok_or("XPM ends in the color table")?;
+        let key = line.get(..cpp).ok_or("Short XPM color line")?;
+        palette.insert(key.to_string(), color(&line[cpp..]));
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for _ in 0..height {
+        let row = strings.next().ok_or("XPM ends in the pixels")?;
+        for x in 0..width {
+            let key = row
+                .get(x * cpp..(x + 1) * cpp)
+                .ok_or("Short XPM pixel row")?;
+            let pixel = palette
+                .get(key)
+                .ok_or_else(|| format!("Unknown XPM color {:?}", key))?;
+            rgba.extend_from_slice(pixel);
+        }
+    }
+
+    Ok(Image {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+/// The C string literals of the file, in order
+fn strings(content: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => string.extend(chars.next()),
+                        c => string.push(c),
+                    }
+                }
+                strings.push(string);
+            }
+            // Skip comments, which may contain quotes
+            '/' if chars.as_str().starts_with('*') => {
+                let rest = chars.as_str();
+                let end = rest.find("*/").map_or(rest.len(), |i| i + 2);
+                chars = rest[end..].chars();
+            }
+            _ => {}
+        }
+    }
+    strings
+}
+
+/// Color of a color table entry such as `c #FF0000 m black`
+fn color(spec: &str) -> [u8; 4] {
+    let tokens: Vec<&str> = spec.split_whitespace().collect();
+    let value = tokens
+        .iter()
+        .position(|t| *t == "c")
+        .or_else(|| tokens.iter().position(|t| matches!(*t, "g" | "g4" | "m")))
+        .and_then(|i| tokens.get(i + 1..))
+        .map(|rest| {
+            // Color names may contain spaces, up to the next visual key
+            rest.iter()
+                .take_while(|t| !matches!(**t, "c" | "g" | "g4" | "m" | "s"))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+
+    if value.eq_ignore_ascii_case("none") {
+        return [0, 0, 0, 0];
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        return hex_color(hex).unwrap_or([0, 0, 0, 255]);
+    }
+    named_color(&value.to_lowercase().replace(' ', ""))
+}
+
+/// `#RGB`, `#RRGGBB` or `#RRRRGGGGBBBB`, keeping the high byte of each
+/// channel
+fn hex_color(hex: &str) -> Option<[u8; 4]> {
+    if !hex.len().is_multiple_of(3) || hex.is_empty() || !hex.is_ascii() {
+        return None;
+    }
+    let digits = hex.len() / 3;
+    let channel = |i: usize| -> Option<u8> {
+        let part = &hex[i * digits..(i + 1) * digits];
+        let value = u16::from_str_radix(part, 16).ok()?;
+        Some(match digits {
+            1 => (value * 17) as u8,
+            2 => value as u8,
+            _ => u16::from_str_radix(&part[..2], 16).ok()? as u8,
+        })
+    };
+    Some([channel(0)?, channel(1)?, channel(2)?, 255])
+}
+
+/// The X11 color names that icons commonly use
+fn named_color(name: &str) -> [u8; 4] {
+    let rgb = match name {
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" => [0, 255, 255],
+        "magenta" => [255, 0, 255],
+        "orange" => [255, 165, 0],
+        "gray" | "grey" => [190, 190, 190],
+        "darkgray" | "darkgrey" => [169, 169, 169],
+        "lightgray" | "lightgrey" => [211, 211, 211],
+        _ => {
+            // gray0 to gray100
+            let level = name
+                .strip_prefix("gray")
+                .or_else(|| name.strip_prefix("grey"))
+                .and_then(|l| l.parse::<u32>().ok())
+                .filter(|l| *l <= 100);
+            match level {
+                Some(level) => {
+                    let v = (level * 255 / 100) as u8;
+                    [v, v, v]
+                }
+                None => [0, 0, 0],
+            }
+        }
+    };
+    [rgb[0], rgb[1], rgb[2], 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let xpm = r##"/* XPM */
+static char * icon_xpm[] = {
+/* columns rows colors chars-per-pixel */
+"3 2 4 2",
+"   c None",
+".. c #F00 m black",
+"++ c gray50",
+"@@ s accent c #0000FFFF0000",
+"  ..++",
+"@@..  "};
+"##;
+        let image = decode(xpm).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(
+            image.rgba,
+            vec![
+                0, 0, 0, 0, 255, 0, 0, 255, 127, 127, 127, 255, //
+                0, 255, 0, 255, 255, 0, 0, 255, 0, 0, 0, 0,
+            ]
+        );
+
+        assert!(decode("\"2 2 1 1\", \". c red\", \"..\"").is_err());
+        assert!(decode("\"2 x 1 1\"").is_err());
+    }
+}
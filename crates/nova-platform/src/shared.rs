@@ -47,6 +47,12 @@ impl Notifications for NotifyRustNotifications {
             .map_err(|e| NovaError::Platform(e.to_string()))?;
         Ok(())
     }
+
+    fn countdown(&self, _title: &str, _seconds: u32) -> NovaResult<bool> {
+        Err(NovaError::Platform(
+            "Countdown notifications are not supported".to_string(),
+        ))
+    }
 }
 
 /// Cross-platform file/URL opening using the `open` crate
@@ -60,6 +66,77 @@ impl SystemOpen for OpenCrateOpener {
     fn open_file(&self, path: &str) -> NovaResult<()> {
         open::that(path).map_err(|e| NovaError::Platform(e.to_string()))
     }
+
+    fn reveal_file(&self, path: &str) -> NovaResult<()> {
+        #[cfg(target_os = "linux")]
+        {
+            // Ask the file manager to select the file; not every desktop has one
+            let uri = file_uri(path);
+            let shown = std::process::Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--dest=org.freedesktop.FileManager1",
+                    "--type=method_call",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                    &format!("array:string:{}", uri),
+                    "string:",
+                ])
+                .status()
+                .is_ok_and(|status| status.success());
+            if shown {
+                return Ok(());
+            }
+        }
+
+        let parent = std::path::Path::new(path)
+            .parent()
+            .ok_or_else(|| NovaError::Platform(format!("{} has no parent folder", path)))?;
+        open::that(parent).map_err(|e| NovaError::Platform(e.to_string()))
+    }
+
+    fn open_with(&self, path: &str) -> NovaResult<()> {
+        #[cfg(target_os = "linux")]
+        {
+            // The desktop portal shows the application chooser when asked to
+            let uri = file_uri(path);
+            std::process::Command::new("gdbus")
+                .args([
+                    "call",
+                    "--session",
+                    "--dest",
+                    "org.freedesktop.portal.Desktop",
+                    "--object-path",
+                    "/org/freedesktop/portal/desktop",
+                    "--method",
+                    "org.freedesktop.portal.OpenURI.OpenURI",
+                    "",
+                    &uri,
+                    "{'ask': <true>}",
+                ])
+                .spawn()
+                .map_err(|e| NovaError::Platform(format!("Failed to open chooser: {}", e)))?;
+            Ok(())
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        self.open_file(path)
+    }
+}
+
+/// Percent-encode an absolute path as a `file://` URI
+#[cfg(target_os = "linux")]
+fn file_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
 }
 
 /// Cross-platform autostart using auto-launch
@@ -101,3 +178,15 @@ impl AutostartManager for AutoLaunchAutostart {
         self.auto_launch.is_enabled().unwrap_or(false)
     }
 }
+
+/// Icon lookup for platforms without icon themes: only image paths resolve
+#[cfg(not(target_os = "linux"))]
+pub struct PathIcons;
+
+#[cfg(not(target_os = "linux"))]
+impl crate::traits::IconLookup for PathIcons {
+    fn find_icon(&self, name: &str, _size: u16, _scale: u16) -> Option<std::path::PathBuf> {
+        let path = std::path::Path::new(name);
+        (path.is_absolute() && path.is_file()).then(|| path.to_path_buf())
+    }
+}
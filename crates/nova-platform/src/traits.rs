@@ -1,13 +1,49 @@
-use nova_core::{NovaResult, PlatformAppEntry, SystemCommand};
+use std::path::PathBuf;
+
+use nova_core::{Config, NovaResult, OpenWindow, PlatformAppEntry, SystemCommand};
 
 /// Discover and launch applications
 pub trait AppDiscovery: Send + Sync {
     fn discover_apps(&self) -> Vec<PlatformAppEntry>;
+    /// Watch the application directories for installed, changed and
+    /// removed apps
+    fn watch(&self) -> NovaResult<Box<dyn AppWatcher>>;
     fn launch_app(&self, app: &PlatformAppEntry) -> NovaResult<()>;
+    /// Run one of the app's desktop actions, by its id
+    fn launch_action(&self, app: &PlatformAppEntry, action: &str) -> NovaResult<()>;
+    /// Run an exec line inside a terminal emulator
+    fn launch_in_terminal(&self, exec: &str, name: &str) -> NovaResult<()>;
+}
+
+/// A change to the set of installed applications
+#[derive(Debug, Clone)]
+pub enum AppChange {
+    /// An app was installed or its entry changed
+    Updated(PlatformAppEntry),
+    /// The app with this id was removed or is no longer shown
+    Removed(String),
+}
+
+/// Source of application index changes
+pub trait AppWatcher: Send {
+    /// Block until the next batch of changes. Bursts of file events, such as
+    /// a package manager transaction, arrive as one batch.
+    fn next_changes(&mut self) -> NovaResult<Vec<AppChange>>;
+}
+
+/// List and focus open windows
+pub trait WindowManager: Send + Sync {
+    /// Open windows, most recently used first when the window manager
+    /// tracks it
+    fn list_windows(&self) -> NovaResult<Vec<OpenWindow>>;
+    /// Focus a window by its id, switching to its workspace
+    fn focus_window(&self, id: &str) -> NovaResult<()>;
 }
 
 /// Execute system-level commands (lock, sleep, shutdown, etc.)
 pub trait SystemCommands: Send + Sync {
+    /// Commands the current session can run
+    fn supported(&self) -> Vec<SystemCommand>;
     fn execute(&self, command: SystemCommand) -> NovaResult<()>;
 }
 
@@ -20,12 +56,19 @@ pub trait ClipboardAccess: Send + Sync {
 /// Show desktop notifications
 pub trait Notifications: Send + Sync {
     fn show(&self, title: &str, body: &str) -> NovaResult<()>;
+    /// Count `seconds` down in a notification with a Cancel button.
+    /// Returns whether the countdown ran out without being cancelled.
+    fn countdown(&self, title: &str, seconds: u32) -> NovaResult<bool>;
 }
 
 /// Open URLs and files with the system default handler
 pub trait SystemOpen: Send + Sync {
     fn open_url(&self, url: &str) -> NovaResult<()>;
     fn open_file(&self, path: &str) -> NovaResult<()>;
+    /// Show a file selected in the file manager
+    fn reveal_file(&self, path: &str) -> NovaResult<()>;
+    /// Ask the user which application to open a file with
+    fn open_with(&self, path: &str) -> NovaResult<()>;
 }
 
 /// Manage application autostart
@@ -34,30 +77,43 @@ pub trait AutostartManager: Send + Sync {
     fn is_enabled(&self) -> bool;
 }
 
+/// Find image files for icon names
+pub trait IconLookup: Send + Sync {
+    /// Find the image for an icon name, or check an image path, at `size`
+    /// logical pixels and `scale`. Returns a PNG or SVG file.
+    fn find_icon(&self, name: &str, size: u16, scale: u16) -> Option<PathBuf>;
+
+    /// Persist what a batch of lookups found, for implementations that
+    /// cache between runs
+    fn flush(&self) {}
+}
+
 /// Aggregate struct holding all platform-specific implementations
 pub struct Platform {
     pub apps: Box<dyn AppDiscovery>,
+    pub windows: Box<dyn WindowManager>,
     pub system: Box<dyn SystemCommands>,
     pub clipboard: Box<dyn ClipboardAccess>,
     pub notifications: Box<dyn Notifications>,
     pub opener: Box<dyn SystemOpen>,
     pub autostart: Box<dyn AutostartManager>,
+    pub icons: Box<dyn IconLookup>,
 }
 
 impl Platform {
     /// Create a Platform instance with OS-appropriate implementations
-    pub fn current() -> Self {
+    pub fn current(config: &Config) -> Self {
         #[cfg(target_os = "linux")]
         {
-            crate::linux::create_platform()
+            crate::linux::create_platform(config)
         }
         #[cfg(target_os = "macos")]
         {
-            crate::macos::create_platform()
+            crate::macos::create_platform(config)
         }
         #[cfg(target_os = "windows")]
         {
-            crate::windows::create_platform()
+            crate::windows::create_platform(config)
         }
     }
 }
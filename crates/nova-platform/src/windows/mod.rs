@@ -1,7 +1,7 @@
-use nova_core::{NovaError, NovaResult, PlatformAppEntry, SystemCommand};
+use nova_core::{Config, NovaError, NovaResult, OpenWindow, PlatformAppEntry, SystemCommand};
 
-use crate::shared::{ArboardClipboard, AutoLaunchAutostart, NotifyRustNotifications, OpenCrateOpener};
-use crate::traits::{AppDiscovery, Platform, SystemCommands};
+use crate::shared::{ArboardClipboard, AutoLaunchAutostart, NotifyRustNotifications, OpenCrateOpener, PathIcons};
+use crate::traits::{AppDiscovery, AppWatcher, Platform, SystemCommands, WindowManager};
 
 struct WindowsAppDiscovery;
 
@@ -10,26 +10,56 @@ impl AppDiscovery for WindowsAppDiscovery {
         Vec::new() // TODO: Enumerate Start Menu .lnk files
     }
 
+    fn watch(&self) -> NovaResult<Box<dyn AppWatcher>> {
+        Err(NovaError::Platform("Windows app watching not yet implemented".to_string()))
+    }
+
     fn launch_app(&self, _app: &PlatformAppEntry) -> NovaResult<()> {
         Err(NovaError::Platform("Windows app launching not yet implemented".to_string()))
     }
+
+    fn launch_action(&self, _app: &PlatformAppEntry, _action: &str) -> NovaResult<()> {
+        Err(NovaError::Platform("Windows app actions not yet implemented".to_string()))
+    }
+
+    fn launch_in_terminal(&self, _exec: &str, _name: &str) -> NovaResult<()> {
+        Err(NovaError::Platform("Windows terminal launching not yet implemented".to_string()))
+    }
+}
+
+struct WindowsWindowManager;
+
+impl WindowManager for WindowsWindowManager {
+    fn list_windows(&self) -> NovaResult<Vec<OpenWindow>> {
+        Err(NovaError::Platform("Windows window listing not yet implemented".to_string()))
+    }
+
+    fn focus_window(&self, _id: &str) -> NovaResult<()> {
+        Err(NovaError::Platform("Windows window focusing not yet implemented".to_string()))
+    }
 }
 
 struct WindowsSystemCommands;
 
 impl SystemCommands for WindowsSystemCommands {
+    fn supported(&self) -> Vec<SystemCommand> {
+        Vec::new()
+    }
+
     fn execute(&self, _command: SystemCommand) -> NovaResult<()> {
         Err(NovaError::Platform("Windows system commands not yet implemented".to_string()))
     }
 }
 
-pub fn create_platform() -> Platform {
+pub fn create_platform(_config: &Config) -> Platform {
     Platform {
         apps: Box::new(WindowsAppDiscovery),
+        windows: Box::new(WindowsWindowManager),
         system: Box::new(WindowsSystemCommands),
         clipboard: Box::new(ArboardClipboard::new().expect("Failed to init clipboard")),
         notifications: Box::new(NotifyRustNotifications),
         opener: Box::new(OpenCrateOpener),
         autostart: Box::new(AutoLaunchAutostart::new().expect("Failed to init autostart")),
+        icons: Box::new(PathIcons),
     }
 }
@@ -1,32 +1,104 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use iced::widget::{column, container, scrollable, text, text_input, Column};
-use iced::{keyboard, Element, Length, Subscription, Task, Theme};
+use iced::{keyboard, task, Element, Length, Subscription, Task, Theme};
 
+use nova_core::services::{HistoryEntry, QueryHistory};
 use nova_core::{
-    CommandModeState, Config, ExecutionAction, PlatformAppEntry, SearchEngine, SearchResult,
+    CommandModeState, Config, Confirmation, ExecutionAction, NamedAction, NovaError, OpenWindow,
+    PlatformAppEntry, ResultIcon, ResultId, ScoredResult, SearchEngine, SearchPhase, SearchResult,
+    SystemCommand,
 };
+use nova_platform::traits::AppChange;
 use nova_platform::Platform;
 
 use crate::execute;
 use crate::style;
-use crate::widgets;
+use crate::widgets::{self, IconImage};
+
+/// Size of result icons, in logical pixels
+const ICON_SIZE: u16 = 24;
 
 /// The main application state
 pub struct Nova {
     config: Config,
-    search_engine: SearchEngine,
+    search_engine: Arc<SearchEngine>,
     platform: Arc<Platform>,
-    apps: Vec<PlatformAppEntry>,
+    apps: Arc<Vec<PlatformAppEntry>>,
+    /// Open windows, listed each time Nova is shown
+    windows: Arc<Vec<OpenWindow>>,
 
     // UI state
     query: String,
     results: Vec<SearchResult>,
+    /// Section titles and the index of the first result under each
+    sections: Vec<(usize, &'static str)>,
     selected_index: usize,
+
+    // Async search state
+    scored: Vec<ScoredResult>,
+    search_generation: u64,
+    results_generation: u64,
+    search_handle: Option<task::Handle>,
+    /// Set when the running search is superseded, so blocking providers
+    /// stop too; aborting its task doesn't stop those
+    search_cancelled: Arc<AtomicBool>,
+    pending_selection: Option<ResultId>,
+
+    /// Resolved icons by name; `None` when the theme has no such icon
+    icons: HashMap<String, Option<IconImage>>,
+    /// Icon names being resolved
+    icons_pending: HashSet<String>,
+
     is_visible: bool,
     command_mode: CommandModeState,
+    action_panel: Option<ActionPanel>,
+    modifiers: keyboard::Modifiers,
     settings_open: bool,
     clipboard_history: nova_core::services::clipboard::ClipboardHistory,
+
+    // Query history recall
+    query_history: QueryHistory,
+    /// Entry shown while stepping through history with Up/Down
+    history_cursor: Option<usize>,
+    history_panel: Option<HistoryPanel>,
+}
+
+/// Secondary actions of a result, listed in place of the results
+struct ActionPanel {
+    title: String,
+    actions: Vec<NamedAction>,
+    selected_index: usize,
+    /// Set when the panel asks to confirm an action, which then runs as is
+    confirming: bool,
+    /// Launch waiting for confirmation, recorded once its action runs
+    launch: Option<Launch>,
+}
+
+/// A result picked from a query, recorded in query history and usage once
+/// its action runs
+#[derive(Debug, Clone)]
+pub struct Launch {
+    query: String,
+    /// Keyword of the command mode the query was typed in
+    keyword: Option<String>,
+    result: SearchResult,
+}
+
+/// An action waiting for confirmation, with the launch it came from
+#[derive(Debug, Clone)]
+pub struct PendingAction {
+    action: NamedAction,
+    launch: Option<Launch>,
+}
+
+/// Previous queries matching the typed text, listed in place of the results
+struct HistoryPanel {
+    entries: Vec<HistoryEntry>,
+    selected_index: usize,
 }
 
 /// Messages that drive the application
@@ -34,15 +106,33 @@ pub struct Nova {
 pub enum Message {
     QueryChanged(String),
     KeyPressed(keyboard::Key, keyboard::Modifiers),
+    ModifiersChanged(keyboard::Modifiers),
     ExecuteSelected,
     SelectIndex(usize),
+    RunAction(usize),
+    RecallHistory(usize),
     Hide,
     Show,
     Toggle,
     ClipboardChanged(String),
+    /// Apps were installed, changed or removed
+    AppsChanged(Vec<AppChange>),
+    /// Open windows were listed
+    WindowsListed(Vec<OpenWindow>),
+    /// The system commands the session supports were found
+    SystemCommandsListed(Vec<SystemCommand>),
+    /// The countdown before a command ended: it ran out, was cancelled, or
+    /// could not be shown
+    CountdownEnded(Box<PendingAction>, Result<bool, String>),
+    /// Launching the named app finished
+    Launched(String, Result<(), String>),
     IpcReceived(String),
     SettingsToggle,
-    SettingsSaved(Config),
+    SettingsSaved(Box<Config>),
+    /// A batch of results for the search with the given generation
+    SearchFinished(u64, Vec<ScoredResult>),
+    /// Image files found for icon names
+    IconsResolved(Vec<(String, Option<PathBuf>)>),
     Noop,
 }
 
@@ -52,7 +142,7 @@ impl Nova {
         platform: Platform,
         apps: Vec<PlatformAppEntry>,
     ) -> (Self, Task<Message>) {
-        let search_engine = SearchEngine::new(&config);
+        let search_engine = Arc::new(SearchEngine::new(&config));
         let max_results = config.behavior.max_results as usize;
         let clipboard_history =
             nova_core::services::clipboard::ClipboardHistory::new(50);
@@ -61,52 +151,131 @@ impl Nova {
             config,
             search_engine,
             platform: Arc::new(platform),
-            apps,
+            apps: Arc::new(apps),
+            windows: Arc::new(Vec::new()),
             query: String::new(),
             results: Vec::new(),
+            sections: Vec::new(),
             selected_index: 0,
+            scored: Vec::new(),
+            search_generation: 0,
+            results_generation: 0,
+            search_handle: None,
+            search_cancelled: Arc::new(AtomicBool::new(false)),
+            pending_selection: None,
+            icons: HashMap::new(),
+            icons_pending: HashSet::new(),
             is_visible: true,
             command_mode: CommandModeState::default(),
+            action_panel: None,
+            modifiers: keyboard::Modifiers::default(),
             settings_open: false,
             clipboard_history,
+            query_history: QueryHistory::load(),
+            history_cursor: None,
+            history_panel: None,
         };
 
         // Initial search with empty query to show default results
-        nova.perform_search(max_results);
+        let search = nova.perform_search(max_results);
+        let windows = nova.list_windows();
+        let system_commands = nova.list_system_commands();
 
-        (nova, text_input::focus(text_input::Id::new("search_input")))
+        (
+            nova,
+            Task::batch([
+                search,
+                windows,
+                system_commands,
+                text_input::focus(text_input::Id::new("search_input")),
+            ]),
+        )
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::QueryChanged(query) => {
                 self.query = query;
+                self.history_cursor = None;
+                if let Some(ref mut panel) = self.history_panel {
+                    // While the history panel is open, typing filters it
+                    panel.entries = self
+                        .query_history
+                        .search(&self.query)
+                        .into_iter()
+                        .cloned()
+                        .collect();
+                    panel.selected_index = 0;
+                    return Task::none();
+                }
                 self.selected_index = 0;
-                self.perform_search(self.config.behavior.max_results as usize);
-                Task::none()
+                self.action_panel = None;
+                self.perform_search(self.config.behavior.max_results as usize)
             }
-            Message::KeyPressed(key, _modifiers) => match key {
+            Message::KeyPressed(key, modifiers) => match key {
+                keyboard::Key::Character(ref c) if modifiers.control() && c.as_str() == "p" => {
+                    self.toggle_favorite()
+                }
+                keyboard::Key::Character(ref c) if modifiers.control() && c.as_str() == "k" => {
+                    self.toggle_action_panel();
+                    Task::none()
+                }
+                keyboard::Key::Character(ref c) if modifiers.control() && c.as_str() == "r" => {
+                    self.toggle_history_panel();
+                    Task::none()
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) if modifiers.control() => {
+                    self.move_favorite(1)
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) if modifiers.control() => {
+                    self.move_favorite(-1)
+                }
                 keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
-                    if !self.results.is_empty() {
+                    if let Some(ref mut panel) = self.action_panel {
+                        panel.selected_index = (panel.selected_index + 1)
+                            .min(panel.actions.len().saturating_sub(1));
+                    } else if let Some(ref mut panel) = self.history_panel {
+                        panel.selected_index =
+                            (panel.selected_index + 1).min(panel.entries.len().saturating_sub(1));
+                    } else if self.history_cursor.is_some() {
+                        return self.step_history(-1);
+                    } else if !self.results.is_empty() {
                         self.selected_index =
                             (self.selected_index + 1).min(self.results.len() - 1);
                     }
                     Task::none()
                 }
                 keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
-                    self.selected_index = self.selected_index.saturating_sub(1);
+                    if let Some(ref mut panel) = self.action_panel {
+                        panel.selected_index = panel.selected_index.saturating_sub(1);
+                    } else if let Some(ref mut panel) = self.history_panel {
+                        panel.selected_index = panel.selected_index.saturating_sub(1);
+                    } else if self.history_cursor.is_some()
+                        || (self.query.is_empty()
+                            && self.selected_index == 0
+                            && !self.command_mode.is_active())
+                    {
+                        return self.step_history(1);
+                    } else {
+                        self.selected_index = self.selected_index.saturating_sub(1);
+                    }
                     Task::none()
                 }
                 keyboard::Key::Named(keyboard::key::Named::Escape) => {
                     if self.settings_open {
                         self.settings_open = false;
                         Task::none()
+                    } else if self.action_panel.is_some() {
+                        self.action_panel = None;
+                        Task::none()
+                    } else if self.history_panel.is_some() {
+                        self.history_panel = None;
+                        Task::none()
                     } else if self.command_mode.is_active() {
                         self.command_mode.exit_mode();
                         self.query.clear();
                         self.selected_index = 0;
-                        self.perform_search(self.config.behavior.max_results as usize);
-                        Task::none()
+                        self.perform_search(self.config.behavior.max_results as usize)
                     } else {
                         self.hide()
                     }
@@ -115,23 +284,40 @@ impl Nova {
                     self.execute_selected()
                 }
                 keyboard::Key::Named(keyboard::key::Named::Tab) => {
-                    self.try_enter_command_mode();
-                    Task::none()
+                    self.try_enter_command_mode()
                 }
                 keyboard::Key::Named(keyboard::key::Named::Backspace) => {
                     if self.query.is_empty() && self.command_mode.is_active() {
                         self.command_mode.exit_mode();
-                        self.perform_search(self.config.behavior.max_results as usize);
+                        self.perform_search(self.config.behavior.max_results as usize)
+                    } else {
+                        Task::none()
                     }
-                    Task::none()
                 }
                 _ => Task::none(),
             },
-            Message::ExecuteSelected => self.execute_selected(),
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+            Message::ExecuteSelected => {
+                if let Some(index) = self.action_panel.as_ref().map(|p| p.selected_index) {
+                    self.run_action(index)
+                } else if let Some(index) = self.history_panel.as_ref().map(|p| p.selected_index) {
+                    self.recall_history(index)
+                } else if self.modifiers.shift() {
+                    self.toggle_action_panel();
+                    Task::none()
+                } else {
+                    self.execute_selected()
+                }
+            }
             Message::SelectIndex(index) => {
                 self.selected_index = index;
                 self.execute_selected()
             }
+            Message::RunAction(index) => self.run_action(index),
+            Message::RecallHistory(index) => self.recall_history(index),
             Message::Hide => self.hide(),
             Message::Show => self.show(),
             Message::Toggle => {
@@ -145,6 +331,48 @@ impl Nova {
                 self.clipboard_history.check_and_add(content);
                 Task::none()
             }
+            Message::AppsChanged(changes) => {
+                self.apply_app_changes(changes);
+                if self.is_visible {
+                    self.perform_search(self.config.behavior.max_results as usize)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::WindowsListed(windows) => {
+                self.windows = Arc::new(windows);
+                if self.is_visible {
+                    self.perform_search(self.config.behavior.max_results as usize)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::SystemCommandsListed(commands) => {
+                self.search_engine.set_system_commands(commands);
+                if self.is_visible {
+                    self.perform_search(self.config.behavior.max_results as usize)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::CountdownEnded(pending, outcome) => match outcome {
+                Ok(true) => self.run_confirmed(*pending),
+                Ok(false) => Task::none(),
+                Err(e) => {
+                    eprintln!("[Nova] Failed to count down: {}", e);
+                    // Ask in the launcher instead
+                    let show = self.show();
+                    self.confirm(*pending);
+                    show
+                }
+            },
+            Message::Launched(name, launched) => match launched {
+                Ok(()) => self.hide(),
+                Err(e) => {
+                    eprintln!("[Nova] Failed to launch {}: {}", name, e);
+                    Task::none()
+                }
+            },
             Message::IpcReceived(msg) => {
                 if msg.trim() == "toggle" {
                     if self.is_visible {
@@ -161,14 +389,30 @@ impl Nova {
                 Task::none()
             }
             Message::SettingsSaved(config) => {
-                self.config = config.clone();
-                self.search_engine = SearchEngine::new(&config);
+                self.config = (*config).clone();
+                // The launch history stays with its one writer, and the
+                // supported commands are only listed at startup
+                let search_engine = SearchEngine::with_usage(&config, self.search_engine.usage());
+                search_engine.set_system_commands(self.search_engine.system_commands());
+                self.search_engine = Arc::new(search_engine);
                 if let Err(e) = config.save() {
                     eprintln!("[Nova] Failed to save config: {}", e);
                 }
                 self.settings_open = false;
                 Task::none()
             }
+            Message::SearchFinished(generation, results) => {
+                self.apply_search_results(generation, results);
+                self.load_icons()
+            }
+            Message::IconsResolved(icons) => {
+                for (name, path) in icons {
+                    self.icons_pending.remove(&name);
+                    self.icons.insert(name, path.map(IconImage::from_path));
+                }
+                // Results whose first icon was missing still need a fallback
+                self.load_icons()
+            }
             Message::Noop => Task::none(),
         }
     }
@@ -214,19 +458,57 @@ impl Nova {
             Element::from(input)
         };
 
-        // Results list
-        let results_column: Column<Message> = self
-            .results
-            .iter()
-            .enumerate()
-            .fold(Column::new().spacing(0), |col, (i, result)| {
-                col.push(widgets::result_row(
-                    result,
-                    i == self.selected_index,
-                    &theme_palette,
-                    i,
-                ))
-            });
+        // Results list, or the actions of the selected result, or past queries
+        let results_column: Column<Message> = if let Some(ref panel) = self.history_panel {
+            panel.entries.iter().enumerate().fold(
+                Column::new()
+                    .spacing(0)
+                    .push(widgets::section_header("History", &theme_palette)),
+                |col, (i, entry)| {
+                    col.push(widgets::history_row(
+                        entry,
+                        i == panel.selected_index,
+                        &theme_palette,
+                        i,
+                    ))
+                },
+            )
+        } else if let Some(ref panel) = self.action_panel {
+            panel.actions.iter().enumerate().fold(
+                Column::new()
+                    .spacing(0)
+                    .push(widgets::section_header(&panel.title, &theme_palette)),
+                |col, (i, action)| {
+                    col.push(widgets::action_row(
+                        action,
+                        i == panel.selected_index,
+                        &theme_palette,
+                        i,
+                    ))
+                },
+            )
+        } else {
+            self.results
+                .iter()
+                .enumerate()
+                .fold(Column::new().spacing(0), |col, (i, result)| {
+                    // Headers sit between rows; selection indexes results only
+                    let col = match self.sections.iter().find(|(start, _)| *start == i) {
+                        Some((_, title)) => {
+                            col.push(widgets::section_header(title, &theme_palette))
+                        }
+                        None => col,
+                    };
+                    col.push(widgets::result_row(
+                        result,
+                        i == self.selected_index,
+                        self.search_engine.is_favorite(result),
+                        &self.icons,
+                        &theme_palette,
+                        i,
+                    ))
+                })
+        };
 
         let results_scrollable = scrollable(results_column)
             .height(Length::Fill);
@@ -270,30 +552,238 @@ impl Nova {
             }
         });
 
+        // Ctrl shortcuts are captured by the search input, so listen to all events
+        let shortcut_sub = iced::event::listen_with(|event, _status, _window| match event {
+            iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if modifiers.control() && matches!(key, keyboard::Key::Character(_)) =>
+            {
+                Some(Message::KeyPressed(key, modifiers))
+            }
+            // Tracked so Shift+Enter can open the action panel from the input
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            _ => None,
+        });
+
         let ipc_sub = crate::subscriptions::ipc_listener();
         let clipboard_sub = crate::subscriptions::clipboard_poll(self.platform.clone());
+        let apps_sub = crate::subscriptions::app_changes(self.platform.clone());
 
-        Subscription::batch([keyboard_sub, ipc_sub, clipboard_sub])
+        Subscription::batch([keyboard_sub, shortcut_sub, ipc_sub, clipboard_sub, apps_sub])
     }
 
     pub fn theme(&self) -> Theme {
         style::theme::nova_theme(&self.config)
     }
 
-    fn perform_search(&mut self, max_results: usize) {
+    /// Update the app index in place, keeping it sorted by name
+    fn apply_app_changes(&mut self, changes: Vec<AppChange>) {
+        let apps = Arc::make_mut(&mut self.apps);
+        for change in changes {
+            match change {
+                AppChange::Updated(app) => {
+                    apps.retain(|a| a.id != app.id);
+                    let name = app.name.to_lowercase();
+                    let pos = apps.partition_point(|a| a.name.to_lowercase() < name);
+                    apps.insert(pos, app);
+                }
+                AppChange::Removed(id) => apps.retain(|a| a.id != id),
+            }
+        }
+    }
+
+    /// Start a search for the current query.
+    ///
+    /// Fast providers and slow ones run as separate tasks so quick results
+    /// show first. Each search gets a new generation; results from earlier
+    /// generations are dropped and their tasks aborted.
+    fn perform_search(&mut self, max_results: usize) -> Task<Message> {
+        self.search_generation += 1;
+        let generation = self.search_generation;
+
+        self.cancel_search();
         if self.command_mode.is_active() {
+            self.scored.clear();
+            self.sections.clear();
+            self.results_generation = generation;
             self.results = self.search_engine.search_in_command_mode(
                 &self.command_mode,
                 &self.query,
                 max_results,
             );
+            return self.load_icons();
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.search_cancelled = Arc::clone(&cancelled);
+        let phases = [SearchPhase::Fast, SearchPhase::Slow].map(|phase| {
+            let engine = Arc::clone(&self.search_engine);
+            let cancelled = Arc::clone(&cancelled);
+            let apps = Arc::clone(&self.apps);
+            let windows = Arc::clone(&self.windows);
+            let clipboard = self.clipboard_history.clone();
+            let query = self.query.clone();
+
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        engine.search_phase(
+                            &apps,
+                            &windows,
+                            &clipboard,
+                            &query,
+                            max_results,
+                            phase,
+                            &cancelled,
+                        )
+                    })
+                    .await
+                    .unwrap_or_default()
+                },
+                move |results| Message::SearchFinished(generation, results),
+            )
+        });
+
+        let (task, handle) = Task::batch(phases).abortable();
+        self.search_handle = Some(handle.abort_on_drop());
+        task
+    }
+
+    /// Stop the running search, if any
+    fn cancel_search(&mut self) {
+        // Dropping the handle aborts the search's task
+        self.search_handle = None;
+        self.search_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// List open windows in the background; they are searched once listed
+    fn list_windows(&self) -> Task<Message> {
+        let platform = Arc::clone(&self.platform);
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || platform.windows.list_windows())
+                    .await
+                    .unwrap_or_else(|e| Err(NovaError::Platform(e.to_string())))
+            },
+            |listed| match listed {
+                Ok(windows) => Message::WindowsListed(windows),
+                Err(e) => {
+                    eprintln!("[Nova] Failed to list windows: {}", e);
+                    Message::Noop
+                }
+            },
+        )
+    }
+
+    /// Find the system commands the session supports, in the background
+    /// since checking them takes a few D-Bus calls
+    fn list_system_commands(&self) -> Task<Message> {
+        let platform = Arc::clone(&self.platform);
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || platform.system.supported())
+                    .await
+                    .unwrap_or_default()
+            },
+            Message::SystemCommandsListed,
+        )
+    }
+
+    /// Look up the icons of the shown results that aren't known yet.
+    ///
+    /// Each result names its icon followed by fallbacks; names are tried in
+    /// order until one is found, in the background since lookups hit the
+    /// disk.
+    fn load_icons(&mut self) -> Task<Message> {
+        let mut chains: Vec<Vec<String>> = Vec::new();
+        for result in &self.results {
+            let ResultIcon::Themed(names) = result.icon() else {
+                continue;
+            };
+            // Skip names known to be missing; stop at a found or pending one
+            let Some(first) = names
+                .iter()
+                .position(|name| !matches!(self.icons.get(name), Some(None)))
+            else {
+                continue;
+            };
+            let name = &names[first];
+            if self.icons.contains_key(name) || self.icons_pending.contains(name) {
+                continue;
+            }
+            self.icons_pending.insert(name.clone());
+            chains.push(names[first..].to_vec());
+        }
+
+        if chains.is_empty() {
+            return Task::none();
+        }
+
+        let platform = Arc::clone(&self.platform);
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut resolved = Vec::new();
+                    for chain in chains {
+                        for name in chain {
+                            let path = platform.icons.find_icon(&name, ICON_SIZE, 1);
+                            let found = path.is_some();
+                            resolved.push((name, path));
+                            if found {
+                                break;
+                            }
+                        }
+                    }
+                    platform.icons.flush();
+                    resolved
+                })
+                .await
+                .unwrap_or_default()
+            },
+            Message::IconsResolved,
+        )
+    }
+
+    /// Merge a batch of search results into the list, ignoring stale ones
+    fn apply_search_results(&mut self, generation: u64, results: Vec<ScoredResult>) {
+        if generation != self.search_generation {
+            return;
+        }
+
+        // The first batch of a search replaces the old list; later batches
+        // merge in without moving the selection off its result
+        let selected = if self.results_generation != generation {
+            self.results_generation = generation;
+            self.scored.clear();
+            self.pending_selection.take()
         } else {
-            self.results = self.search_engine.search(
-                &self.apps,
-                &self.clipboard_history,
-                &self.query,
-                max_results,
-            );
+            self.results.get(self.selected_index).map(|r| r.id())
+        };
+
+        self.scored.extend(results);
+        self.scored = nova_core::merge_results(
+            std::mem::take(&mut self.scored),
+            self.config.behavior.max_results as usize,
+        );
+
+        let sections = self.search_engine.group_results(self.scored.clone());
+        self.sections.clear();
+        self.results.clear();
+        for section in sections {
+            self.sections
+                .push((self.results.len(), section.group.title()));
+            self.results
+                .extend(section.results.into_iter().map(|s| s.result));
+        }
+
+        match selected {
+            Some(id) => self.select_id(&id),
+            None => {
+                self.selected_index = self
+                    .selected_index
+                    .min(self.results.len().saturating_sub(1));
+            }
         }
     }
 
@@ -307,16 +797,270 @@ impl Nova {
 
         // Check if this should enter command mode
         if matches!(action, ExecutionAction::NeedsInput) {
-            self.try_enter_command_mode();
+            return self.try_enter_command_mode();
+        }
+
+        let launch = Launch {
+            query: self.query.clone(),
+            keyword: self
+                .command_mode
+                .active_extension
+                .as_ref()
+                .map(|ext| ext.keyword.clone()),
+            result: result.clone(),
+        };
+        let name = result.name().to_string();
+        self.dispatch(NamedAction::new(name, action), Some(launch))
+    }
+
+    /// Run an action once its confirmation policy allows. Every action runs
+    /// through here, so commands like Shut Down are confirmed whether they
+    /// were picked from the results, the action panel or the favourites.
+    fn dispatch(&mut self, action: NamedAction, launch: Option<Launch>) -> Task<Message> {
+        let policy = match &launch {
+            Some(launch) => launch.result.confirmation(&self.config.confirmation),
+            None => action.action.confirmation(&self.config.confirmation),
+        };
+        let pending = PendingAction { action, launch };
+        match policy {
+            Confirmation::None => self.run_confirmed(pending),
+            Confirmation::Confirm => {
+                self.confirm(pending);
+                Task::none()
+            }
+            Confirmation::Countdown => {
+                let countdown = self.count_down(pending);
+                Task::batch([self.hide(), countdown])
+            }
+        }
+    }
+
+    /// Run an action that needs no more confirmation, recording its launch
+    fn run_confirmed(&mut self, pending: PendingAction) -> Task<Message> {
+        if let Some(launch) = &pending.launch {
+            self.record_launch(launch);
+        }
+        execute::run_action(pending.action.action, &self.platform, &self.config)
+    }
+
+    /// Record a launch in query history and usage
+    fn record_launch(&mut self, launch: &Launch) {
+        self.query_history
+            .record(&launch.query, launch.keyword.as_deref());
+
+        // In command mode the query is the argument; attribute the launch to the keyword
+        let usage_query = launch.keyword.as_deref().unwrap_or(&launch.query);
+        self.search_engine.record_usage(usage_query, &launch.result);
+    }
+
+    /// Ask before running an action, in the action panel: Enter runs it,
+    /// Escape cancels
+    fn confirm(&mut self, pending: PendingAction) {
+        self.action_panel = Some(ActionPanel {
+            title: format!("{}? Press Enter to confirm", pending.action.title),
+            actions: vec![pending.action],
+            selected_index: 0,
+            confirming: true,
+            launch: pending.launch,
+        });
+    }
+
+    /// Run an action once a countdown notification runs out, unless the
+    /// user cancels it there
+    fn count_down(&self, pending: PendingAction) -> Task<Message> {
+        let platform = Arc::clone(&self.platform);
+        let seconds = self.config.confirmation.countdown_seconds;
+        let title = pending.action.title.clone();
+        Task::perform(
+            async move {
+                let outcome = tokio::task::spawn_blocking(move || {
+                    platform.notifications.countdown(&title, seconds)
+                })
+                .await
+                .unwrap_or_else(|e| Err(NovaError::Platform(e.to_string())))
+                .map_err(|e| e.to_string());
+                (Box::new(pending), outcome)
+            },
+            |(pending, outcome)| Message::CountdownEnded(pending, outcome),
+        )
+    }
+
+    /// Open the action panel for the selected result, or close it
+    fn toggle_action_panel(&mut self) {
+        if self.action_panel.take().is_some() {
+            return;
+        }
+        let Some(result) = self.results.get(self.selected_index) else {
+            return;
+        };
+
+        let actions = result.actions();
+        if !actions.is_empty() {
+            self.action_panel = Some(ActionPanel {
+                title: format!("Actions for {}", result.name()),
+                actions,
+                selected_index: 0,
+                confirming: false,
+                launch: None,
+            });
+        }
+    }
+
+    /// Run an action from the action panel and close it
+    fn run_action(&mut self, index: usize) -> Task<Message> {
+        let Some(panel) = self.action_panel.take() else {
             return Task::none();
+        };
+        match panel.actions.into_iter().nth(index) {
+            Some(named) if panel.confirming => self.run_confirmed(PendingAction {
+                action: named,
+                launch: panel.launch,
+            }),
+            Some(named) => self.dispatch(named, panel.launch),
+            None => Task::none(),
         }
+    }
 
-        execute::run_action(action, &self.platform, &self.config)
+    /// Open the history panel filtered by the current query, or close it
+    fn toggle_history_panel(&mut self) {
+        if self.history_panel.take().is_some() {
+            return;
+        }
+        self.action_panel = None;
+        self.history_panel = Some(HistoryPanel {
+            entries: self
+                .query_history
+                .search(&self.query)
+                .into_iter()
+                .cloned()
+                .collect(),
+            selected_index: 0,
+        });
+    }
+
+    /// Restore an entry from the history panel and close it
+    fn recall_history(&mut self, index: usize) -> Task<Message> {
+        let Some(panel) = self.history_panel.take() else {
+            return Task::none();
+        };
+        match panel.entries.into_iter().nth(index) {
+            Some(entry) => {
+                self.history_cursor = None;
+                self.restore_query(entry)
+            }
+            None => Task::none(),
+        }
     }
 
-    fn try_enter_command_mode(&mut self) {
+    /// Step through history: positive offsets go to older queries. Stepping
+    /// past the most recent one returns to an empty query.
+    fn step_history(&mut self, offset: isize) -> Task<Message> {
+        let next = self
+            .history_cursor
+            .map_or(0, |cursor| cursor as isize + offset);
+
+        if next < 0 {
+            self.history_cursor = None;
+            self.command_mode.exit_mode();
+            self.query.clear();
+            self.selected_index = 0;
+            return self.perform_search(self.config.behavior.max_results as usize);
+        }
+
+        match self.query_history.get(next as usize).cloned() {
+            Some(entry) => {
+                self.history_cursor = Some(next as usize);
+                self.restore_query(entry)
+            }
+            // Already at the oldest entry
+            None => Task::none(),
+        }
+    }
+
+    /// Put a previous query back in the search field, along with the
+    /// command mode it was typed in
+    fn restore_query(&mut self, entry: HistoryEntry) -> Task<Message> {
+        let ext = entry
+            .extension
+            .as_deref()
+            .and_then(|keyword| self.search_engine.extension_index.get_by_keyword(keyword))
+            .cloned();
+        match ext {
+            Some(ext) => self.command_mode.enter_mode(ext),
+            None => self.command_mode.exit_mode(),
+        }
+
+        self.query = entry.query;
+        self.selected_index = 0;
+        self.action_panel = None;
+        let search = self.perform_search(self.config.behavior.max_results as usize);
+        Task::batch([
+            search,
+            text_input::move_cursor_to_end(text_input::Id::new("search_input")),
+        ])
+    }
+
+    /// Pin or unpin the selected result
+    fn toggle_favorite(&mut self) -> Task<Message> {
+        if self.command_mode.is_active() {
+            return Task::none();
+        }
+        let Some(result) = self.results.get(self.selected_index) else {
+            return Task::none();
+        };
+
+        let id = result.id();
+        if !self.config.is_favorite(&id.to_string())
+            && self.search_engine.resolve(&id, &self.apps).is_none()
+        {
+            // Clipboard entries and conversions can't be rebuilt later
+            return Task::none();
+        }
+
+        self.config.toggle_favorite(&id.to_string());
+        self.save_favorites();
+        self.pending_selection = Some(id);
+        self.perform_search(self.config.behavior.max_results as usize)
+    }
+
+    /// Move the selected pinned result within the empty-query view
+    fn move_favorite(&mut self, offset: isize) -> Task<Message> {
+        if !self.query.is_empty() || self.command_mode.is_active() {
+            return Task::none();
+        }
+        let Some(result) = self.results.get(self.selected_index) else {
+            return Task::none();
+        };
+
+        let id = result.id();
+        if self.config.move_favorite(&id.to_string(), offset).is_none() {
+            return Task::none();
+        }
+        self.save_favorites();
+        self.pending_selection = Some(id);
+        self.perform_search(self.config.behavior.max_results as usize)
+    }
+
+    fn save_favorites(&mut self) {
+        self.search_engine.set_favorites(&self.config.favorites);
+        if let Err(e) = self.config.save() {
+            eprintln!("[Nova] Failed to save config: {}", e);
+        }
+    }
+
+    fn select_id(&mut self, id: &ResultId) {
+        if let Some(index) = self.results.iter().position(|r| &r.id() == id) {
+            self.selected_index = index;
+        } else {
+            self.selected_index = self
+                .selected_index
+                .min(self.results.len().saturating_sub(1));
+        }
+    }
+
+    fn try_enter_command_mode(&mut self) -> Task<Message> {
         if self.results.is_empty() || self.command_mode.is_active() {
-            return;
+            return Task::none();
         }
 
         let result = &self.results[self.selected_index];
@@ -340,9 +1084,10 @@ impl Nova {
                 self.command_mode.enter_mode(ext.clone());
                 self.query.clear();
                 self.selected_index = 0;
-                self.perform_search(self.config.behavior.max_results as usize);
+                return self.perform_search(self.config.behavior.max_results as usize);
             }
         }
+        Task::none()
     }
 
     fn hide(&mut self) -> Task<Message> {
@@ -350,6 +1095,10 @@ impl Nova {
         self.query.clear();
         self.selected_index = 0;
         self.command_mode.exit_mode();
+        self.action_panel = None;
+        self.history_panel = None;
+        self.history_cursor = None;
+        self.cancel_search();
         // Iced 0.13 has no set_visible; minimize as a proxy for hiding
         iced::window::get_oldest()
             .and_then(|id| iced::window::minimize(id, true))
@@ -357,8 +1106,10 @@ impl Nova {
 
     fn show(&mut self) -> Task<Message> {
         self.is_visible = true;
-        self.perform_search(self.config.behavior.max_results as usize);
+        let search = self.perform_search(self.config.behavior.max_results as usize);
         Task::batch([
+            search,
+            self.list_windows(),
             iced::window::get_oldest()
                 .and_then(|id| {
                     Task::batch([
@@ -15,23 +15,47 @@ pub fn run_action(
     _config: &Config,
 ) -> Task<Message> {
     match action {
-        ExecutionAction::LaunchApp { exec, name } => {
+        ExecutionAction::LaunchApp {
+            app_id,
+            exec,
+            name,
+            desktop_file,
+            terminal,
+            action,
+        } => {
             let platform = Arc::clone(platform);
             let app = nova_core::PlatformAppEntry {
-                id: String::new(),
+                id: app_id,
                 name: name.clone(),
                 exec,
                 icon: None,
                 description: None,
                 keywords: Vec::new(),
+                desktop_file,
+                terminal,
+                actions: Vec::new(),
             };
-            match platform.apps.launch_app(&app) {
-                Ok(()) => hide_task(),
-                Err(e) => {
-                    eprintln!("[Nova] Failed to launch {}: {}", name, e);
-                    Task::none()
-                }
+            // Launching waits on the display server for a startup token
+            Task::perform(
+                async move {
+                    tokio::task::spawn_blocking(move || match action {
+                        Some(action) => platform.apps.launch_action(&app, &action),
+                        None => platform.apps.launch_app(&app),
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|launched| launched.map_err(|e| e.to_string()))
+                },
+                move |launched| Message::Launched(name.clone(), launched),
+            )
+        }
+
+        ExecutionAction::FocusWindow { id } => {
+            let platform = Arc::clone(platform);
+            if let Err(e) = platform.windows.focus_window(&id) {
+                eprintln!("[Nova] Failed to focus window: {}", e);
             }
+            hide_task()
         }
 
         ExecutionAction::OpenSettings => Task::done(Message::SettingsToggle),
@@ -120,6 +144,33 @@ pub fn run_action(
             hide_task()
         }
 
+        ExecutionAction::RevealFile { path } => {
+            let platform = Arc::clone(platform);
+            if let Err(e) = platform.opener.reveal_file(&path) {
+                eprintln!("[Nova] Failed to reveal file: {}", e);
+            }
+            hide_task()
+        }
+
+        ExecutionAction::OpenWith { path } => {
+            let platform = Arc::clone(platform);
+            if let Err(e) = platform.opener.open_with(&path) {
+                eprintln!("[Nova] Failed to open file: {}", e);
+            }
+            hide_task()
+        }
+
+        ExecutionAction::RunInTerminal { command, name } => {
+            let platform = Arc::clone(platform);
+            match platform.apps.launch_in_terminal(&command, &name) {
+                Ok(()) => hide_task(),
+                Err(e) => {
+                    eprintln!("[Nova] Failed to open {} in terminal: {}", name, e);
+                    Task::none()
+                }
+            }
+        }
+
         ExecutionAction::NeedsInput => Task::none(),
     }
 }
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use iced::Subscription;
 
+use nova_platform::traits::AppWatcher;
 use nova_platform::Platform;
 
 use crate::app::Message;
@@ -84,3 +85,47 @@ fn clipboard_stream(platform: Arc<Platform>) -> impl futures_lite::Stream<Item =
         },
     )
 }
+
+/// Application index refresh subscription
+pub fn app_changes(platform: Arc<Platform>) -> Subscription<Message> {
+    Subscription::run_with_id("app_changes", app_change_stream(platform))
+}
+
+fn app_change_stream(platform: Arc<Platform>) -> impl futures_lite::Stream<Item = Message> {
+    futures_lite::stream::unfold(
+        (platform, None::<Box<dyn AppWatcher>>),
+        |(platform, watcher)| async move {
+            let mut watcher = match watcher {
+                Some(watcher) => watcher,
+                None => match platform.apps.watch() {
+                    Ok(watcher) => watcher,
+                    Err(e) => {
+                        eprintln!("[Nova] Not watching applications: {}", e);
+                        return None;
+                    }
+                },
+            };
+
+            // The watcher blocks until something changes
+            let waited = tokio::task::spawn_blocking(move || {
+                let changes = watcher.next_changes();
+                (watcher, changes)
+            })
+            .await;
+
+            match waited {
+                Ok((watcher, Ok(changes))) => {
+                    Some((Message::AppsChanged(changes), (platform, Some(watcher))))
+                }
+                Ok((_, Err(e))) => {
+                    eprintln!("[Nova] Application watcher stopped: {}", e);
+                    None
+                }
+                Err(e) => {
+                    eprintln!("[Nova] Application watcher stopped: {}", e);
+                    None
+                }
+            }
+        },
+    )
+}
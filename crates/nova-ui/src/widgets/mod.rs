@@ -1,14 +1,85 @@
-use iced::widget::{column, container, mouse_area, text};
-use iced::{Element, Theme};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-use nova_core::{SearchResult, ThemePalette};
+use iced::widget::{column, container, horizontal_space, image, mouse_area, row, svg, text};
+use iced::{Element, Length, Theme};
+
+use nova_core::services::HistoryEntry;
+use nova_core::{NamedAction, ResultIcon, SearchResult, ThemePalette};
 
 use crate::app::Message;
 
+/// Size of the icon slot in front of results
+const ICON_SLOT: f32 = 24.0;
+
+/// A loaded result icon
+#[derive(Debug, Clone)]
+pub enum IconImage {
+    Raster(image::Handle),
+    Vector(svg::Handle),
+}
+
+impl IconImage {
+    pub fn from_path(path: PathBuf) -> Self {
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "svg" || ext == "svgz")
+        {
+            IconImage::Vector(svg::Handle::from_path(path))
+        } else {
+            IconImage::Raster(image::Handle::from_path(path))
+        }
+    }
+}
+
+/// Render the icon of a result: the first of its icon names that was found,
+/// or its glyph. Rows without an icon keep an empty slot so names line up.
+fn result_icon<'a>(
+    result: &SearchResult,
+    icons: &HashMap<String, Option<IconImage>>,
+    palette: &ThemePalette,
+) -> Element<'a, Message> {
+    let icon: Element<'a, Message> = match result.icon() {
+        ResultIcon::Themed(names) => {
+            match names
+                .iter()
+                .find_map(|name| icons.get(name).and_then(Option::as_ref))
+            {
+                Some(IconImage::Raster(handle)) => image(handle.clone())
+                    .width(ICON_SLOT)
+                    .height(ICON_SLOT)
+                    .into(),
+                Some(IconImage::Vector(handle)) => svg(handle.clone())
+                    .width(ICON_SLOT)
+                    .height(ICON_SLOT)
+                    .into(),
+                None => horizontal_space().width(ICON_SLOT).into(),
+            }
+        }
+        ResultIcon::Glyph(glyph) => text(glyph)
+            .size(18)
+            .color(iced::Color::from_rgb8(
+                palette.subtext.0,
+                palette.subtext.1,
+                palette.subtext.2,
+            ))
+            .into(),
+    };
+
+    container(icon)
+        .width(ICON_SLOT)
+        .height(ICON_SLOT)
+        .center_x(Length::Fixed(ICON_SLOT))
+        .center_y(Length::Fixed(ICON_SLOT))
+        .into()
+}
+
 /// Render a single result row
 pub fn result_row<'a>(
     result: &SearchResult,
     is_selected: bool,
+    is_pinned: bool,
+    icons: &HashMap<String, Option<IconImage>>,
     palette: &ThemePalette,
     index: usize,
 ) -> Element<'a, Message> {
@@ -36,7 +107,84 @@ pub fn result_row<'a>(
 
     let accent = palette.accent;
 
-    let row_container = container(content)
+    let content = row![result_icon(result, icons, palette), content]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+    let content = if is_pinned {
+        let pin = text("\u{2605}")
+            .size(13)
+            .color(iced::Color::from_rgb8(accent.0, accent.1, accent.2));
+        content.push(horizontal_space()).push(pin).into()
+    } else {
+        content.into()
+    };
+
+    mouse_area(selectable(content, is_selected, accent))
+        .on_press(Message::SelectIndex(index))
+        .into()
+}
+
+/// Render a section header (result group or action panel title)
+pub fn section_header<'a>(title: &str, palette: &ThemePalette) -> Element<'a, Message> {
+    container(
+        text(title.to_string())
+            .size(12)
+            .color(iced::Color::from_rgb8(
+                palette.subtext.0,
+                palette.subtext.1,
+                palette.subtext.2,
+            )),
+    )
+    .padding([6, 12])
+    .into()
+}
+
+/// Render a single row of the action panel
+pub fn action_row<'a>(
+    action: &NamedAction,
+    is_selected: bool,
+    palette: &ThemePalette,
+    index: usize,
+) -> Element<'a, Message> {
+    let title = text(action.title.clone())
+        .size(15)
+        .color(iced::Color::from_rgb8(
+            palette.text.0,
+            palette.text.1,
+            palette.text.2,
+        ));
+
+    mouse_area(selectable(title.into(), is_selected, palette.accent))
+        .on_press(Message::RunAction(index))
+        .into()
+}
+
+/// Render a previous query in the history panel
+pub fn history_row<'a>(
+    entry: &HistoryEntry,
+    is_selected: bool,
+    palette: &ThemePalette,
+    index: usize,
+) -> Element<'a, Message> {
+    let query = text(entry.display()).size(15).color(iced::Color::from_rgb8(
+        palette.text.0,
+        palette.text.1,
+        palette.text.2,
+    ));
+
+    mouse_area(selectable(query.into(), is_selected, palette.accent))
+        .on_press(Message::RecallHistory(index))
+        .into()
+}
+
+/// Wrap row content, highlighting it when selected
+fn selectable(
+    content: Element<'_, Message>,
+    is_selected: bool,
+    accent: (u8, u8, u8),
+) -> container::Container<'_, Message> {
+    container(content)
         .width(iced::Length::Fill)
         .padding([6, 12])
         .style(move |_theme: &Theme| {
@@ -54,9 +202,5 @@ pub fn result_row<'a>(
             } else {
                 container::Style::default()
             }
-        });
-
-    mouse_area(row_container)
-        .on_press(Message::SelectIndex(index))
-        .into()
+        })
 }
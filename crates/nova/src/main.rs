@@ -35,7 +35,7 @@ fn main() {
     let config = Config::load();
 
     // Initialize platform
-    let platform = Platform::current();
+    let platform = Platform::current(&config);
 
     // Discover apps
     let apps = platform.apps.discover_apps();